@@ -0,0 +1,28 @@
+//! Sends one query to a running `rustsearch --serve` instance and prints the reply.
+//!
+//! ```text
+//! cargo run --example pipe_client -- "*.rs main" 20
+//! ```
+
+use std::io::{BufRead, BufReader, Write};
+
+const PIPE_NAME: &str = r"\\.\pipe\wizmini";
+
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let query = args.next().unwrap_or_default();
+    let limit: usize = args.next().and_then(|v| v.parse().ok()).unwrap_or(50);
+
+    let pipe = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(PIPE_NAME)?;
+
+    let request = serde_json::json!({ "query": query, "limit": limit }).to_string();
+    writeln!(&pipe, "{}", request)?;
+
+    let mut reply = String::new();
+    BufReader::new(&pipe).read_line(&mut reply)?;
+    print!("{}", reply);
+    Ok(())
+}
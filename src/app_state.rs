@@ -1,51 +1,102 @@
-use std::collections::{HashMap, HashSet};
-use std::sync::mpsc;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use global_hotkey::hotkey::{Code, HotKey, Modifiers};
 use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager};
+use regex::{Regex, RegexBuilder};
 use tray_icon::menu::{Menu, MenuId, MenuItem};
 use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
 
 type TrayInit = (Option<TrayIcon>, Option<MenuId>, Option<MenuId>);
 
 use crate::commands::{
-    apply_command_choice, command_menu_items, format_latest_window, is_exact_directive_token,
-    parse_scope_directive,
+    apply_command_choice, command_matches_typed, command_menu_items, drive_menu_items,
+    format_latest_window, help_menu_items, history_menu_items, is_exact_directive_token,
+    parse_scope_directive, pins_menu_items, CommandMenuItem,
 };
-use crate::indexing;
-use crate::platform::{is_process_elevated, open_path, request_self_elevation, reveal_path};
+use crate::export::default_export_path;
+use crate::feed::{ChangeFeed, ChangeKind, RecentEvent, RecentEvents};
+use crate::indexing::{self, IndexOptions};
+use crate::path_table::{folder_is_under, remove_item, split_path, upsert_item, PathIndex};
+use crate::platform::{
+    file_attributes, is_process_elevated, launch_at_startup_enabled, open_containing_folder,
+    open_path, open_terminal_at, open_with, recycle_paths, rename_path, request_self_elevation,
+    restore_recycled, reveal_path, set_launch_at_startup, validate_new_name,
+};
+use crate::preview::PreviewResult;
 use crate::search::{
-    contains_ascii_case_insensitive, file_name_from_path, query_has_incomplete_boolean_logic,
-    query_uses_boolean_logic, SearchQuery,
+    contains_case_insensitive, dedup_items, file_name_from_path, filename_index_answers,
+    filename_index_lookup, fold_if, highlight_ranges, index_filename,
+    query_has_incomplete_boolean_logic, query_uses_boolean_logic, select_first_sorted, sort_items,
+    unindex_swap_removed, unix_now, volume_of, DateFilter, LatestLinks, SearchQuery, SortMode,
 };
-use crate::search_worker::{SearchEvent, SearchWorkerMessage};
+use crate::search_worker::{MatchSource, SearchEvent, SearchRun, SearchWorkerMessage};
+use crate::settings::{parse_hotkey, Settings, FONT_SCALE_PERCENT_RANGE};
+use crate::sizes::ResolvedSizes;
 use crate::storage::{
-    load_persisted_scope, load_quick_help_dismissed, persist_quick_help_dismissed, persist_scope,
+    clear_all_caches, load_last_query, load_persisted_scope, load_pinned_paths,
+    load_quick_help_dismissed, load_search_history, persist_last_query, persist_pinned_paths,
+    persist_quick_help_dismissed, persist_scope, persist_search_history, push_search_history,
+    toggle_pinned_path,
 };
+use crate::theme::{Palette, Theme};
 use crate::{
-    debug_log, estimate_index_memory_bytes, IndexBackend, IndexEvent, RendererModeRequest,
-    SearchItem, SearchScope, WindowModeRequest, DEFAULT_LATEST_WINDOW_SECS, DEFAULT_RESULT_ROWS,
-    DELTA_REFRESH_COOLDOWN, FILENAME_INDEX_BUILD_BATCH, KEYBOARD_PAGE_JUMP,
-    MAX_INDEX_EVENTS_PER_TICK, MAX_RESULT_ROWS, MAX_SEARCH_EVENTS_PER_TICK, MIN_RESULT_ROWS,
-    QUERY_DEBOUNCE_DELAY, UNKNOWN_TS, VISIBLE_RESULTS_LIMIT,
+    debug_log, estimate_index_memory_bytes, format_count, IndexBackend, IndexEvent,
+    RendererModeRequest, SearchItem, SearchItemKind, SearchScope, WindowModeRequest,
+    DEFAULT_LATEST_WINDOW_SECS, DEFAULT_RESULT_ROWS, DELTA_REFRESH_COOLDOWN,
+    FILENAME_INDEX_BUILD_BATCH, FILE_PATH_MAX_CHARS, KEYBOARD_PAGE_JUMP, MAX_INDEX_EVENTS_PER_TICK,
+    MAX_RESULT_ROWS, MAX_SEARCH_EVENTS_PER_TICK, MIN_RESULT_ROWS, RECYCLE_UNDO_DEPTH, UNKNOWN_SIZE,
+    UNKNOWN_TS,
 };
 
+const NTFS_UNAVAILABLE_HINT: &str =
+    "NTFS indexing unavailable (run elevated and ensure USN journal is available)";
+
+/// An entry picked from a result row's context menu or the Tab palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RowAction {
+    Open,
+    Reveal,
+    OpenFolder,
+    CopyPath,
+    CopyName,
+    OpenWith,
+    Recycle,
+}
+
+pub(crate) const ROW_ACTIONS: [(&str, RowAction); 7] = [
+    ("Open", RowAction::Open),
+    ("Reveal in Explorer", RowAction::Reveal),
+    ("Open folder", RowAction::OpenFolder),
+    ("Copy path", RowAction::CopyPath),
+    ("Copy name", RowAction::CopyName),
+    ("Open with...", RowAction::OpenWith),
+    ("Recycle", RowAction::Recycle),
+];
+
 pub(crate) struct TickOutcome {
     pub(crate) visibility_changed: bool,
     pub(crate) focus_search: bool,
     pub(crate) should_quit: bool,
     pub(crate) window_mode_request: Option<WindowModeRequest>,
     pub(crate) renderer_mode_request: Option<RendererModeRequest>,
+    pub(crate) clipboard_text: Option<String>,
 }
 
 pub(crate) struct AppState {
     pub(crate) raw_query: String,
     pub(crate) query: String,
     pub(crate) all_items: Vec<SearchItem>,
+    pub(crate) path_index: PathIndex,
+    pub(crate) path_index_dirty: bool,
     pub(crate) items: Vec<SearchItem>,
     pub(crate) selected: usize,
+    /// Full paths of rows marked for batch actions, separate from the cursor.
+    pub(crate) selected_set: HashSet<Box<str>>,
     pub(crate) last_action: String,
     pub(crate) panel_visible: bool,
     pub(crate) _hotkey_manager: Option<GlobalHotKeyManager>,
@@ -54,14 +105,26 @@ pub(crate) struct AppState {
     pub(crate) menu_toggle_id: Option<MenuId>,
     pub(crate) menu_quit_id: Option<MenuId>,
     pub(crate) last_toggle_at: Option<Instant>,
+    /// Last key, pointer or scroll input, or when the panel was shown; drives
+    /// `auto_hide_secs`.
+    pub(crate) last_interaction: Instant,
     pub(crate) scope: SearchScope,
     pub(crate) command_selected: usize,
     pub(crate) index_rx: Option<mpsc::Receiver<IndexEvent>>,
     pub(crate) index_job_counter: u64,
     pub(crate) active_index_job: Option<u64>,
+    pub(crate) index_cancel: Arc<AtomicBool>,
+    pub(crate) volumes_loaded_job: Option<u64>,
+    /// Mount paths of the volumes `volumes_loaded_job` has delivered so far.
+    pub(crate) volumes_loaded: Vec<String>,
     pub(crate) indexing_in_progress: bool,
     pub(crate) indexing_progress: f32,
     pub(crate) indexing_phase: &'static str,
+    pub(crate) indexing_scanned: usize,
+    pub(crate) indexing_total_known: bool,
+    pub(crate) indexing_rate_ema: f32,
+    pub(crate) indexing_eta_secs: Option<f32>,
+    pub(crate) indexing_last_sample: Option<(Instant, usize)>,
     pub(crate) index_backend: IndexBackend,
     pub(crate) index_memory_bytes: usize,
     pub(crate) visual_progress_test_active: bool,
@@ -72,6 +135,9 @@ pub(crate) struct AppState {
     pub(crate) show_quick_help_overlay: bool,
     pub(crate) show_about_overlay: bool,
     pub(crate) quick_help_selected_action: usize,
+    /// Tab over a result: the action list for it, and the entry under the cursor.
+    pub(crate) action_palette_open: bool,
+    pub(crate) action_selected: usize,
     pub(crate) pending_query: Option<(String, Instant, u64)>,
     pub(crate) query_edit_counter: u64,
     pub(crate) search_tx: mpsc::Sender<SearchWorkerMessage>,
@@ -80,6 +146,8 @@ pub(crate) struct AppState {
     pub(crate) active_search_job: Option<u64>,
     pub(crate) active_search_query: Option<String>,
     pub(crate) active_search_cursor: usize,
+    /// When the running worker search started; drives the busy spinner.
+    pub(crate) active_search_started: Instant,
     pub(crate) filename_exact_index: HashMap<String, Vec<usize>>,
     pub(crate) filename_prefix_index: HashMap<String, Vec<usize>>,
     pub(crate) filename_index_dirty: bool,
@@ -88,10 +156,41 @@ pub(crate) struct AppState {
     pub(crate) needs_search_refresh: bool,
     pub(crate) next_search_refresh_at: Instant,
     pub(crate) latest_only_mode: bool,
+    /// The date chip above the results, if one is on.
+    pub(crate) date_filter: Option<DateFilter>,
+    /// `/feed`: the results area lists `change_feed` instead of matches.
+    pub(crate) feed_mode: bool,
+    pub(crate) change_feed: ChangeFeed,
     pub(crate) latest_window_secs: i64,
+    pub(crate) sort_mode: SortMode,
+    pub(crate) sort_descending: bool,
+    pub(crate) regex_pattern: Option<Regex>,
     pub(crate) tracking_enabled: bool,
     pub(crate) result_rows: usize,
-    pub(crate) recent_event_by_path: HashMap<Box<str>, i64>,
+    pub(crate) recent_event_by_path: RecentEvents,
+    pub(crate) size_cache: HashMap<Box<str>, u64>,
+    pub(crate) size_tx: mpsc::Sender<Vec<String>>,
+    pub(crate) size_rx: mpsc::Receiver<ResolvedSizes>,
+    /// Paths sent to the size worker that have not been answered yet.
+    pub(crate) sizes_pending: HashSet<Box<str>>,
+    /// Which hard link `/dedup` shows for files the live journal touched.
+    pub(crate) latest_links: LatestLinks,
+    pub(crate) search_history: VecDeque<String>,
+    pub(crate) history_cursor: Option<usize>,
+    pub(crate) pinned_paths: Vec<String>,
+    /// Pins found missing the last time they were surfaced; drawn greyed out.
+    pub(crate) missing_pins: HashSet<Box<str>>,
+    /// Full path of the result being renamed, and the name typed so far.
+    pub(crate) renaming: Option<(String, String)>,
+    /// Why volumes of the current index job could not be read, one per drive.
+    pub(crate) index_errors: Vec<(String, String)>,
+    /// Paths recycled this session, one entry per Ctrl+Delete, newest last.
+    pub(crate) recycled: Vec<Vec<String>>,
+    pub(crate) preview_tx: mpsc::Sender<String>,
+    pub(crate) preview_rx: mpsc::Receiver<PreviewResult>,
+    /// Path last sent to the preview worker; results for other paths are stale.
+    pub(crate) preview_requested: Option<String>,
+    pub(crate) preview: Option<PreviewResult>,
     pub(crate) changes_added_since_index: usize,
     pub(crate) changes_updated_since_index: usize,
     pub(crate) changes_deleted_since_index: usize,
@@ -100,15 +199,24 @@ pub(crate) struct AppState {
     pub(crate) should_exit: bool,
     pub(crate) pending_window_mode_request: Option<WindowModeRequest>,
     pub(crate) pending_renderer_mode_request: Option<RendererModeRequest>,
+    /// Text a command wants on the clipboard, which only the UI can reach.
+    pub(crate) pending_clipboard_text: Option<String>,
+    pub(crate) settings: Settings,
+    pub(crate) theme: Theme,
+    pub(crate) total_matches: usize,
 }
 
 impl AppState {
     pub(crate) fn new(start_visible: bool, startup_scope: Option<SearchScope>) -> Self {
         let (tray_icon, menu_toggle_id, menu_quit_id) = init_tray().unwrap_or((None, None, None));
-        let (hotkey_manager, hotkey, hotkey_retry_after) = match init_hotkey() {
+        let settings = Settings::load_or_default();
+        let (hotkey_manager, hotkey, hotkey_retry_after) = match init_hotkey(&settings.hotkey) {
             Ok((manager, hotkey)) => (manager, hotkey, None),
             Err(err) => {
-                debug_log(&format!("init_hotkey failed: {}", err));
+                debug_log(&format!(
+                    "init_hotkey failed for {}: {}",
+                    settings.hotkey, err
+                ));
                 (
                     None,
                     None,
@@ -116,10 +224,20 @@ impl AppState {
                 )
             }
         };
+        let theme = Theme::from_name(&settings.theme).unwrap_or_else(|| {
+            debug_log(&format!("unknown theme {}, using default", settings.theme));
+            Theme::Default
+        });
+        // Rewriting the entry keeps it pointing at the current exe after moves.
+        if let Err(err) = set_launch_at_startup(settings.launch_at_startup) {
+            debug_log(&format!("launch at startup sync failed: {}", err));
+        }
         let persisted_scope = load_persisted_scope();
         let is_elevated = is_process_elevated();
         let arg_scope_override = startup_scope;
         let (search_tx, search_rx) = crate::search_worker::spawn_search_worker();
+        let (preview_tx, preview_rx) = crate::preview::spawn_preview_worker();
+        let (size_tx, size_rx) = crate::sizes::spawn_size_worker();
         let startup_scope = if let Some(scope) = arg_scope_override.clone() {
             scope
         } else if is_elevated {
@@ -132,8 +250,11 @@ impl AppState {
             raw_query: String::new(),
             query: String::new(),
             all_items: Vec::new(),
+            path_index: HashMap::new(),
+            path_index_dirty: true,
             items: Vec::new(),
             selected: 0,
+            selected_set: HashSet::new(),
             last_action: "Indexing files...".to_string(),
             panel_visible: start_visible,
             _hotkey_manager: hotkey_manager,
@@ -142,14 +263,23 @@ impl AppState {
             menu_toggle_id,
             menu_quit_id,
             last_toggle_at: None,
+            last_interaction: Instant::now(),
             scope: startup_scope,
             command_selected: 0,
             index_rx: None,
             index_job_counter: 0,
             active_index_job: None,
+            index_cancel: Arc::new(AtomicBool::new(false)),
+            volumes_loaded_job: None,
+            volumes_loaded: Vec::new(),
             indexing_in_progress: false,
             indexing_progress: 0.0,
             indexing_phase: "index",
+            indexing_scanned: 0,
+            indexing_total_known: true,
+            indexing_rate_ema: 0.0,
+            indexing_eta_secs: None,
+            indexing_last_sample: None,
             index_backend: IndexBackend::Detecting,
             index_memory_bytes: 0,
             visual_progress_test_active: false,
@@ -160,6 +290,8 @@ impl AppState {
             show_quick_help_overlay: is_elevated && !load_quick_help_dismissed(),
             show_about_overlay: false,
             quick_help_selected_action: 0,
+            action_palette_open: false,
+            action_selected: 0,
             pending_query: None,
             query_edit_counter: 0,
             search_tx,
@@ -168,6 +300,7 @@ impl AppState {
             active_search_job: None,
             active_search_query: None,
             active_search_cursor: 0,
+            active_search_started: Instant::now(),
             filename_exact_index: HashMap::new(),
             filename_prefix_index: HashMap::new(),
             filename_index_dirty: true,
@@ -176,10 +309,32 @@ impl AppState {
             needs_search_refresh: false,
             next_search_refresh_at: Instant::now(),
             latest_only_mode: false,
+            date_filter: None,
+            feed_mode: false,
+            change_feed: ChangeFeed::default(),
             latest_window_secs: DEFAULT_LATEST_WINDOW_SECS,
+            sort_mode: SortMode::Relevance,
+            sort_descending: false,
+            regex_pattern: None,
             tracking_enabled: true,
             result_rows: DEFAULT_RESULT_ROWS,
             recent_event_by_path: HashMap::new(),
+            size_cache: HashMap::new(),
+            size_tx,
+            size_rx,
+            sizes_pending: HashSet::new(),
+            latest_links: HashMap::new(),
+            search_history: load_search_history(),
+            history_cursor: None,
+            pinned_paths: load_pinned_paths(),
+            missing_pins: HashSet::new(),
+            renaming: None,
+            index_errors: Vec::new(),
+            recycled: Vec::new(),
+            preview_tx,
+            preview_rx,
+            preview_requested: None,
+            preview: None,
             changes_added_since_index: 0,
             changes_updated_since_index: 0,
             changes_deleted_since_index: 0,
@@ -188,8 +343,23 @@ impl AppState {
             should_exit: false,
             pending_window_mode_request: None,
             pending_renderer_mode_request: None,
+            pending_clipboard_text: None,
+            settings,
+            theme,
+            total_matches: 0,
         };
 
+        if app.settings.restore_last_query {
+            let query = load_last_query();
+            if !query.is_empty() {
+                // Searched once the panel shows; indexing progress refreshes it after.
+                app.raw_query = query.clone();
+                app.pending_query = Some((query, Instant::now(), app.query_edit_counter));
+            }
+        } else {
+            persist_last_query("");
+        }
+
         app.begin_index(app.scope.clone());
         app
     }
@@ -204,18 +374,23 @@ impl AppState {
         if self.show_about_overlay {
             self.show_about_overlay = false;
         }
+        self.action_palette_open = false;
 
         self.raw_query = query;
+        self.history_cursor = None;
+        if self.raw_query.is_empty() {
+            self.selected_set.clear();
+        }
         self.query_edit_counter = self.query_edit_counter.wrapping_add(1);
         self.cancel_active_search();
         self.needs_search_refresh = false;
         self.pending_query = Some((
             self.raw_query.clone(),
-            Instant::now() + QUERY_DEBOUNCE_DELAY,
+            Instant::now() + self.settings.query_debounce(),
             self.query_edit_counter,
         ));
 
-        let suggestions = command_menu_items(&self.raw_query, self.tracking_enabled);
+        let suggestions = self.command_suggestions();
         if suggestions.is_empty() {
             self.command_selected = 0;
         } else {
@@ -224,6 +399,19 @@ impl AppState {
     }
 
     pub(crate) fn activate_selected(&mut self) {
+        self.activate(self.settings.hide_after_open);
+    }
+
+    /// Shift+Enter: opens like Enter but with `hide_after_open` flipped.
+    pub(crate) fn on_shift_enter(&mut self) {
+        self.activate(!self.settings.hide_after_open);
+    }
+
+    fn activate(&mut self, hide_after_open: bool) {
+        if self.action_palette_open {
+            self.run_palette_action();
+            return;
+        }
         if self.show_quick_help_overlay {
             if self.quick_help_selected_action == 0 {
                 self.show_quick_help_overlay = false;
@@ -234,28 +422,595 @@ impl AppState {
             return;
         }
 
-        let suggestions = command_menu_items(&self.raw_query, self.tracking_enabled);
+        if let Some(pins) = self.pins_listing() {
+            if let Some(choice) = pins.get(self.command_selected) {
+                let path = choice.command.to_string();
+                self.last_action = format!("Open: {}", path);
+                let opened = open_path(&path).is_ok();
+                self.clear_command_input();
+                if opened && hide_after_open {
+                    self.panel_visible = false;
+                }
+            }
+            return;
+        }
+
+        let drives = drive_menu_items(&self.raw_query, &indexing::available_drive_roots());
+        if let Some(choice) =
+            drives.and_then(|drives| drives.into_iter().nth(self.command_selected))
+        {
+            self.command_selected = 0;
+            self.apply_raw_query(choice.command.to_string(), true);
+            return;
+        }
+
+        let listing = history_menu_items(&self.raw_query, &self.search_history)
+            .or_else(|| help_menu_items(&self.raw_query, self.tracking_enabled));
+        if let Some(listing) = listing {
+            if let Some(choice) = listing.get(self.command_selected) {
+                self.command_selected = 0;
+                self.apply_raw_query(choice.command.to_string(), false);
+            }
+            return;
+        }
+
+        let suggestions = self.command_suggestions();
         let first_token = self.raw_query.split_whitespace().next().unwrap_or("");
+        let regex_query = self.regex_pattern.is_some();
 
-        if is_exact_directive_token(first_token, self.tracking_enabled) {
+        if !regex_query && is_exact_directive_token(first_token, self.tracking_enabled) {
             self.apply_raw_query(self.raw_query.clone(), true);
             return;
         }
 
         if !suggestions.is_empty() {
             if let Some(choice) = suggestions.get(self.command_selected) {
-                let new_raw = apply_command_choice(&self.raw_query, choice.command);
-                self.apply_raw_query(new_raw, true);
+                let new_raw = apply_command_choice(&self.raw_query, &choice.command);
+                // A typo guess only fills the box; a second Enter runs it.
+                if command_matches_typed(&self.raw_query, &choice.command) {
+                    self.apply_raw_query(new_raw, true);
+                } else {
+                    self.last_action = format!("Enter again to run {}", choice.command);
+                    self.apply_raw_query(new_raw, false);
+                }
             }
-        } else if self.raw_query.trim_start().starts_with('/') {
+        } else if !regex_query && self.raw_query.trim_start().starts_with('/') {
             self.last_action = format!("Unknown command: {}", first_token);
-        } else if let Some(item) = self.items.get(self.selected) {
-            self.last_action = format!("Open: {}", item.path);
-            let _ = open_path(item.path.as_ref());
+        } else {
+            self.open_targets(hide_after_open);
+        }
+    }
+
+    fn open_targets(&mut self, hide_after_open: bool) {
+        let targets = self.action_targets();
+        if targets.is_empty() {
+            return;
+        }
+        let mut failed = None;
+        for path in &targets {
+            if let Err(err) = self.open_target(path) {
+                failed = Some(err);
+            }
+        }
+        let opened = failed.is_none();
+        self.last_action = match failed {
+            Some(err) => format!("Open failed: {}", err),
+            None => describe_targets("Open", &targets),
+        };
+        self.record_search_history();
+        if opened && hide_after_open {
+            self.panel_visible = false;
+        }
+    }
+
+    fn pins_listing(&self) -> Option<Vec<CommandMenuItem>> {
+        pins_menu_items(&self.raw_query, &self.pinned_paths, |pin| {
+            self.missing_pins.contains(pin)
+        })
+    }
+
+    pub(crate) fn is_pinned(&self, item: &SearchItem) -> bool {
+        if self.pinned_paths.is_empty() {
+            return false;
+        }
+        let path = item.full_path();
+        self.pinned_paths
+            .iter()
+            .any(|pin| pin.eq_ignore_ascii_case(&path))
+    }
+
+    pub(crate) fn is_missing_pin(&self, item: &SearchItem) -> bool {
+        !self.missing_pins.is_empty() && self.missing_pins.contains(item.full_path().as_str())
+    }
+
+    /// The mark drawn beside a result: `*` for marked rows, `+` for pins.
+    pub(crate) fn row_mark(&self, item: &SearchItem) -> char {
+        if self.is_marked(item) {
+            '*'
+        } else if self.is_pinned(item) {
+            '+'
+        } else {
+            ' '
+        }
+    }
+
+    /// Ctrl+P and `/pin`: pins the selected result, or unpins it.
+    pub(crate) fn toggle_pin_selected(&mut self) {
+        let Some(item) = self.items.get(self.selected) else {
+            self.last_action = "Nothing selected to pin".to_string();
+            return;
+        };
+        let path = item.full_path();
+        let pinned = toggle_pinned_path(&mut self.pinned_paths, &path);
+        persist_pinned_paths(&self.pinned_paths);
+        if !pinned {
+            self.missing_pins.remove(path.as_str());
+        }
+        self.last_action = format!("{}: {}", if pinned { "Pinned" } else { "Unpinned" }, path);
+        self.schedule_search_from_current_query();
+    }
+
+    /// How `item` last changed, for labelling rows in the `/latest` view.
+    pub(crate) fn latest_change(&self, item: &SearchItem) -> Option<ChangeKind> {
+        if !self.latest_only_mode {
+            return None;
+        }
+        self.recent_event_by_path
+            .get(item.full_path().as_str())
+            .map(|event| event.kind)
+    }
+
+    /// Saves the settings and shows `message`, with the error when the
+    /// file could not be written.
+    fn save_settings_and_report(&mut self, message: String) {
+        self.last_action = match self.settings.save() {
+            Ok(()) => message,
+            Err(err) => format!("{} ({})", message, err),
+        };
+    }
+
+    /// Queues a renderer rebuild and saves the choice for the next start.
+    fn switch_renderer(&mut self, mode: RendererModeRequest, label: &str) {
+        self.pending_renderer_mode_request = Some(mode);
+        self.settings.renderer = mode;
+        let state = format!("Switching renderer to {}", label);
+        self.save_settings_and_report(state);
+    }
+
+    /// Ctrl+T and `/term`: opens a terminal in the selected result's folder.
+    pub(crate) fn open_terminal_selected(&mut self) {
+        let Some(item) = self.items.get(self.selected) else {
+            self.last_action = "Nothing selected to open a terminal for".to_string();
+            return;
+        };
+        let folder = item.folder.to_string();
+        self.last_action = match open_terminal_at(&folder) {
+            Ok(terminal) => format!("{}: {}", terminal, folder),
+            Err(err) => format!("Could not open a terminal in {}: {}", folder, err),
+        };
+    }
+
+    /// Puts pinned paths that match the query at the top of the results, even
+    /// when they are outside the indexed scope. Existence is checked here, so
+    /// deleted pins show up greyed out instead of disappearing.
+    fn surface_pins(&mut self) {
+        if self.pinned_paths.is_empty()
+            || self.regex_pattern.is_some()
+            || self.latest_only_mode
+            || self.date_filter.is_some()
+        {
+            return;
+        }
+
+        let query = self.query.trim().to_lowercase();
+        let parsed = SearchQuery::parse(&query)
+            .matching_full_paths(self.settings.search_full_paths)
+            .folding_diacritics(self.settings.ascii_fold);
+        let mut pins = Vec::new();
+        for path in &self.pinned_paths {
+            let metadata = std::fs::metadata(path).ok();
+            let modified_unix_secs = metadata
+                .as_ref()
+                .and_then(|metadata| metadata.modified().ok())
+                .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs() as i64)
+                .unwrap_or(UNKNOWN_TS);
+            let (size, kind) = match &metadata {
+                Some(metadata) if metadata.is_dir() => (UNKNOWN_SIZE, SearchItemKind::Folder),
+                Some(metadata) => (metadata.len(), SearchItemKind::File),
+                None => (UNKNOWN_SIZE, SearchItemKind::File),
+            };
+            if metadata.is_none() {
+                self.missing_pins.insert(path.as_str().into());
+            } else {
+                self.missing_pins.remove(path.as_str());
+            }
+
+            let item = SearchItem::new(path, modified_unix_secs, size, kind)
+                .with_attrs(metadata.as_ref().map(file_attributes).unwrap_or(0));
+            if query.is_empty() || parsed.matches_item(&item) {
+                pins.push(item);
+            }
+        }
+        if pins.is_empty() {
+            return;
+        }
+
+        let before = self.items.len();
+        self.items.retain(|item| {
+            let path = item.full_path();
+            !pins
+                .iter()
+                .any(|pin| pin.full_path().eq_ignore_ascii_case(&path))
+        });
+        self.total_matches += pins.len() - (before - self.items.len());
+        self.items.splice(0..0, pins);
+        self.items.truncate(self.settings.result_limit());
+        self.clamp_selected();
+    }
+
+    /// Opens a file in the editor configured for its extension, and anything
+    /// else with the shell default.
+    fn open_target(&self, path: &str) -> Result<(), String> {
+        match self.settings.editor_for(path) {
+            Some(editor) if std::path::Path::new(path).is_file() => open_with(path, editor),
+            _ => open_path(path),
+        }
+    }
+
+    fn open_targets_with(&mut self, program: Option<String>) {
+        let targets = self.action_targets();
+        let Some(program) = program else {
+            self.last_action = match targets
+                .first()
+                .and_then(|path| self.settings.editor_for(path).map(|editor| (path, editor)))
+            {
+                Some((path, editor)) => format!(
+                    "Usage: /openwith <program> ({} opens in {})",
+                    file_name_from_path(path),
+                    editor
+                ),
+                None => "Usage: /openwith <program>".to_string(),
+            };
+            return;
+        };
+        if targets.is_empty() {
+            self.last_action = "Nothing selected to open".to_string();
+            return;
+        }
+
+        let mut failed = None;
+        for path in &targets {
+            if let Err(err) = open_with(path, &program) {
+                failed = Some(err);
+            }
+        }
+        self.last_action = match failed {
+            Some(err) => format!("Open with {} failed: {}", program, err),
+            None => describe_targets(&format!("Open with {}", program), &targets),
+        };
+    }
+
+    pub(crate) fn is_marked(&self, item: &SearchItem) -> bool {
+        !self.selected_set.is_empty() && self.selected_set.contains(item.full_path().as_str())
+    }
+
+    pub(crate) fn toggle_mark_selected(&mut self) {
+        let Some(item) = self.items.get(self.selected) else {
+            return;
+        };
+        let path = item.full_path().into_boxed_str();
+        if !self.selected_set.remove(&path) {
+            self.selected_set.insert(path);
+        }
+        self.last_action = format!("{} marked", self.selected_set.len());
+    }
+
+    /// Shift+Arrow: marks the row under the cursor and the one it moves to.
+    pub(crate) fn on_extend_selection(&mut self, down: bool) {
+        let listing_results = !self.raw_query.trim_start().starts_with('/');
+        if listing_results {
+            self.mark_selected();
+        }
+        if down {
+            self.on_move_down();
+        } else {
+            self.on_move_up();
+        }
+        if listing_results {
+            self.mark_selected();
+            self.last_action = format!("{} marked", self.selected_set.len());
+        }
+    }
+
+    fn mark_selected(&mut self) {
+        if let Some(item) = self.items.get(self.selected) {
+            self.selected_set.insert(item.full_path().into_boxed_str());
+        }
+    }
+
+    /// Marked rows that are still listed, in list order, or else the row
+    /// under the cursor.
+    fn action_targets(&self) -> Vec<String> {
+        let marked: Vec<String> = self
+            .items
+            .iter()
+            .filter(|item| self.is_marked(item))
+            .map(SearchItem::full_path)
+            .collect();
+        if !marked.is_empty() {
+            return marked;
+        }
+        self.items
+            .get(self.selected)
+            .map(|item| vec![item.full_path()])
+            .unwrap_or_default()
+    }
+
+    /// Ctrl+C: the target paths, one per line, for the clipboard.
+    pub(crate) fn copy_targets_text(&mut self) -> Option<String> {
+        let targets = self.action_targets();
+        if targets.is_empty() {
+            return None;
+        }
+        self.last_action = describe_targets("Copied", &targets);
+        Some(targets.join("\r\n"))
+    }
+
+    /// Ctrl+Delete: sends the targets to the Recycle Bin and drops them from
+    /// the results without waiting for the journal.
+    pub(crate) fn recycle_targets(&mut self) {
+        let targets = self.action_targets();
+        if targets.is_empty() {
+            return;
+        }
+        if let Err(err) = recycle_paths(&targets) {
+            self.last_action = format!("Recycle failed: {}", err);
+            return;
+        }
+
+        let removed: HashSet<&str> = targets.iter().map(String::as_str).collect();
+        self.items
+            .retain(|item| !removed.contains(item.full_path().as_str()));
+        self.selected_set.clear();
+        self.clamp_selected();
+        self.last_action = format!(
+            "{} (Ctrl+Z restores)",
+            describe_targets("Recycled", &targets)
+        );
+        if self.recycled.len() == RECYCLE_UNDO_DEPTH {
+            self.recycled.remove(0);
+        }
+        self.recycled.push(targets.clone());
+        self.apply_index_delta(Vec::new(), targets, HashMap::new());
+        self.push_corpus_to_search_worker();
+    }
+
+    /// Ctrl+Z or `/undo`: moves the last recycled batch back out of the
+    /// Recycle Bin and into the index.
+    pub(crate) fn undo_recycle(&mut self) {
+        let Some(batch) = self.recycled.pop() else {
+            self.last_action = "Nothing recycled this session".to_string();
+            return;
+        };
+
+        let mut restored = Vec::new();
+        let mut failed = Vec::new();
+        for path in batch {
+            match restore_recycled(&path) {
+                Ok(()) => restored.push(path),
+                Err(err) => {
+                    debug_log(&format!("restore {} failed: {}", path, err));
+                    failed.push(path);
+                }
+            }
+        }
+
+        self.last_action = match (restored.is_empty(), failed.is_empty()) {
+            (_, true) => describe_targets("Restored", &restored),
+            (true, false) => format!(
+                "{}; restore it from the Recycle Bin in Explorer",
+                describe_targets("Could not restore", &failed)
+            ),
+            (false, false) => format!(
+                "{}; {} left in the Recycle Bin: {}",
+                describe_targets("Restored", &restored),
+                failed.len(),
+                failed.join(", ")
+            ),
+        };
+        let upserts: Vec<SearchItem> = restored
+            .iter()
+            .filter_map(|path| item_on_disk(path))
+            .collect();
+        if !upserts.is_empty() {
+            self.apply_index_delta(upserts, Vec::new(), HashMap::new());
+            self.push_corpus_to_search_worker();
+            self.schedule_search_from_current_query();
+        }
+    }
+
+    /// F2: edits the selected result's name in place of the search text.
+    pub(crate) fn begin_rename(&mut self) {
+        // Results about to be replaced could drop the row being renamed.
+        if self.renaming.is_some() || self.active_search_job.is_some() {
+            return;
+        }
+        let Some(item) = self.items.get(self.selected) else {
+            return;
+        };
+        self.last_action = format!("Rename {}: Enter applies, Esc cancels", item.name);
+        self.renaming = Some((item.full_path(), item.name.to_string()));
+    }
+
+    pub(crate) fn on_rename_changed(&mut self, name: String) {
+        if let Some((_, buffer)) = &mut self.renaming {
+            *buffer = name;
+        }
+    }
+
+    pub(crate) fn cancel_rename(&mut self) {
+        if self.renaming.take().is_some() {
+            self.last_action = "Rename cancelled".to_string();
+        }
+    }
+
+    /// Renames on disk, then patches the corpus right away instead of
+    /// waiting for the journal to report the change. Invalid names keep the
+    /// editor open so they can be corrected. The result is found again by
+    /// path, since the rows may have been replaced while the editor was open.
+    pub(crate) fn commit_rename(&mut self) {
+        let Some((path, new_name)) = self.renaming.take() else {
+            return;
+        };
+        let Some(index) = self.items.iter().position(|item| item.full_path() == path) else {
+            self.last_action = format!("Cannot rename {}: no longer in the results", path);
+            return;
+        };
+        let item = self.items[index].clone();
+        if new_name == *item.name {
+            self.last_action = "Rename cancelled: name unchanged".to_string();
+            return;
+        }
+
+        let old_path = item.full_path();
+        let new_path = format!("{}{}", item.folder, new_name);
+        let refused = validate_new_name(&new_name).err().or_else(|| {
+            let case_only = new_name.eq_ignore_ascii_case(&item.name);
+            (!case_only && std::path::Path::new(&new_path).exists())
+                .then(|| format!("{} already exists", new_name))
+        });
+        if let Some(reason) = refused {
+            self.last_action = format!("Cannot rename {}: {}", item.name, reason);
+            self.renaming = Some((path, new_name));
+            return;
+        }
+
+        if let Err(err) = rename_path(&old_path, &new_path) {
+            self.last_action = format!("Rename failed: {} ({})", old_path, err);
+            return;
+        }
+
+        let mut renamed = item;
+        renamed.name = new_name.into_boxed_str();
+        self.items[index] = renamed.clone();
+        let kinds = HashMap::from([(new_path.clone(), ChangeKind::Renamed)]);
+        self.apply_index_delta(vec![renamed], vec![old_path.clone()], kinds);
+        self.push_corpus_to_search_worker();
+        self.last_action = format!("Renamed {} to {}", old_path, new_path);
+    }
+
+    /// What the results pane says instead of rows when there are none, telling
+    /// a scan still running apart from a query that matched nothing.
+    pub(crate) fn empty_state_message(&self) -> Option<String> {
+        if !self.items.is_empty() {
+            return None;
+        }
+        if self.indexing_in_progress {
+            let so_far = self.indexing_scanned.max(self.all_items.len());
+            return Some(format!("Indexing... {} files so far", format_count(so_far)));
+        }
+        if self.all_items.is_empty() && !self.index_errors.is_empty() {
+            return Some(self.index_errors_label());
+        }
+        if self.all_items.is_empty() && self.index_backend == IndexBackend::Detecting {
+            return Some(NTFS_UNAVAILABLE_HINT.to_string());
+        }
+        // A search still on its way has not matched nothing yet.
+        if self.pending_query.is_some() || self.active_search_query.is_some() {
+            return None;
+        }
+        let query = self.query.trim();
+        if self.query_too_short(&query.to_lowercase()) {
+            return Some(format!(
+                "Keep typing... ({} characters start a search)",
+                self.settings.min_query_len()
+            ));
+        }
+        if !query.is_empty() {
+            return Some(format!("No matches for '{}'", query));
+        }
+        if self.all_items.is_empty() {
+            return Some(format!("Nothing indexed in {}", self.scope.label()));
+        }
+        None
+    }
+
+    /// Path column width in characters; smaller text leaves room for more.
+    pub(crate) fn path_max_chars(&self) -> usize {
+        (FILE_PATH_MAX_CHARS as f32 / self.settings.font_scale()).round() as usize
+    }
+
+    pub(crate) fn toggle_preview(&mut self) {
+        self.settings.show_preview = !self.settings.show_preview;
+        if !self.settings.show_preview {
+            self.preview_requested = None;
+            self.preview = None;
+        }
+        let state = if self.settings.show_preview {
+            "Preview pane shown"
+        } else {
+            "Preview pane hidden"
+        };
+        self.save_settings_and_report(state.to_string());
+    }
+
+    /// Asks the preview worker for the row under the cursor once it changes,
+    /// and keeps only the answer for that row.
+    fn update_preview(&mut self) {
+        if !self.settings.show_preview {
+            return;
+        }
+        let wanted = self.items.get(self.selected).map(SearchItem::full_path);
+        if wanted != self.preview_requested {
+            if let Some(path) = &wanted {
+                let _ = self.preview_tx.send(path.clone());
+            }
+            self.preview_requested = wanted;
+            self.preview = None;
+        }
+        while let Ok(result) = self.preview_rx.try_recv() {
+            if self.preview_requested.as_deref() == Some(result.path.as_str()) {
+                self.preview = Some(result);
+            }
         }
     }
 
+    pub(crate) fn command_suggestions(&self) -> Vec<CommandMenuItem> {
+        self.pins_listing()
+            .or_else(|| history_menu_items(&self.raw_query, &self.search_history))
+            .or_else(|| drive_menu_items(&self.raw_query, &indexing::available_drive_roots()))
+            .or_else(|| help_menu_items(&self.raw_query, self.tracking_enabled))
+            .unwrap_or_else(|| command_menu_items(&self.raw_query, self.tracking_enabled))
+    }
+
+    fn record_search_history(&mut self) {
+        if push_search_history(&mut self.search_history, &self.raw_query) {
+            persist_search_history(&self.search_history);
+        }
+    }
+
+    /// ArrowUp in an empty search box, or again right after a recall, steps
+    /// back through recent searches instead of moving the selection.
+    fn recall_older_search(&mut self) -> bool {
+        let next = match self.history_cursor {
+            Some(cursor) => cursor + 1,
+            None if self.raw_query.is_empty() => 0,
+            None => return false,
+        };
+        let Some(entry) = self.search_history.get(next).cloned() else {
+            return self.history_cursor.is_some();
+        };
+        self.on_query_changed(entry);
+        self.history_cursor = Some(next);
+        true
+    }
+
     pub(crate) fn on_escape(&mut self) {
+        if self.action_palette_open {
+            self.action_palette_open = false;
+            return;
+        }
         if self.show_privilege_overlay {
             self.show_privilege_overlay = false;
             return;
@@ -268,6 +1023,11 @@ impl AppState {
             self.show_quick_help_overlay = false;
             return;
         }
+        if self.feed_mode {
+            self.feed_mode = false;
+            self.last_action = "Live feed closed".to_string();
+            return;
+        }
         self.panel_visible = false;
     }
 
@@ -276,7 +1036,12 @@ impl AppState {
             self.quick_help_selected_action = 1;
             return;
         }
-        let suggestions = command_menu_items(&self.raw_query, self.tracking_enabled);
+        if self.action_palette_open {
+            self.action_selected = (self.action_selected + 1) % ROW_ACTIONS.len();
+            return;
+        }
+        self.history_cursor = None;
+        let suggestions = self.command_suggestions();
         let command_mode = !suggestions.is_empty();
         if command_mode {
             self.command_selected = (self.command_selected + 1).min(suggestions.len() - 1);
@@ -290,7 +1055,15 @@ impl AppState {
             self.quick_help_selected_action = 0;
             return;
         }
-        let suggestions = command_menu_items(&self.raw_query, self.tracking_enabled);
+        if self.action_palette_open {
+            self.action_selected =
+                (self.action_selected + ROW_ACTIONS.len() - 1) % ROW_ACTIONS.len();
+            return;
+        }
+        if self.recall_older_search() {
+            return;
+        }
+        let suggestions = self.command_suggestions();
         let command_mode = !suggestions.is_empty();
         if command_mode {
             self.command_selected = self.command_selected.saturating_sub(1);
@@ -300,7 +1073,7 @@ impl AppState {
     }
 
     pub(crate) fn on_page_down(&mut self) {
-        let suggestions = command_menu_items(&self.raw_query, self.tracking_enabled);
+        let suggestions = self.command_suggestions();
         let command_mode = !suggestions.is_empty();
         if command_mode {
             self.command_selected =
@@ -311,7 +1084,7 @@ impl AppState {
     }
 
     pub(crate) fn on_page_up(&mut self) {
-        let suggestions = command_menu_items(&self.raw_query, self.tracking_enabled);
+        let suggestions = self.command_suggestions();
         let command_mode = !suggestions.is_empty();
         if command_mode {
             self.command_selected = self.command_selected.saturating_sub(KEYBOARD_PAGE_JUMP);
@@ -321,7 +1094,7 @@ impl AppState {
     }
 
     pub(crate) fn on_home(&mut self) {
-        let suggestions = command_menu_items(&self.raw_query, self.tracking_enabled);
+        let suggestions = self.command_suggestions();
         let command_mode = !suggestions.is_empty();
         if command_mode {
             self.command_selected = 0;
@@ -331,7 +1104,7 @@ impl AppState {
     }
 
     pub(crate) fn on_end(&mut self) {
-        let suggestions = command_menu_items(&self.raw_query, self.tracking_enabled);
+        let suggestions = self.command_suggestions();
         let command_mode = !suggestions.is_empty();
         if command_mode {
             self.command_selected = suggestions.len() - 1;
@@ -340,42 +1113,247 @@ impl AppState {
         }
     }
 
-    pub(crate) fn on_alt_enter(&mut self) {
-        if self.show_quick_help_overlay {
+    pub(crate) fn on_result_click(&mut self, row: usize, double_click: bool) {
+        if self.show_quick_help_overlay
+            || self.show_about_overlay
+            || self.show_privilege_overlay
+            || row >= self.items.len()
+        {
             return;
         }
-        if let Some(item) = self.items.get(self.selected) {
-            self.last_action = format!("Reveal: {}", item.path);
-            let _ = reveal_path(item.path.as_ref());
+        self.selected = row;
+        if double_click {
+            self.activate_selected();
         }
     }
 
-    fn apply_raw_query(&mut self, raw_query: String, execute_directives: bool) {
-        self.pending_query = None;
-        self.needs_search_refresh = false;
-        self.raw_query = raw_query;
-        let command_invocation = self.raw_query.trim_start().starts_with('/');
-
-        let parsed = parse_scope_directive(&self.raw_query);
-        self.query = parsed.clean_query;
-
-        if !execute_directives {
-            let cmd = self.raw_query.trim_start();
-            if !cmd.starts_with("/latest") && !cmd.starts_with("/last") {
-                self.latest_only_mode = false;
-            }
-            self.schedule_search_from_current_query();
+    /// Tab: opens the action list for the selected result, or closes it.
+    pub(crate) fn toggle_action_palette(&mut self) {
+        if self.action_palette_open {
+            self.action_palette_open = false;
             return;
         }
-
-        if parsed.test_progress {
-            self.visual_progress_test_active = true;
-            self.indexing_in_progress = true;
-            self.indexing_progress = 0.0;
-            self.last_action = "Running visual progress test".to_string();
-            if command_invocation {
-                self.clear_command_input();
-            }
+        if self.items.is_empty()
+            || self.feed_mode
+            || self.renaming.is_some()
+            || self.raw_query.trim_start().starts_with('/')
+        {
+            return;
+        }
+        self.action_palette_open = true;
+        self.action_selected = 0;
+    }
+
+    fn run_palette_action(&mut self) {
+        self.action_palette_open = false;
+        let (_, action) = ROW_ACTIONS[self.action_selected.min(ROW_ACTIONS.len() - 1)];
+        if let Some(text) = self.on_row_action(self.selected, action) {
+            self.pending_clipboard_text = Some(text);
+        }
+    }
+
+    /// Runs a context menu entry on `row`, or on every marked row when `row`
+    /// is one of them. Returns text for the clipboard when copying.
+    pub(crate) fn on_row_action(&mut self, row: usize, action: RowAction) -> Option<String> {
+        if self.show_quick_help_overlay
+            || self.show_about_overlay
+            || self.show_privilege_overlay
+            || row >= self.items.len()
+        {
+            return None;
+        }
+        self.selected = row;
+        let marks = if self.is_marked(&self.items[row]) {
+            HashSet::new()
+        } else {
+            std::mem::take(&mut self.selected_set)
+        };
+
+        let mut copied = None;
+        match action {
+            RowAction::Open => self.open_targets(false),
+            RowAction::Reveal => self.on_alt_enter(),
+            RowAction::OpenFolder => self.on_ctrl_enter(),
+            RowAction::CopyPath => copied = self.copy_targets_text(),
+            RowAction::CopyName => {
+                let names: Vec<String> = self
+                    .action_targets()
+                    .iter()
+                    .map(|path| file_name_from_path(path).to_string())
+                    .collect();
+                self.last_action = describe_targets("Copied name", &names);
+                copied = Some(names.join("\r\n"));
+            }
+            RowAction::OpenWith => {
+                // Leaves the results alone; Enter runs it once a program is typed.
+                self.raw_query = "/openwith ".to_string();
+                self.command_selected = 0;
+                self.last_action = "Type a program and press Enter".to_string();
+            }
+            RowAction::Recycle => self.recycle_targets(),
+        }
+
+        self.selected_set.extend(marks);
+        copied
+    }
+
+    pub(crate) fn on_alt_enter(&mut self) {
+        if self.show_quick_help_overlay {
+            return;
+        }
+        let targets = self.action_targets();
+        if !targets.is_empty() {
+            for path in &targets {
+                let _ = reveal_path(path);
+            }
+            self.last_action = describe_targets("Reveal", &targets);
+            self.record_search_history();
+        }
+    }
+
+    pub(crate) fn on_ctrl_enter(&mut self) {
+        if self.show_quick_help_overlay {
+            return;
+        }
+        if let Some(item) = self.items.get(self.selected) {
+            self.last_action = format!("Open folder: {}", item.folder);
+            let _ = open_containing_folder(&item.full_path());
+            self.record_search_history();
+        }
+    }
+
+    fn rebind_hotkey(&mut self, combo: &str) {
+        let (mods, code) = match parse_hotkey(combo) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                self.last_action = err;
+                return;
+            }
+        };
+        let hotkey = HotKey::new(Some(mods), code);
+
+        if let Some(manager) = &self._hotkey_manager {
+            let previous = self._hotkey.take();
+            if let Some(previous) = previous {
+                let _ = manager.unregister(previous);
+            }
+            if let Err(err) = manager.register(hotkey) {
+                if let Some(previous) = previous {
+                    if manager.register(previous).is_ok() {
+                        self._hotkey = Some(previous);
+                    }
+                }
+                self.last_action = format!("Could not bind {}: {}", combo, err);
+                return;
+            }
+            self._hotkey = Some(hotkey);
+        }
+
+        // Without a manager the retry loop in tick picks up the new combo.
+        self.settings.hotkey = combo.to_string();
+        self.save_settings_and_report(format!("Hotkey set to {}", combo));
+    }
+
+    /// Byte ranges of `name` to highlight for the current query or regex.
+    pub(crate) fn name_highlights(&self, name: &str, terms: &[String]) -> Vec<Range<usize>> {
+        match &self.regex_pattern {
+            Some(regex) => regex
+                .find_iter(name)
+                .map(|found| found.range())
+                .filter(|range| !range.is_empty())
+                .collect(),
+            None => highlight_ranges(name, terms),
+        }
+    }
+
+    pub(crate) fn palette(&self) -> &'static Palette {
+        self.theme.palette()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.settings.theme = theme.name().to_string();
+        self.save_settings_and_report(format!("Theme: {}", theme.name()));
+    }
+
+    fn set_launch_at_startup(&mut self, enabled: bool) {
+        if let Err(err) = set_launch_at_startup(enabled) {
+            self.last_action = err;
+            return;
+        }
+
+        let state = if enabled { "on" } else { "off" };
+        self.settings.launch_at_startup = enabled;
+        self.save_settings_and_report(format!("Launch at startup: {}", state));
+    }
+
+    fn apply_raw_query(&mut self, raw_query: String, execute_directives: bool) {
+        self.pending_query = None;
+        self.needs_search_refresh = false;
+        self.raw_query = raw_query;
+        let command_invocation = self.raw_query.trim_start().starts_with('/');
+
+        let parsed = parse_scope_directive(&self.raw_query);
+        // Typing `/export` or `/openwith` must leave the results it acts on untouched.
+        if parsed.export_directive {
+            if execute_directives {
+                self.export_results(parsed.export_path);
+                self.raw_query.clear();
+                self.command_selected = 0;
+            }
+            return;
+        }
+        if parsed.goto_directive {
+            if execute_directives {
+                self.goto_row(parsed.goto_target);
+                self.raw_query.clear();
+                self.command_selected = 0;
+            }
+            return;
+        }
+        if parsed.copy_all {
+            if execute_directives {
+                self.copy_all_results();
+                self.raw_query.clear();
+                self.command_selected = 0;
+            }
+            return;
+        }
+        if parsed.openwith_directive {
+            if execute_directives {
+                self.open_targets_with(parsed.openwith_program);
+                self.raw_query.clear();
+                self.command_selected = 0;
+            }
+            return;
+        }
+        self.query = parsed.clean_query;
+
+        if parsed.regex_mode {
+            self.latest_only_mode = false;
+            self.apply_regex_query();
+            return;
+        }
+        self.regex_pattern = None;
+
+        if !execute_directives {
+            let cmd = self.raw_query.trim_start();
+            if !cmd.starts_with("/latest") && !cmd.starts_with("/last") {
+                self.latest_only_mode = false;
+            }
+            self.schedule_search_from_current_query();
+            return;
+        }
+
+        if parsed.test_progress {
+            self.visual_progress_test_active = true;
+            self.indexing_in_progress = true;
+            self.indexing_progress = 0.0;
+            self.reset_indexing_rate();
+            self.last_action = "Running visual progress test".to_string();
+            if command_invocation {
+                self.clear_command_input();
+            }
             return;
         }
 
@@ -411,6 +1389,23 @@ impl AppState {
             }
         }
 
+        if parsed.toggle_feed {
+            if !self.tracking_enabled {
+                self.last_action = "Tracking is off (use /track to enable)".to_string();
+            } else {
+                self.feed_mode = !self.feed_mode;
+                self.last_action = if self.feed_mode {
+                    "Live feed: changes show as they arrive (Esc or /feed closes)".to_string()
+                } else {
+                    "Live feed closed".to_string()
+                };
+            }
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
         if parsed.latest_only {
             if !self.tracking_enabled {
                 self.last_action = "Tracking is off (use /track to enable)".to_string();
@@ -440,6 +1435,8 @@ impl AppState {
             self.tracking_enabled = !self.tracking_enabled;
             self.latest_only_mode = false;
             self.recent_event_by_path.clear();
+            self.feed_mode = false;
+            self.change_feed.clear();
             if self.tracking_enabled {
                 self.last_action = "Tracking enabled".to_string();
             } else {
@@ -454,6 +1451,102 @@ impl AppState {
             return;
         }
 
+        if parsed.toggle_dirs {
+            self.settings.include_dirs = !self.settings.include_dirs;
+            let state = if self.settings.include_dirs {
+                "Folders shown in results"
+            } else {
+                "Folders hidden from results"
+            };
+            self.save_settings_and_report(state.to_string());
+            if command_invocation {
+                self.clear_command_input();
+            }
+            self.schedule_search_from_current_query();
+            return;
+        }
+
+        if parsed.toggle_full_path {
+            self.settings.search_full_paths = !self.settings.search_full_paths;
+            let state = if self.settings.search_full_paths {
+                "Matching full paths"
+            } else {
+                "Matching file names"
+            };
+            self.save_settings_and_report(state.to_string());
+            if command_invocation {
+                self.clear_command_input();
+            }
+            self.schedule_search_from_current_query();
+            return;
+        }
+
+        if parsed.toggle_diverse {
+            self.settings.diverse_results = !self.settings.diverse_results;
+            let state = match self.settings.per_folder_cap() {
+                Some(cap) => format!("At most {} results per folder", cap),
+                None => "Results per folder not capped".to_string(),
+            };
+            self.save_settings_and_report(state);
+            if command_invocation {
+                self.clear_command_input();
+            }
+            self.schedule_search_from_current_query();
+            return;
+        }
+
+        if parsed.toggle_dedup {
+            self.settings.dedup_results = !self.settings.dedup_results;
+            let state = if self.settings.dedup_results {
+                "Hard links to one file shown once"
+            } else {
+                "Every hard link shown"
+            };
+            self.save_settings_and_report(state.to_string());
+            if command_invocation {
+                self.clear_command_input();
+            }
+            self.schedule_search_from_current_query();
+            return;
+        }
+
+        if parsed.toggle_fold {
+            self.settings.ascii_fold = !self.settings.ascii_fold;
+            // Index keys are folded too, so the filename index is rebuilt.
+            self.filename_index_dirty = true;
+            self.filename_index_building = false;
+            self.filename_index_build_cursor = 0;
+            let state = if self.settings.ascii_fold {
+                "Ignoring diacritics (cafe finds café)"
+            } else {
+                "Matching diacritics exactly"
+            };
+            self.save_settings_and_report(state.to_string());
+            if command_invocation {
+                self.clear_command_input();
+            }
+            self.schedule_search_from_current_query();
+            return;
+        }
+
+        if parsed.toggle_density {
+            self.settings.density = self.settings.density.toggled();
+            let state = format!("Row density: {}", self.settings.density.label());
+            self.save_settings_and_report(state);
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.toggle_preview {
+            self.toggle_preview();
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
         if parsed.toggle_fullscreen {
             self.pending_window_mode_request = Some(WindowModeRequest::ToggleFullscreen);
             self.last_action = "Toggling fullscreen".to_string();
@@ -488,9 +1581,27 @@ impl AppState {
             return;
         }
 
+        if parsed.font_size_directive {
+            if let Some(percent) = parsed.font_size_percent {
+                let percent = self.settings.set_font_scale_percent(percent);
+                let message = format!("Font size {}%", percent);
+                self.save_settings_and_report(message);
+            } else {
+                self.last_action = format!(
+                    "Font size {:.0}% (usage: /fontsize {}-{})",
+                    self.settings.font_scale() * 100.0,
+                    FONT_SCALE_PERCENT_RANGE.start(),
+                    FONT_SCALE_PERCENT_RANGE.end()
+                );
+            }
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
         if parsed.switch_renderer_gpu {
-            self.pending_renderer_mode_request = Some(RendererModeRequest::Gpu);
-            self.last_action = "Switching renderer to GPU".to_string();
+            self.switch_renderer(RendererModeRequest::Gpu, "GPU");
             if command_invocation {
                 self.clear_command_input();
             }
@@ -498,61 +1609,544 @@ impl AppState {
         }
 
         if parsed.switch_renderer_soft {
-            self.pending_renderer_mode_request = Some(RendererModeRequest::Soft);
-            self.last_action = "Switching renderer to soft".to_string();
+            self.switch_renderer(RendererModeRequest::Soft, "soft");
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.show_about {
+            self.show_about_overlay = true;
+            self.last_action = "Showing about info".to_string();
             if command_invocation {
                 self.clear_command_input();
             }
             return;
         }
 
-        if parsed.show_about {
-            self.show_about_overlay = true;
-            self.last_action = "Showing about info".to_string();
-            if command_invocation {
-                self.clear_command_input();
+        if parsed.hotkey_directive {
+            match parsed.hotkey {
+                Some(combo) => self.rebind_hotkey(&combo),
+                None => {
+                    self.last_action = format!(
+                        "Usage: /hotkey COMBO (current {}, example /hotkey Ctrl+Space)",
+                        self.settings.hotkey
+                    );
+                }
+            }
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.theme_directive {
+            match parsed.theme.as_deref() {
+                Some(name) => match Theme::from_name(name) {
+                    Some(theme) => self.set_theme(theme),
+                    None => {
+                        let names: Vec<&str> = Theme::ALL.iter().map(|t| t.name()).collect();
+                        self.last_action =
+                            format!("Unknown theme: {} (try {})", name, names.join(", "));
+                    }
+                },
+                None => self.set_theme(self.theme.next()),
+            }
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.startup_directive {
+            match parsed.launch_at_startup {
+                Some(enabled) => self.set_launch_at_startup(enabled),
+                None => {
+                    let state = match launch_at_startup_enabled() {
+                        Ok(true) => "on".to_string(),
+                        Ok(false) => "off".to_string(),
+                        Err(err) => err,
+                    };
+                    self.last_action =
+                        format!("Launch at startup: {} (usage: /startup on|off)", state);
+                }
+            }
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.sort_directive {
+            self.sort_mode = parsed.sort_mode.unwrap_or_else(|| self.sort_mode.next());
+            self.sort_descending = parsed
+                .sort_descending
+                .unwrap_or_else(|| self.sort_mode.default_descending());
+            self.last_action = format!("Sorting by {}", self.sort_label());
+            if command_invocation {
+                self.clear_command_input();
+            }
+            self.schedule_search_from_current_query();
+            return;
+        }
+
+        if parsed.clear_cache {
+            self.latest_only_mode = false;
+            self.query.clear();
+            self.last_action = match clear_all_caches() {
+                Ok(removed) => format!(
+                    "Removed {} cache file{}, reindexing scope: {}",
+                    removed,
+                    if removed == 1 { "" } else { "s" },
+                    self.scope.label()
+                ),
+                Err(err) => format!("Cache clear failed ({}), reindexing anyway", err),
+            };
+            self.begin_index(self.scope.clone());
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.show_history {
+            if self.search_history.is_empty() {
+                self.last_action = "No recent searches".to_string();
+                if command_invocation {
+                    self.clear_command_input();
+                }
+            } else {
+                self.raw_query = "/history ".to_string();
+                self.command_selected = 0;
+                self.last_action = "Recent searches: Enter runs the selected one".to_string();
+            }
+            return;
+        }
+
+        if parsed.undo_recycle {
+            self.undo_recycle();
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.toggle_pin {
+            self.toggle_pin_selected();
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.open_terminal {
+            self.open_terminal_selected();
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.show_pins {
+            if self.pinned_paths.is_empty() {
+                self.last_action = "No pinned items (Ctrl+P pins the selected result)".to_string();
+                if command_invocation {
+                    self.clear_command_input();
+                }
+            } else {
+                self.raw_query = "/pins ".to_string();
+                self.command_selected = 0;
+                self.last_action = "Pinned items: Enter opens the selected one".to_string();
+            }
+            return;
+        }
+
+        if parsed.show_help {
+            self.raw_query = "/help ".to_string();
+            self.command_selected = 0;
+            self.last_action =
+                "All commands: type to filter, Enter runs the selected one".to_string();
+            return;
+        }
+
+        if parsed.gitignore_directive {
+            match parsed.respect_gitignore {
+                Some(enabled) if enabled == self.settings.respect_gitignore => {
+                    self.last_action = format!(
+                        "Gitignore filtering already {}",
+                        if enabled { "on" } else { "off" }
+                    );
+                }
+                Some(enabled) => {
+                    self.settings.respect_gitignore = enabled;
+                    let message = format!(
+                        "Gitignore filtering {}, reindexing scope: {}",
+                        if enabled { "on" } else { "off" },
+                        self.scope.label()
+                    );
+                    self.save_settings_and_report(message);
+                    self.latest_only_mode = false;
+                    self.query.clear();
+                    self.begin_index(self.scope.clone());
+                }
+                None => {
+                    self.last_action = format!(
+                        "Gitignore filtering: {} (usage: /gitignore on|off)",
+                        if self.settings.respect_gitignore {
+                            "on"
+                        } else {
+                            "off"
+                        }
+                    );
+                }
+            }
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.hidden_directive {
+            match parsed.index_hidden {
+                Some(enabled) if enabled == self.settings.index_hidden => {
+                    self.last_action = format!(
+                        "Hidden files already {}",
+                        if enabled { "indexed" } else { "left out" }
+                    );
+                }
+                Some(enabled) => {
+                    self.settings.index_hidden = enabled;
+                    let message = format!(
+                        "Hidden files {}, reindexing scope: {}",
+                        if enabled { "indexed" } else { "left out" },
+                        self.scope.label()
+                    );
+                    self.save_settings_and_report(message);
+                    self.latest_only_mode = false;
+                    self.query.clear();
+                    self.begin_index(self.scope.clone());
+                }
+                None => {
+                    self.last_action = format!(
+                        "Hidden files: {} (usage: /hidden on|off)",
+                        if self.settings.index_hidden {
+                            "on"
+                        } else {
+                            "off"
+                        }
+                    );
+                }
+            }
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.set_directive {
+            let mut changed = false;
+            match (parsed.set_key.as_deref(), parsed.set_value) {
+                (Some(key), Some(value)) => match self.settings.set_tunable(key, value) {
+                    Ok(stored) => {
+                        let message = format!("Set {} to {}", key, stored);
+                        self.save_settings_and_report(message);
+                        changed = true;
+                    }
+                    Err(err) => self.last_action = err,
+                },
+                _ => {
+                    self.last_action = format!(
+                        "limit {}, debounce {} ms, batch {}, minlen {}, perfolder {}, autohide {} s, memlimit {} MB, margin {} (usage: /set limit|debounce|batch|minlen|perfolder|autohide|memlimit|margin <n>)",
+                        self.settings.result_limit(),
+                        self.settings.query_debounce().as_millis(),
+                        self.settings.search_batch_size(),
+                        self.settings.min_query_len(),
+                        self.settings.per_folder_limit,
+                        self.settings
+                            .auto_hide_after()
+                            .map_or(0, |after| after.as_secs()),
+                        self.settings.memory_limit_mb,
+                        self.settings.scroll_margin_rows()
+                    );
+                }
+            }
+            if command_invocation {
+                self.clear_command_input();
+            }
+            if changed {
+                self.schedule_search_from_current_query();
+            }
+            return;
+        }
+
+        if parsed.exclude_directive {
+            let Some(pattern) = parsed.exclude_pattern else {
+                self.last_action = if self.settings.exclude_paths.is_empty() {
+                    "No excluded paths".to_string()
+                } else {
+                    format!("Excluded: {}", self.settings.exclude_paths.join(", "))
+                };
+                if command_invocation {
+                    self.clear_command_input();
+                }
+                return;
+            };
+
+            if self
+                .settings
+                .exclude_paths
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(&pattern))
+            {
+                self.last_action = format!("Already excluded: {}", pattern);
+            } else {
+                self.settings.exclude_paths.push(pattern.clone());
+                let message = format!(
+                    "Excluded {}, reindexing scope: {}",
+                    pattern,
+                    self.scope.label()
+                );
+                self.save_settings_and_report(message);
+                self.latest_only_mode = false;
+                self.query.clear();
+                self.begin_index(self.scope.clone());
+            }
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.reindex_current_scope {
+            self.latest_only_mode = false;
+            self.query.clear();
+            self.last_action = format!("Reindexing scope: {}", self.scope.label());
+            self.begin_index(self.scope.clone());
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        let cmd = self.raw_query.trim_start();
+        if !cmd.starts_with("/latest") && !cmd.starts_with("/last") {
+            self.latest_only_mode = false;
+        }
+
+        if parsed.drive_directive {
+            let roots = indexing::available_drive_roots();
+            match parsed.drive_letter {
+                Some(letter) if roots.contains(&format!("{}:\\", letter)) => {
+                    self.switch_scope(SearchScope::Drive(letter));
+                }
+                Some(letter) => {
+                    self.last_action = format!("Drive {}: not found", letter);
+                }
+                None => {
+                    self.last_action = format!(
+                        "Drives: {} (usage: /drive <letter>)",
+                        roots
+                            .iter()
+                            .map(|root| root.trim_end_matches('\\'))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    );
+                }
+            }
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if parsed.scope_directive {
+            if parsed.cycle_scope {
+                self.cycle_scope();
+            } else {
+                self.last_action = format!(
+                    "Scope: {} (usage: /scope next, or Ctrl+Tab)",
+                    self.scope.label()
+                );
+            }
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        if let Some(new_scope) = parsed.scope_override {
+            self.switch_scope(new_scope);
+            if command_invocation {
+                self.clear_command_input();
+            }
+            return;
+        }
+
+        self.schedule_search_from_current_query();
+    }
+
+    /// Turns the `filter` chip on, or off when it is the active one.
+    pub(crate) fn toggle_date_filter(&mut self, filter: DateFilter) {
+        self.set_date_filter((self.date_filter != Some(filter)).then_some(filter));
+    }
+
+    /// Moves to the next date chip in the Ctrl+D order.
+    pub(crate) fn cycle_date_filter(&mut self) {
+        self.set_date_filter(DateFilter::next(self.date_filter));
+    }
+
+    fn set_date_filter(&mut self, filter: Option<DateFilter>) {
+        self.date_filter = filter;
+        self.last_action = match filter {
+            Some(filter) => format!("Modified: {}", filter.label()),
+            None => "Date filter off".to_string(),
+        };
+        self.schedule_search_from_current_query();
+    }
+
+    /// Cutoff the search worker applies for the active date chip.
+    fn date_cutoff(&self) -> Option<i64> {
+        self.date_filter.map(|filter| filter.cutoff(unix_now()))
+    }
+
+    /// Moves to the next scope in the Ctrl+Tab order. A cached snapshot of
+    /// that scope is shown while it reindexes.
+    pub(crate) fn cycle_scope(&mut self) {
+        self.switch_scope(self.scope.next());
+    }
+
+    fn switch_scope(&mut self, new_scope: SearchScope) {
+        if self.indexing_in_progress && self.scope == new_scope {
+            self.last_action = format!("Already indexing scope: {}", self.scope.label());
+            return;
+        }
+
+        self.scope = new_scope;
+        self.all_items.clear();
+        self.path_index_dirty = true;
+        self.items.clear();
+        self.total_matches = 0;
+        self.selected = 0;
+        self.last_action = format!("Indexing scope: {}", self.scope.label());
+        self.begin_index(self.scope.clone());
+    }
+
+    fn apply_regex_query(&mut self) {
+        let pattern = self.query.trim();
+        if pattern.is_empty() {
+            self.regex_pattern = None;
+            self.schedule_search_from_current_query();
+            return;
+        }
+
+        match RegexBuilder::new(pattern).case_insensitive(true).build() {
+            Ok(regex) => {
+                self.regex_pattern = Some(regex);
+                self.schedule_search_from_current_query();
+            }
+            Err(err) => {
+                self.regex_pattern = None;
+                self.items.clear();
+                self.total_matches = 0;
+                self.cancel_active_search();
+                self.clamp_selected();
+                self.last_action = format!("invalid regex: {}", err);
             }
-            return;
         }
+    }
 
-        if parsed.reindex_current_scope {
-            self.latest_only_mode = false;
-            self.query.clear();
-            self.last_action = format!("Reindexing scope: {}", self.scope.label());
-            self.begin_index(self.scope.clone());
-            if command_invocation {
-                self.clear_command_input();
-            }
+    fn export_results(&mut self, path: Option<String>) {
+        let path = path
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(|| default_export_path(unix_now()));
+        self.last_action = format!("Exporting to {}...", path.display());
+        let source = self.match_source();
+        let _ = self
+            .search_tx
+            .send(SearchWorkerMessage::Export { source, path });
+    }
+
+    /// `/goto`: selects the first row whose path contains `target`, which
+    /// scrolls it into view, without touching the result set.
+    fn goto_row(&mut self, target: Option<String>) {
+        let Some(target) = target else {
+            self.last_action = "Usage: /goto <text in path>".to_string();
             return;
+        };
+        let needle = target.replace('/', "\\").to_lowercase();
+        match self
+            .items
+            .iter()
+            .position(|item| contains_case_insensitive(&item.full_path(), &needle))
+        {
+            Some(row) => {
+                self.selected = row;
+                self.last_action = format!("Row {}: {}", row + 1, self.items[row].full_path());
+            }
+            None => self.last_action = format!("No result path contains '{}'", target),
         }
+    }
 
-        let cmd = self.raw_query.trim_start();
-        if !cmd.starts_with("/latest") && !cmd.starts_with("/last") {
-            self.latest_only_mode = false;
-        }
+    /// `/copyall`: every match's path, one per line, capped at `copy_all_limit`.
+    fn copy_all_results(&mut self) {
+        self.last_action = "Collecting paths...".to_string();
+        let source = self.match_source();
+        let limit = self.settings.copy_all_limit.max(1);
+        let _ = self
+            .search_tx
+            .send(SearchWorkerMessage::CopyAll { source, limit });
+    }
 
-        if let Some(new_scope) = parsed.scope_override {
-            if self.indexing_in_progress && self.scope == new_scope {
-                self.last_action = format!("Already indexing scope: {}", self.scope.label());
-                if command_invocation {
-                    self.clear_command_input();
-                }
-                return;
-            }
+    /// Puts the paths a `/copyall` collected on the clipboard.
+    fn finish_copy_all(&mut self, paths: Vec<String>, total: usize) {
+        self.last_action = if total > paths.len() {
+            format!(
+                "Copied {} of {} paths (copy_all_limit)",
+                format_count(paths.len()),
+                format_count(total)
+            )
+        } else {
+            format!("Copied {} paths", format_count(paths.len()))
+        };
+        if !paths.is_empty() {
+            self.pending_clipboard_text = Some(paths.join("\r\n"));
+        }
+    }
 
-            self.scope = new_scope;
-            self.all_items.clear();
-            self.items.clear();
-            self.selected = 0;
-            self.last_action = format!("Indexing scope: {}", self.scope.label());
-            self.begin_index(self.scope.clone());
-            if command_invocation {
-                self.clear_command_input();
-            }
-            return;
+    /// Every match of the current search: the rows shown when they are all
+    /// of them, else the search to run again without the cap.
+    fn match_source(&self) -> MatchSource {
+        if self.total_matches <= self.items.len() {
+            MatchSource::Shown(self.items.clone())
+        } else {
+            MatchSource::Rerun(self.search_run(self.search_generation))
         }
+    }
 
-        self.schedule_search_from_current_query();
+    /// The worker search for the current query, settings and sort.
+    fn search_run(&self, generation: u64) -> SearchRun {
+        let regex = self.regex_pattern.clone();
+        SearchRun {
+            generation,
+            query: if regex.is_some() {
+                self.query.trim().to_string()
+            } else {
+                self.query.trim().to_lowercase()
+            },
+            latest_only_mode: self.latest_only_mode && regex.is_none(),
+            regex,
+            latest_window_secs: self.latest_window_secs,
+            include_dirs: self.settings.include_dirs,
+            full_path: self.settings.search_full_paths,
+            fold_diacritics: self.settings.ascii_fold,
+            result_limit: self.settings.result_limit(),
+            batch_size: self.settings.search_batch_size(),
+            sort_mode: self.sort_mode,
+            sort_descending: self.sort_descending,
+            per_folder: self.settings.per_folder_cap(),
+            modified_since: self.date_cutoff(),
+        }
     }
 
     fn clear_command_input(&mut self) {
@@ -563,6 +2157,7 @@ impl AppState {
     }
 
     fn begin_index(&mut self, scope: SearchScope) {
+        self.selected_set.clear();
         self.index_job_counter += 1;
         let job_id = self.index_job_counter;
         self.active_index_job = Some(job_id);
@@ -576,6 +2171,7 @@ impl AppState {
         self.indexing_in_progress = true;
         self.indexing_progress = 0.0;
         self.indexing_phase = "index";
+        self.reset_indexing_rate();
         self.indexing_is_refresh = false;
         self.index_backend = IndexBackend::Detecting;
         self.index_memory_bytes = 0;
@@ -589,13 +2185,19 @@ impl AppState {
         self.changes_added_since_index = 0;
         self.changes_updated_since_index = 0;
         self.changes_deleted_since_index = 0;
+        self.index_errors.clear();
 
         let (tx, rx) = mpsc::channel::<IndexEvent>();
         self.index_rx = Some(rx);
 
-        let allow_dirwalk_fallback = self.use_dirwalk_fallback;
+        // Stop the superseded job instead of letting it finish a full scan.
+        self.index_cancel.store(true, Ordering::Relaxed);
+        self.index_cancel = Arc::new(AtomicBool::new(false));
+        let cancel = self.index_cancel.clone();
+
+        let options = IndexOptions::from_settings(&self.settings, self.use_dirwalk_fallback);
         thread::spawn(move || {
-            indexing::run_index_job(scope, job_id, tx, allow_dirwalk_fallback);
+            indexing::run_index_job(scope, job_id, tx, cancel, options);
         });
     }
 
@@ -606,10 +2208,12 @@ impl AppState {
             should_quit: false,
             window_mode_request: None,
             renderer_mode_request: None,
+            clipboard_text: None,
         };
 
         out.window_mode_request = self.pending_window_mode_request.take();
         out.renderer_mode_request = self.pending_renderer_mode_request.take();
+        out.clipboard_text = self.pending_clipboard_text.take();
 
         if self.visual_progress_test_active {
             self.indexing_in_progress = true;
@@ -627,6 +2231,7 @@ impl AppState {
                 if Instant::now() >= due_at && edit_id == self.query_edit_counter {
                     self.pending_query = None;
                     self.apply_raw_query(pending_query, false);
+                    self.remember_last_query();
                 }
             }
 
@@ -636,6 +2241,7 @@ impl AppState {
 
             if self.needs_search_refresh
                 && self.pending_query.is_none()
+                && self.renaming.is_none()
                 && Instant::now() >= self.next_search_refresh_at
             {
                 self.needs_search_refresh = false;
@@ -646,6 +2252,16 @@ impl AppState {
             if self.pending_query.is_none() {
                 self.process_filename_index_build_step();
             }
+
+            self.apply_resolved_sizes();
+            self.resolve_visible_sizes();
+            self.update_preview();
+
+            if self.auto_hide_due() {
+                self.panel_visible = false;
+                self.last_action = "Hidden after being idle".to_string();
+                out.visibility_changed = true;
+            }
         }
 
         for _ in 0..MAX_SEARCH_EVENTS_PER_TICK {
@@ -663,15 +2279,42 @@ impl AppState {
                         self.active_search_cursor = scanned.min(total);
                     }
                 }
-                SearchEvent::Done { generation, items } => {
+                SearchEvent::Done {
+                    generation,
+                    items,
+                    total_matches,
+                    unsized_matches,
+                } => {
                     if self.active_search_job == Some(generation) {
                         self.items = items;
+                        self.total_matches = total_matches;
+                        if unsized_matches > 0 {
+                            self.last_action = format!(
+                                "Size order is partial: {} matching files were not sized",
+                                format_count(unsized_matches)
+                            );
+                        }
                         self.active_search_job = None;
                         self.active_search_query = None;
                         self.active_search_cursor = 0;
+                        self.collapse_duplicates();
+                        self.surface_pins();
                         self.clamp_selected();
                     }
                 }
+                SearchEvent::Sizes(sizes) => {
+                    self.store_sizes(&sizes.into_iter().collect());
+                }
+                SearchEvent::Exported { path, result } => {
+                    self.last_action = match result {
+                        Ok(count) => format!("Exported {} results to {}", count, path.display()),
+                        Err(err) => format!("Export to {} failed: {}", path.display(), err),
+                    };
+                    debug_log(&self.last_action);
+                }
+                SearchEvent::CopiedPaths { paths, total } => {
+                    self.finish_copy_all(paths, total);
+                }
             }
         }
 
@@ -689,6 +2332,8 @@ impl AppState {
                     IndexEvent::SnapshotLoaded { job_id, items } => {
                         if self.active_index_job == Some(job_id) {
                             self.all_items = items;
+                            self.latest_links.clear();
+                            self.path_index_dirty = true;
                             self.indexing_is_refresh = true;
                             self.filename_index_dirty = true;
                             self.filename_index_building = false;
@@ -707,16 +2352,66 @@ impl AppState {
                         job_id,
                         current,
                         total,
+                        scanned,
+                        total_is_estimate,
                         phase,
                     } => {
                         if self.active_index_job == Some(job_id) {
                             self.indexing_in_progress = true;
+                            if self.indexing_phase != phase {
+                                self.reset_indexing_rate();
+                            }
                             self.indexing_phase = phase;
                             self.indexing_progress = if total == 0 {
                                 0.0
                             } else {
                                 (current as f32 / total as f32).clamp(0.0, 1.0)
                             };
+                            self.indexing_total_known = total > 0 && !total_is_estimate;
+                            self.record_indexing_sample(scanned, total.saturating_sub(current));
+                        }
+                    }
+                    IndexEvent::VolumeLoaded {
+                        job_id,
+                        volume,
+                        items,
+                    } => {
+                        if self.active_index_job == Some(job_id) {
+                            if self.volumes_loaded_job != Some(job_id) {
+                                self.volumes_loaded_job = Some(job_id);
+                                self.volumes_loaded.clear();
+                            }
+                            // Only this volume's old rows are replaced, so other drives
+                            // stay searchable until they load. Volumes mounted in a
+                            // folder below it that already loaded keep theirs.
+                            let nested: Vec<&String> = self
+                                .volumes_loaded
+                                .iter()
+                                .filter(|other| {
+                                    other.len() > volume.len() && folder_is_under(other, &volume)
+                                })
+                                .collect();
+                            self.all_items.retain(|item| {
+                                !folder_is_under(&item.folder, &volume)
+                                    || nested
+                                        .iter()
+                                        .any(|other| folder_is_under(&item.folder, other))
+                            });
+                            self.volumes_loaded.push(volume.clone());
+                            self.all_items.extend(items);
+                            self.path_index_dirty = true;
+                            self.filename_index_dirty = true;
+                            self.filename_index_building = false;
+                            self.filename_index_build_cursor = 0;
+                            self.recompute_index_memory_bytes();
+                            self.push_corpus_to_search_worker();
+                            self.schedule_search_from_current_query();
+                            self.last_action = format!(
+                                "Indexed {}: {} items so far [{}]",
+                                volume,
+                                self.all_items.len(),
+                                self.scope.label()
+                            );
                         }
                     }
                     IndexEvent::Done {
@@ -730,6 +2425,8 @@ impl AppState {
                             self.indexing_phase = "done";
                             self.index_backend = backend;
                             self.all_items = items;
+                            self.latest_links.clear();
+                            self.path_index_dirty = true;
                             self.filename_index_dirty = true;
                             self.filename_index_building = false;
                             self.filename_index_build_cursor = 0;
@@ -739,27 +2436,72 @@ impl AppState {
                             self.changes_updated_since_index = 0;
                             self.changes_deleted_since_index = 0;
                             self.push_corpus_to_search_worker();
-                            if self.all_items.is_empty() && backend == IndexBackend::Detecting {
-                                self.last_action = "NTFS indexing unavailable (run elevated and ensure USN journal is available)".to_string();
+                            if self.all_items.is_empty() && !self.index_errors.is_empty() {
+                                self.last_action = self.index_errors_label();
+                            } else if self.all_items.is_empty()
+                                && backend == IndexBackend::Detecting
+                            {
+                                self.last_action = NTFS_UNAVAILABLE_HINT.to_string();
                             } else {
+                                let gitignore = if self.settings.respect_gitignore
+                                    && matches!(
+                                        backend,
+                                        IndexBackend::WalkDir | IndexBackend::Mixed
+                                    ) {
+                                    " (gitignore)"
+                                } else {
+                                    ""
+                                };
                                 self.last_action = format!(
-                                    "Indexed {} files [{}]",
+                                    "Indexed {} files{} [{}]",
                                     self.all_items.len(),
+                                    gitignore,
                                     self.scope.label()
                                 );
+                                if !self.index_errors.is_empty() {
+                                    self.last_action = format!(
+                                        "{}; {}",
+                                        self.last_action,
+                                        self.index_errors_label()
+                                    );
+                                }
                             }
                             self.schedule_search_from_current_query();
                             out.focus_search = true;
                         }
                     }
+                    IndexEvent::Error {
+                        job_id,
+                        drive,
+                        message,
+                    } => {
+                        // The live pass and the fallback pass can both hit the same drive.
+                        if self.active_index_job == Some(job_id)
+                            && !self.index_errors.iter().any(|(known, _)| *known == drive)
+                        {
+                            self.last_action = message.clone();
+                            self.index_errors.push((drive, message));
+                        }
+                    }
+                    IndexEvent::LiveUpdatesLost { job_id, volume } => {
+                        if self.active_index_job == Some(job_id) {
+                            self.begin_index(self.scope.clone());
+                            self.last_action = format!(
+                                "Live updates lost on {} - reindexing [{}]",
+                                volume,
+                                self.scope.label()
+                            );
+                        }
+                    }
                     IndexEvent::Delta {
                         job_id,
                         upserts,
                         deleted_paths,
+                        kinds,
                     } => {
                         if self.active_index_job == Some(job_id) {
                             let (added, updated, deleted) =
-                                self.apply_index_delta(upserts, deleted_paths);
+                                self.apply_index_delta(upserts, deleted_paths, kinds);
                             self.changes_added_since_index += added;
                             self.changes_updated_since_index += updated;
                             self.changes_deleted_since_index += deleted;
@@ -784,15 +2526,19 @@ impl AppState {
                 .hotkey_retry_after
                 .is_none_or(|due| Instant::now() >= due);
             if should_retry {
-                match init_hotkey() {
+                match init_hotkey(&self.settings.hotkey) {
                     Ok((manager, hotkey)) => {
                         self._hotkey_manager = manager;
                         self._hotkey = hotkey;
                         self.hotkey_retry_after = None;
-                        self.last_action = "Global hotkey ready".to_string();
+                        self.last_action =
+                            format!("Global hotkey ready ({})", self.settings.hotkey);
                     }
                     Err(err) => {
-                        debug_log(&format!("hotkey retry failed: {}", err));
+                        debug_log(&format!(
+                            "hotkey retry failed for {}: {}",
+                            self.settings.hotkey, err
+                        ));
                         self.hotkey_retry_after =
                             Some(Instant::now() + Duration::from_millis(1200));
                     }
@@ -831,6 +2577,7 @@ impl AppState {
             self.last_toggle_at = Some(Instant::now());
             self.panel_visible = !self.panel_visible;
             if self.panel_visible {
+                self.note_interaction();
                 if self.needs_search_refresh || self.items.is_empty() {
                     self.schedule_search_from_current_query();
                 }
@@ -846,8 +2593,44 @@ impl AppState {
         out
     }
 
+    pub(crate) fn note_interaction(&mut self) {
+        self.last_interaction = Instant::now();
+    }
+
+    /// Whether the visible panel has gone unused for `auto_hide_secs`. Running
+    /// searches and indexing hold the timer so their results stay on screen.
+    fn auto_hide_due(&mut self) -> bool {
+        let Some(after) = self.settings.auto_hide_after() else {
+            return false;
+        };
+        if self.indexing_in_progress
+            || self.active_search_job.is_some()
+            || self.pending_query.is_some()
+        {
+            self.note_interaction();
+            return false;
+        }
+        self.last_interaction.elapsed() >= after
+    }
+
     fn recompute_index_memory_bytes(&mut self) {
+        let was_over = self.index_over_memory_limit();
         self.index_memory_bytes = estimate_index_memory_bytes(&self.all_items);
+        if !was_over && self.index_over_memory_limit() {
+            debug_log(&format!(
+                "index memory {} bytes passed the {} MB limit items={}",
+                self.index_memory_bytes,
+                self.settings.memory_limit_mb,
+                self.all_items.len()
+            ));
+        }
+    }
+
+    /// True once the estimated index size passes the `/memlimit` threshold.
+    pub(crate) fn index_over_memory_limit(&self) -> bool {
+        self.settings
+            .memory_limit_bytes()
+            .is_some_and(|limit| self.index_memory_bytes > limit)
     }
 
     fn push_corpus_to_search_worker(&self) {
@@ -864,41 +2647,101 @@ impl AppState {
         let _ = self.search_tx.send(SearchWorkerMessage::Cancel);
     }
 
+    /// Saves the search box for the next start. Slash commands are never
+    /// saved, and directives typed after a search are left out.
+    fn remember_last_query(&self) {
+        if !self.settings.restore_last_query || self.raw_query.trim_start().starts_with('/') {
+            return;
+        }
+        persist_last_query(self.query.trim());
+    }
+
     fn schedule_search_from_current_query(&mut self) {
         if !self.panel_visible {
             self.cancel_active_search();
             return;
         }
 
-        let q = self.query.trim().to_ascii_lowercase();
+        if self.regex_pattern.is_some() {
+            self.search_generation = self.search_generation.wrapping_add(1);
+            let generation = self.search_generation;
+            self.active_search_job = Some(generation);
+            self.active_search_query = Some(self.query.trim().to_string());
+            self.active_search_cursor = 0;
+            self.active_search_started = Instant::now();
+            let _ = self
+                .search_tx
+                .send(SearchWorkerMessage::Run(self.search_run(generation)));
+            return;
+        }
+
+        let q = self.query.trim().to_lowercase();
 
         if query_has_incomplete_boolean_logic(&q) && !self.latest_only_mode {
             self.items.clear();
+            self.total_matches = 0;
+            self.cancel_active_search();
+            self.clamp_selected();
+            return;
+        }
+
+        if self.query_too_short(&q) {
+            // Nearly every name contains one letter; only exact names are worth listing.
+            let (results, total_matches) = self.exact_filename_matches(&q);
+            self.items = results;
+            self.total_matches = total_matches;
+            self.apply_sort_mode();
             self.cancel_active_search();
             self.clamp_selected();
             return;
         }
 
-        if q.is_empty() && !self.latest_only_mode {
-            self.items = self
+        if q.is_empty() && !self.latest_only_mode && self.date_filter.is_none() {
+            let include_dirs = self.settings.include_dirs;
+            let mut visible: Vec<&SearchItem> = self
                 .all_items
                 .iter()
-                .take(VISIBLE_RESULTS_LIMIT)
-                .cloned()
+                .filter(|item| include_dirs || item.kind != SearchItemKind::Folder)
                 .collect();
+            self.total_matches = visible.len();
+            select_first_sorted(
+                &mut visible,
+                self.settings.result_limit(),
+                self.sort_mode,
+                self.sort_descending,
+                |item| *item,
+            );
+            self.items = visible.into_iter().cloned().collect();
+            self.apply_sort_mode();
+            self.collapse_duplicates();
+            self.surface_pins();
             self.cancel_active_search();
             self.clamp_selected();
         } else {
-            if !self.latest_only_mode {
-                if let Some(results) = self.try_fast_boolean_search(&q) {
+            // The filename index only knows names, dates or folder caps, so
+            // full-path matching, date chips and `/diverse` scan.
+            if !self.latest_only_mode
+                && !self.settings.search_full_paths
+                && self.settings.per_folder_cap().is_none()
+                && self.date_filter.is_none()
+            {
+                if let Some((results, total_matches)) = self.try_fast_boolean_search(&q) {
                     self.items = results;
+                    self.total_matches = total_matches;
+                    self.apply_sort_mode();
+                    self.collapse_duplicates();
+                    self.surface_pins();
                     self.cancel_active_search();
                     self.clamp_selected();
                     return;
                 }
 
-                if let Some(results) = self.try_fast_filename_search(&q) {
+                if let Some((results, total_matches)) = self.try_fast_filename_search(&q) {
                     self.items = results;
+                    self.total_matches = total_matches;
+                    self.apply_sort_mode();
+                    self.collapse_duplicates();
+                    self.surface_pins();
                     self.cancel_active_search();
                     self.clamp_selected();
                     return;
@@ -910,12 +2753,10 @@ impl AppState {
             self.active_search_job = Some(generation);
             self.active_search_query = Some(q);
             self.active_search_cursor = 0;
-            let _ = self.search_tx.send(SearchWorkerMessage::Run {
-                generation,
-                query: self.query.trim().to_ascii_lowercase(),
-                latest_only_mode: self.latest_only_mode,
-                latest_window_secs: self.latest_window_secs,
-            });
+            self.active_search_started = Instant::now();
+            let _ = self
+                .search_tx
+                .send(SearchWorkerMessage::Run(self.search_run(generation)));
         }
     }
 
@@ -934,21 +2775,13 @@ impl AppState {
         let end = (self.filename_index_build_cursor + FILENAME_INDEX_BUILD_BATCH)
             .min(self.all_items.len());
         for index in self.filename_index_build_cursor..end {
-            let item = &self.all_items[index];
-            let name_lower = file_name_from_path(item.path.as_ref()).to_ascii_lowercase();
-            self.filename_exact_index
-                .entry(name_lower.clone())
-                .or_default()
-                .push(index);
-
-            let mut prefix = String::new();
-            for ch in name_lower.chars().take(3) {
-                prefix.push(ch);
-                self.filename_prefix_index
-                    .entry(prefix.clone())
-                    .or_default()
-                    .push(index);
-            }
+            index_filename(
+                &mut self.filename_exact_index,
+                &mut self.filename_prefix_index,
+                &self.all_items[index].name,
+                index,
+                self.settings.ascii_fold,
+            );
         }
 
         self.filename_index_build_cursor = end;
@@ -959,15 +2792,8 @@ impl AppState {
         }
     }
 
-    fn try_fast_filename_search(&mut self, query_lower: &str) -> Option<Vec<SearchItem>> {
-        if query_lower.is_empty()
-            || query_lower.contains('*')
-            || query_lower.contains('?')
-            || query_lower.contains('\\')
-            || query_lower.contains('/')
-            || query_lower.contains(':')
-            || query_uses_boolean_logic(query_lower)
-        {
+    fn try_fast_filename_search(&mut self, query_lower: &str) -> Option<(Vec<SearchItem>, usize)> {
+        if !filename_index_answers(query_lower, self.sort_mode) {
             return None;
         }
 
@@ -975,50 +2801,83 @@ impl AppState {
             return None;
         }
 
-        let mut out = Vec::new();
-        let mut seen: HashSet<usize> = HashSet::new();
-
-        if let Some(exact) = self.filename_exact_index.get(query_lower) {
-            for &idx in exact {
-                if seen.insert(idx) {
-                    out.push(self.all_items[idx].clone());
-                    if out.len() >= VISIBLE_RESULTS_LIMIT {
-                        return Some(out);
-                    }
-                }
-            }
-        }
+        // The index is keyed by folded names while folding is on.
+        let query = fold_if(query_lower, self.settings.ascii_fold);
+        let query_lower = query.as_ref();
+        let limit = self.settings.result_limit();
+        let mut matches = filename_index_lookup(
+            &self.filename_exact_index,
+            &self.filename_prefix_index,
+            &self.all_items,
+            query_lower,
+            self.settings.ascii_fold,
+        );
+        matches.retain(|&idx| self.include_item(idx));
+
+        let total_matches = matches.len();
+        select_first_sorted(
+            &mut matches,
+            limit,
+            self.sort_mode,
+            self.sort_descending,
+            |&idx| &self.all_items[idx],
+        );
+        let out: Vec<SearchItem> = matches
+            .iter()
+            .map(|&idx| self.all_items[idx].clone())
+            .collect();
 
-        let mut prefix_key = String::new();
-        for ch in query_lower.chars().take(3) {
-            prefix_key.push(ch);
+        if out.is_empty() {
+            None
+        } else {
+            Some((out, total_matches))
         }
+    }
 
-        if let Some(candidates) = self.filename_prefix_index.get(&prefix_key) {
-            for &idx in candidates {
-                if seen.contains(&idx) {
-                    continue;
-                }
+    /// Queries below `min_query_len` do not scan; `/latest` and wildcards still do.
+    fn query_too_short(&self, query_lower: &str) -> bool {
+        !self.latest_only_mode
+            && self.date_filter.is_none()
+            && self.regex_pattern.is_none()
+            && !query_lower.is_empty()
+            && !query_lower.contains(['*', '?'])
+            && query_lower.chars().count() < self.settings.min_query_len()
+    }
 
-                let name = file_name_from_path(self.all_items[idx].path.as_ref());
-                if contains_ascii_case_insensitive(name, query_lower) {
-                    seen.insert(idx);
-                    out.push(self.all_items[idx].clone());
-                    if out.len() >= VISIBLE_RESULTS_LIMIT {
-                        break;
-                    }
-                }
-            }
+    /// Items named exactly `query_lower`, from the filename index when it is ready.
+    fn exact_filename_matches(&self, query_lower: &str) -> (Vec<SearchItem>, usize) {
+        if self.filename_index_dirty || self.filename_index_building {
+            return (Vec::new(), 0);
         }
+        let query = fold_if(query_lower, self.settings.ascii_fold);
+        let Some(exact) = self.filename_exact_index.get(query.as_ref()) else {
+            return (Vec::new(), 0);
+        };
+        let mut matches: Vec<usize> = exact
+            .iter()
+            .copied()
+            .filter(|&idx| self.include_item(idx))
+            .collect();
+        let total_matches = matches.len();
+        select_first_sorted(
+            &mut matches,
+            self.settings.result_limit(),
+            self.sort_mode,
+            self.sort_descending,
+            |&idx| &self.all_items[idx],
+        );
+        let items = matches
+            .into_iter()
+            .map(|idx| self.all_items[idx].clone())
+            .collect();
+        (items, total_matches)
+    }
 
-        if out.is_empty() {
-            None
-        } else {
-            Some(out)
-        }
+    fn include_item(&self, idx: usize) -> bool {
+        self.settings.include_dirs || self.all_items[idx].kind != SearchItemKind::Folder
     }
 
-    fn try_fast_boolean_search(&mut self, query_lower: &str) -> Option<Vec<SearchItem>> {
+    fn try_fast_boolean_search(&mut self, query_lower: &str) -> Option<(Vec<SearchItem>, usize)> {
         if !query_uses_boolean_logic(query_lower)
             || self.filename_index_dirty
             || self.filename_index_building
@@ -1026,32 +2885,40 @@ impl AppState {
             return None;
         }
 
-        let parsed = SearchQuery::parse(query_lower);
+        let parsed = SearchQuery::parse(query_lower).folding_diacritics(self.settings.ascii_fold);
         let groups = parsed.boolean_groups()?;
-        let mut out = Vec::new();
+        let mut matches = Vec::new();
         let mut seen: HashSet<usize> = HashSet::new();
 
         for group in groups {
-            let candidates = self.boolean_group_candidates(group)?;
+            let candidates = self.boolean_group_candidates(&group)?;
             for idx in candidates {
-                if seen.contains(&idx) {
+                if seen.contains(&idx) || !self.include_item(idx) {
                     continue;
                 }
-                let item = &self.all_items[idx];
-                if parsed.matches_item(item) {
+                if parsed.matches_item(&self.all_items[idx]) {
                     seen.insert(idx);
-                    out.push(item.clone());
-                    if out.len() >= VISIBLE_RESULTS_LIMIT {
-                        return Some(out);
-                    }
+                    matches.push(idx);
                 }
             }
         }
 
-        Some(out)
+        let total_matches = matches.len();
+        select_first_sorted(
+            &mut matches,
+            self.settings.result_limit(),
+            self.sort_mode,
+            self.sort_descending,
+            |&idx| &self.all_items[idx],
+        );
+        let out = matches
+            .into_iter()
+            .map(|idx| self.all_items[idx].clone())
+            .collect();
+        Some((out, total_matches))
     }
 
-    fn boolean_group_candidates(&self, terms: &[String]) -> Option<Vec<usize>> {
+    fn boolean_group_candidates(&self, terms: &[&str]) -> Option<Vec<usize>> {
         let seed = terms
             .iter()
             .filter_map(|term| self.filename_candidate_indices(term))
@@ -1067,42 +2934,204 @@ impl AppState {
             || query_lower.contains('\\')
             || query_lower.contains('/')
             || query_lower.contains(':')
+            || query_lower.contains(char::is_whitespace)
+            || query_lower.starts_with('-')
+            || query_lower.contains('"')
             || query_uses_boolean_logic(query_lower)
         {
             return None;
         }
 
-        let mut out = Vec::new();
-        let mut seen: HashSet<usize> = HashSet::new();
+        Some(filename_index_lookup(
+            &self.filename_exact_index,
+            &self.filename_prefix_index,
+            &self.all_items,
+            query_lower,
+            self.settings.ascii_fold,
+        ))
+    }
 
-        if let Some(exact) = self.filename_exact_index.get(query_lower) {
-            for &idx in exact {
-                if seen.insert(idx) {
-                    out.push(idx);
-                }
+    fn index_errors_label(&self) -> String {
+        self.index_errors
+            .iter()
+            .map(|(_, message)| message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    fn reset_indexing_rate(&mut self) {
+        self.indexing_scanned = 0;
+        self.indexing_total_known = true;
+        self.indexing_rate_ema = 0.0;
+        self.indexing_eta_secs = None;
+        self.indexing_last_sample = None;
+    }
+
+    fn record_indexing_sample(&mut self, scanned: usize, remaining: usize) {
+        let now = Instant::now();
+        if let Some((at, previous)) = self.indexing_last_sample {
+            let secs = now.saturating_duration_since(at).as_secs_f32();
+            if secs > 0.0 && scanned >= previous {
+                let rate = (scanned - previous) as f32 / secs;
+                self.indexing_rate_ema = if self.indexing_rate_ema > 0.0 {
+                    self.indexing_rate_ema * 0.7 + rate * 0.3
+                } else {
+                    rate
+                };
             }
         }
+        self.indexing_last_sample = Some((now, scanned));
+        self.indexing_scanned = scanned;
+        self.indexing_eta_secs = (self.indexing_total_known && self.indexing_rate_ema >= 1.0)
+            .then(|| remaining as f32 / self.indexing_rate_ema);
+    }
+
+    /// `42% · 1.2M files · 340k/s · ~4s left`, leaving out the percent and ETA
+    /// while the total is only an estimate.
+    pub(crate) fn indexing_detail(&self) -> String {
+        let mut parts = Vec::new();
+        if self.indexing_total_known {
+            parts.push(format!("{:.0}%", self.indexing_progress * 100.0));
+        }
+        if self.indexing_scanned > 0 {
+            parts.push(format!(
+                "{} files",
+                format_compact_count(self.indexing_scanned as f32)
+            ));
+        }
+        if self.indexing_rate_ema >= 1.0 {
+            parts.push(format!(
+                "{}/s",
+                format_compact_count(self.indexing_rate_ema)
+            ));
+        }
+        if let Some(eta) = self.indexing_eta_secs {
+            parts.push(format!("~{} left", format_eta(eta)));
+        }
+        if parts.is_empty() {
+            return "starting".to_string();
+        }
+        parts.join(" · ")
+    }
 
-        let mut prefix_key = String::new();
-        for ch in query_lower.chars().take(3) {
-            prefix_key.push(ch);
+    pub(crate) fn results_label(&self) -> String {
+        let more = self.total_matches.saturating_sub(self.items.len());
+        if more == 0 {
+            self.items.len().to_string()
+        } else {
+            format!("{} (+{} more)", self.items.len(), format_count(more))
         }
+    }
 
-        if let Some(candidates) = self.filename_prefix_index.get(&prefix_key) {
-            for &idx in candidates {
-                if seen.contains(&idx) {
-                    continue;
-                }
+    pub(crate) fn sort_label(&self) -> String {
+        if self.sort_mode == SortMode::Relevance {
+            self.sort_mode.label().to_string()
+        } else if self.sort_descending {
+            format!("{} desc", self.sort_mode.label())
+        } else {
+            format!("{} asc", self.sort_mode.label())
+        }
+    }
 
-                let name = file_name_from_path(self.all_items[idx].path.as_ref());
-                if contains_ascii_case_insensitive(name, query_lower) {
-                    seen.insert(idx);
-                    out.push(idx);
-                }
+    /// Drops repeated paths, and with `/dedup` on, extra hard links to a file.
+    fn collapse_duplicates(&mut self) {
+        let before = self.items.len();
+        let latest_links = self.settings.dedup_results.then_some(&self.latest_links);
+        dedup_items(&mut self.items, latest_links);
+        self.total_matches = self.total_matches.saturating_sub(before - self.items.len());
+    }
+
+    fn apply_sort_mode(&mut self) {
+        if self.sort_mode == SortMode::Size {
+            self.resolve_item_sizes(0, self.items.len());
+        }
+        sort_items(&mut self.items, self.sort_mode, self.sort_descending);
+    }
+
+    fn resolve_visible_sizes(&mut self) {
+        let start = self.selected.saturating_sub(self.result_rows);
+        let end = (self.selected + self.result_rows).min(self.items.len());
+        self.resolve_item_sizes(start, end);
+    }
+
+    /// Fills in cached sizes and asks the size worker for the rest; answers
+    /// arrive through `apply_resolved_sizes`.
+    fn resolve_item_sizes(&mut self, start: usize, end: usize) {
+        if start >= end {
+            return;
+        }
+
+        let mut wanted = Vec::new();
+        for item in &mut self.items[start..end] {
+            if item.size != UNKNOWN_SIZE || item.kind != SearchItemKind::File {
+                continue;
+            }
+
+            let path = item.full_path();
+            if let Some(size) = self.size_cache.get(path.as_str()) {
+                item.size = *size;
+                continue;
+            }
+            if self.sizes_pending.insert(path.clone().into_boxed_str()) {
+                wanted.push(path);
+            }
+        }
+        if !wanted.is_empty() {
+            let _ = self.size_tx.send(wanted);
+        }
+    }
+
+    /// Puts sizes from the size worker on the results, and redoes a size
+    /// sort around the selected row.
+    fn apply_resolved_sizes(&mut self) {
+        let mut resolved: HashMap<String, u64> = HashMap::new();
+        while let Ok(sizes) = self.size_rx.try_recv() {
+            resolved.extend(sizes);
+        }
+        if resolved.is_empty() {
+            return;
+        }
+        self.store_sizes(&resolved);
+
+        let mut changed = false;
+        for item in &mut self.items {
+            if item.size != UNKNOWN_SIZE {
+                continue;
+            }
+            if let Some(&size) = resolved.get(&item.full_path()) {
+                item.size = size;
+                changed = true;
+            }
+        }
+        if changed && self.sort_mode == SortMode::Size {
+            let selected = self.items.get(self.selected).map(SearchItem::full_path);
+            sort_items(&mut self.items, self.sort_mode, self.sort_descending);
+            if let Some(row) = selected
+                .and_then(|path| self.items.iter().position(|item| item.full_path() == path))
+            {
+                self.selected = row;
             }
         }
+    }
 
-        Some(out)
+    /// Keeps sizes read off the UI thread on the indexed items, so later
+    /// searches have them too.
+    fn store_sizes(&mut self, resolved: &HashMap<String, u64>) {
+        if self.path_index_dirty {
+            self.rebuild_path_index();
+        }
+        for (path, &size) in resolved {
+            self.sizes_pending.remove(path.as_str());
+            self.size_cache.insert(path.as_str().into(), size);
+            let (folder, name) = split_path(path);
+            if let Some(&index) = self
+                .path_index
+                .get(folder)
+                .and_then(|siblings| siblings.get(name))
+            {
+                self.all_items[index].size = size;
+            }
+        }
     }
 
     fn clamp_selected(&mut self) {
@@ -1113,16 +3142,35 @@ impl AppState {
         }
     }
 
+    fn rebuild_path_index(&mut self) {
+        self.path_index.clear();
+        for (index, item) in self.all_items.iter().enumerate() {
+            self.path_index
+                .entry(item.folder.clone())
+                .or_default()
+                .insert(item.name.clone(), index);
+        }
+        self.path_index_dirty = false;
+    }
+
     fn apply_index_delta(
         &mut self,
         upserts: Vec<SearchItem>,
         deleted_paths: Vec<String>,
+        kinds: HashMap<String, ChangeKind>,
     ) -> (usize, usize, usize) {
         let now_unix = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_secs() as i64)
             .unwrap_or(0);
 
+        if self.path_index_dirty {
+            self.rebuild_path_index();
+        }
+        // A finished filename index follows the delta in place; one that is
+        // missing or half built is rebuilt from scratch instead.
+        let incremental = !self.filename_index_dirty && !self.filename_index_building;
+
         let mut deleted_count = 0usize;
         if !deleted_paths.is_empty() {
             let delete_set: HashSet<String> = deleted_paths.into_iter().collect();
@@ -1130,52 +3178,117 @@ impl AppState {
                 deleted_count = delete_set.len();
                 for path in &delete_set {
                     self.recent_event_by_path.remove(path.as_str());
+                    self.change_feed
+                        .push(path.as_str(), ChangeKind::Deleted, now_unix);
+                }
+            }
+            for path in &delete_set {
+                self.size_cache.remove(path.as_str());
+                let Some((removed, slot)) =
+                    remove_item(&mut self.all_items, &mut self.path_index, path)
+                else {
+                    continue;
+                };
+                if incremental {
+                    unindex_swap_removed(
+                        &mut self.filename_exact_index,
+                        &mut self.filename_prefix_index,
+                        &self.all_items,
+                        &removed.name,
+                        slot,
+                        self.settings.ascii_fold,
+                    );
                 }
             }
-            self.all_items
-                .retain(|item| !delete_set.contains(item.path.as_ref()));
         }
 
         let mut added_count = 0usize;
         let mut updated_count = 0usize;
         for upsert in upserts {
+            let path = upsert.full_path();
+            self.size_cache.remove(path.as_str());
+            if upsert.file_id != 0 {
+                let volume = volume_of(&upsert.folder).into();
+                self.latest_links
+                    .insert((volume, upsert.file_id), path.as_str().into());
+            }
+            let modified_unix_secs = upsert.modified_unix_secs;
+
+            let (slot, added) = upsert_item(&mut self.all_items, &mut self.path_index, upsert);
+            if added && incremental {
+                index_filename(
+                    &mut self.filename_exact_index,
+                    &mut self.filename_prefix_index,
+                    &self.all_items[slot].name,
+                    slot,
+                    self.settings.ascii_fold,
+                );
+            }
             if self.tracking_enabled {
-                let event_ts = if upsert.modified_unix_secs == UNKNOWN_TS {
+                let kind = kinds.get(&path).copied().unwrap_or(if added {
+                    ChangeKind::Created
+                } else {
+                    ChangeKind::Modified
+                });
+                let at_unix_secs = if modified_unix_secs == UNKNOWN_TS {
                     now_unix
                 } else {
-                    upsert.modified_unix_secs
+                    modified_unix_secs
                 };
+                self.change_feed.push(path.as_str(), kind, now_unix);
                 self.recent_event_by_path
-                    .insert(upsert.path.clone(), event_ts);
-            }
-            if let Some(existing) = self
-                .all_items
-                .iter_mut()
-                .find(|item| item.path == upsert.path)
-            {
-                *existing = upsert;
-                if self.tracking_enabled {
-                    updated_count += 1;
-                }
-            } else {
-                self.all_items.push(upsert);
-                if self.tracking_enabled {
+                    .insert(path.into_boxed_str(), RecentEvent { at_unix_secs, kind });
+                if added {
                     added_count += 1;
+                } else {
+                    updated_count += 1;
                 }
             }
         }
 
         self.needs_search_refresh = true;
-        self.filename_index_dirty = true;
-        self.filename_index_building = false;
-        self.filename_index_build_cursor = 0;
+        if !incremental {
+            self.filename_index_dirty = true;
+            self.filename_index_building = false;
+            self.filename_index_build_cursor = 0;
+        }
         (added_count, updated_count, deleted_count)
     }
 }
 
-fn init_hotkey() -> Result<(Option<GlobalHotKeyManager>, Option<HotKey>), String> {
+fn item_on_disk(path: &str) -> Option<SearchItem> {
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    let modified_unix_secs = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(UNKNOWN_TS);
+    let (size, kind) = if metadata.is_dir() {
+        (UNKNOWN_SIZE, SearchItemKind::Folder)
+    } else {
+        (metadata.len(), SearchItemKind::File)
+    };
+    Some(
+        SearchItem::new(path, modified_unix_secs, size, kind)
+            .with_attrs(file_attributes(&metadata)),
+    )
+}
+
+fn describe_targets(verb: &str, targets: &[String]) -> String {
+    match targets {
+        [single] => format!("{}: {}", verb, single),
+        _ => format!("{}: {} items", verb, targets.len()),
+    }
+}
+
+fn init_hotkey(combo: &str) -> Result<(Option<GlobalHotKeyManager>, Option<HotKey>), String> {
     let manager = GlobalHotKeyManager::new().map_err(|e| e.to_string())?;
-    let hotkey = HotKey::new(Some(Modifiers::empty()), Code::Backquote);
+    let (mods, code) = parse_hotkey(combo).unwrap_or_else(|err| {
+        debug_log(&format!("{}, falling back to `", err));
+        (Modifiers::empty(), Code::Backquote)
+    });
+    let hotkey = HotKey::new(Some(mods), code);
 
     manager.register(hotkey).map_err(|e| e.to_string())?;
 
@@ -1229,3 +3342,24 @@ fn build_tray_icon() -> Result<Icon, String> {
 
     Icon::from_rgba(rgba, width, height).map_err(|e| e.to_string())
 }
+
+fn format_compact_count(value: f32) -> String {
+    if value >= 1_000_000.0 {
+        format!("{:.1}M", value / 1_000_000.0)
+    } else if value >= 1_000.0 {
+        format!("{:.0}k", value / 1_000.0)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+fn format_eta(secs: f32) -> String {
+    let secs = secs.ceil() as u64;
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
@@ -0,0 +1,384 @@
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::feed::RecentEvents;
+use crate::indexing::index_files_for_scope;
+use crate::search::{SearchQuery, SortMode};
+use crate::search_worker::{run_search_to_end, SearchRun};
+use crate::settings::Settings;
+use crate::{format_count, parse_scope_arg, SearchItem, SearchScope, UNKNOWN_SIZE, UNKNOWN_TS};
+
+/// Queries timed by `--benchmark` when no `--queries` file is given.
+const DEFAULT_BENCHMARK_QUERIES: [&str; 8] = [
+    "a",
+    "report",
+    "*.rs",
+    "ext:pdf",
+    "invoice or receipt",
+    "config path:src",
+    "size:>10mb",
+    "modified:7d",
+];
+/// Timed repeats of each query after the first, cold one.
+const WARM_RUNS: u32 = 3;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct CliRequest {
+    pub(crate) query: String,
+    pub(crate) scope: SearchScope,
+    pub(crate) json: bool,
+    pub(crate) limit: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct BenchmarkRequest {
+    pub(crate) scope: SearchScope,
+    pub(crate) queries_path: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct CliItem {
+    path: String,
+    modified_unix_secs: Option<i64>,
+    size: Option<u64>,
+}
+
+impl From<&SearchItem> for CliItem {
+    fn from(item: &SearchItem) -> Self {
+        Self {
+            path: item.full_path(),
+            modified_unix_secs: (item.modified_unix_secs != UNKNOWN_TS)
+                .then_some(item.modified_unix_secs),
+            size: (item.size != UNKNOWN_SIZE).then_some(item.size),
+        }
+    }
+}
+
+/// Returns a request when `--query` is present; the GUI starts otherwise.
+pub(crate) fn cli_request_from_args(args: impl Iterator<Item = String>) -> Option<CliRequest> {
+    let mut query = None;
+    let mut scope = SearchScope::CurrentFolder;
+    let mut json = false;
+    let mut limit = None;
+
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .map(str::to_string)
+                .or_else(|| args.next_if(|next| !next.starts_with("--")))
+        };
+
+        match name.as_str() {
+            "--query" => query = value(),
+            "--scope" => {
+                if let Some(parsed) = value().as_deref().and_then(parse_scope_arg) {
+                    scope = parsed;
+                }
+            }
+            "--limit" => limit = value().and_then(|v| v.trim().parse().ok()),
+            "--json" => json = true,
+            _ => {}
+        }
+    }
+
+    Some(CliRequest {
+        query: query?,
+        scope,
+        json,
+        limit,
+    })
+}
+
+/// Returns a request when `--benchmark` is present.
+pub(crate) fn benchmark_request_from_args(
+    args: impl Iterator<Item = String>,
+) -> Option<BenchmarkRequest> {
+    let mut benchmark = false;
+    let mut scope = SearchScope::CurrentFolder;
+    let mut queries_path = None;
+
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .map(str::to_string)
+                .or_else(|| args.next_if(|next| !next.starts_with("--")))
+        };
+
+        match name.as_str() {
+            "--benchmark" => benchmark = true,
+            "--scope" => {
+                if let Some(parsed) = value().as_deref().and_then(parse_scope_arg) {
+                    scope = parsed;
+                }
+            }
+            "--queries" => queries_path = value(),
+            _ => {}
+        }
+    }
+
+    benchmark.then_some(BenchmarkRequest {
+        scope,
+        queries_path,
+    })
+}
+
+pub(crate) fn serve_requested(mut args: impl Iterator<Item = String>) -> bool {
+    args.any(|arg| arg == "--serve")
+}
+
+pub(crate) fn run_cli_request(request: CliRequest) -> i32 {
+    attach_parent_console();
+
+    let query = SearchQuery::parse(&request.query.to_lowercase());
+    let limit = request.limit.unwrap_or(usize::MAX);
+    let items: Vec<SearchItem> = index_files_for_scope(request.scope)
+        .into_iter()
+        .filter(|item| query.matches_item(item))
+        .take(limit)
+        .collect();
+
+    let mut out = std::io::stdout().lock();
+    let written = if request.json {
+        let items: Vec<CliItem> = items.iter().map(CliItem::from).collect();
+        serde_json::to_writer(&mut out, &items)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writeln!(out))
+    } else {
+        items
+            .iter()
+            .try_for_each(|item| writeln!(out, "{}", item.full_path()))
+    };
+
+    match written.and_then(|_| out.flush()) {
+        Ok(()) => 0,
+        Err(_) => 1,
+    }
+}
+
+/// Indexes the scope, times each query cold and then warm, and prints a
+/// table. Exits non-zero when the queries file cannot be read.
+pub(crate) fn run_benchmark(request: BenchmarkRequest) -> i32 {
+    attach_parent_console();
+
+    let queries = match &request.queries_path {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(content) => benchmark_queries(&content),
+            Err(err) => {
+                eprintln!("could not read {}: {}", path, err);
+                return 1;
+            }
+        },
+        None => DEFAULT_BENCHMARK_QUERIES
+            .iter()
+            .map(|query| query.to_string())
+            .collect(),
+    };
+
+    let started = Instant::now();
+    let mut corpus = index_files_for_scope(request.scope.clone());
+    let index_time = started.elapsed();
+
+    let settings = Settings::load_or_default();
+    let mut recent = RecentEvents::new();
+    let timings: Vec<QueryTiming> = queries
+        .into_iter()
+        .map(|query| {
+            let run = SearchRun {
+                generation: 0,
+                query: query.to_lowercase(),
+                regex: None,
+                latest_only_mode: false,
+                latest_window_secs: 0,
+                include_dirs: settings.include_dirs,
+                full_path: settings.search_full_paths,
+                fold_diacritics: settings.ascii_fold,
+                result_limit: settings.result_limit(),
+                batch_size: settings.search_batch_size(),
+                sort_mode: SortMode::Relevance,
+                sort_descending: false,
+                per_folder: None,
+                modified_since: None,
+            };
+            let started = Instant::now();
+            let results = run_search_to_end(run.clone(), &mut corpus, &mut recent);
+            let cold = started.elapsed();
+            let started = Instant::now();
+            for _ in 0..WARM_RUNS {
+                run_search_to_end(run.clone(), &mut corpus, &mut recent);
+            }
+            QueryTiming {
+                query,
+                cold,
+                warm: started.elapsed() / WARM_RUNS,
+                results,
+            }
+        })
+        .collect();
+
+    let table = benchmark_table(&request.scope, index_time, corpus.len(), &timings);
+    let mut out = std::io::stdout().lock();
+    match out.write_all(table.as_bytes()).and_then(|_| out.flush()) {
+        Ok(()) => 0,
+        Err(_) => 1,
+    }
+}
+
+struct QueryTiming {
+    query: String,
+    cold: Duration,
+    /// Mean of the warm runs.
+    warm: Duration,
+    results: usize,
+}
+
+/// One query per line; blank lines and `#` comments are skipped.
+fn benchmark_queries(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+fn benchmark_table(
+    scope: &SearchScope,
+    index_time: Duration,
+    corpus_len: usize,
+    timings: &[QueryTiming],
+) -> String {
+    let ms = |duration: Duration| format!("{:.1}", duration.as_secs_f64() * 1000.0);
+    let width = timings
+        .iter()
+        .map(|timing| timing.query.chars().count())
+        .chain([5])
+        .max()
+        .unwrap_or(5);
+
+    let mut out = format!(
+        "indexed {} items from {} in {} ms\n\n",
+        format_count(corpus_len),
+        scope.label(),
+        ms(index_time)
+    );
+    out.push_str(&format!(
+        "{:<width$}  {:>10}  {:>10}  {:>10}\n",
+        "query", "cold ms", "warm ms", "results"
+    ));
+    for timing in timings {
+        out.push_str(&format!(
+            "{:<width$}  {:>10}  {:>10}  {:>10}\n",
+            timing.query,
+            ms(timing.cold),
+            ms(timing.warm),
+            format_count(timing.results)
+        ));
+    }
+    out
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn attach_parent_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // The GUI subsystem starts without a console; reuse the caller's terminal.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn attach_parent_console() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> impl Iterator<Item = String> {
+        values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn parses_query_scope_json_and_limit() {
+        let request = cli_request_from_args(args(&[
+            "--query",
+            "*.rs main",
+            "--scope=d:",
+            "--json",
+            "--limit",
+            "5",
+        ]))
+        .unwrap();
+        assert_eq!(
+            request,
+            CliRequest {
+                query: "*.rs main".to_string(),
+                scope: SearchScope::Drive('D'),
+                json: true,
+                limit: Some(5),
+            }
+        );
+    }
+
+    #[test]
+    fn gui_starts_without_query() {
+        assert!(cli_request_from_args(args(&["--scope=all-local-drives", "--hide"])).is_none());
+        assert!(cli_request_from_args(args(&["--query"])).is_none());
+        assert!(benchmark_request_from_args(args(&["--scope=all-local-drives"])).is_none());
+    }
+
+    #[test]
+    fn parses_benchmark_scope_and_queries() {
+        let request =
+            benchmark_request_from_args(args(&["--benchmark", "--scope", "d:", "--queries=q.txt"]))
+                .unwrap();
+        assert_eq!(
+            request,
+            BenchmarkRequest {
+                scope: SearchScope::Drive('D'),
+                queries_path: Some("q.txt".to_string()),
+            }
+        );
+        assert_eq!(
+            benchmark_queries("# warm-up\nreport\n\n  ext:pdf  \n"),
+            ["report", "ext:pdf"]
+        );
+    }
+
+    #[test]
+    fn benchmark_table_aligns_columns() {
+        let timings = [QueryTiming {
+            query: "invoice or receipt".to_string(),
+            cold: Duration::from_micros(12_340),
+            warm: Duration::from_micros(4_500),
+            results: 1_234,
+        }];
+        let table = benchmark_table(
+            &SearchScope::Drive('C'),
+            Duration::from_millis(2_500),
+            1_500_000,
+            &timings,
+        );
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "indexed 1,500,000 items from C: in 2500.0 ms");
+        assert_eq!(lines[2].len(), lines[3].len());
+        assert!(lines[3].starts_with("invoice or receipt        12.3         4.5"));
+        assert!(lines[3].ends_with("1,234"));
+    }
+}
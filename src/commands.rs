@@ -1,3 +1,8 @@
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+use crate::search::{relevance_score, SortMode};
 use crate::SearchScope;
 
 pub(crate) struct ParsedDirective {
@@ -7,16 +12,63 @@ pub(crate) struct ParsedDirective {
     pub(crate) exit_app: bool,
     pub(crate) elevate_app: bool,
     pub(crate) latest_only: bool,
+    pub(crate) toggle_feed: bool,
     pub(crate) latest_window_secs: Option<i64>,
     pub(crate) reindex_current_scope: bool,
+    pub(crate) clear_cache: bool,
     pub(crate) toggle_tracking: bool,
     pub(crate) toggle_fullscreen: bool,
     pub(crate) toggle_fullheight: bool,
     pub(crate) result_rows_directive: bool,
     pub(crate) result_rows: Option<usize>,
+    pub(crate) drive_directive: bool,
+    pub(crate) drive_letter: Option<char>,
+    pub(crate) scope_directive: bool,
+    pub(crate) cycle_scope: bool,
+    pub(crate) font_size_directive: bool,
+    pub(crate) font_size_percent: Option<u32>,
     pub(crate) switch_renderer_gpu: bool,
     pub(crate) switch_renderer_soft: bool,
     pub(crate) show_about: bool,
+    pub(crate) sort_directive: bool,
+    pub(crate) sort_mode: Option<SortMode>,
+    pub(crate) sort_descending: Option<bool>,
+    pub(crate) regex_mode: bool,
+    pub(crate) hotkey_directive: bool,
+    pub(crate) hotkey: Option<String>,
+    pub(crate) theme_directive: bool,
+    pub(crate) theme: Option<String>,
+    pub(crate) startup_directive: bool,
+    pub(crate) launch_at_startup: Option<bool>,
+    pub(crate) toggle_dirs: bool,
+    pub(crate) toggle_full_path: bool,
+    pub(crate) toggle_fold: bool,
+    pub(crate) toggle_density: bool,
+    pub(crate) toggle_diverse: bool,
+    pub(crate) toggle_dedup: bool,
+    pub(crate) toggle_preview: bool,
+    pub(crate) exclude_directive: bool,
+    pub(crate) exclude_pattern: Option<String>,
+    pub(crate) gitignore_directive: bool,
+    pub(crate) respect_gitignore: Option<bool>,
+    pub(crate) hidden_directive: bool,
+    pub(crate) index_hidden: Option<bool>,
+    pub(crate) set_directive: bool,
+    pub(crate) set_key: Option<String>,
+    pub(crate) set_value: Option<u64>,
+    pub(crate) show_history: bool,
+    pub(crate) toggle_pin: bool,
+    pub(crate) open_terminal: bool,
+    pub(crate) undo_recycle: bool,
+    pub(crate) show_pins: bool,
+    pub(crate) show_help: bool,
+    pub(crate) export_directive: bool,
+    pub(crate) copy_all: bool,
+    pub(crate) export_path: Option<String>,
+    pub(crate) openwith_directive: bool,
+    pub(crate) openwith_program: Option<String>,
+    pub(crate) goto_directive: bool,
+    pub(crate) goto_target: Option<String>,
 }
 
 pub(crate) fn parse_scope_directive(input: &str) -> ParsedDirective {
@@ -26,27 +78,181 @@ pub(crate) fn parse_scope_directive(input: &str) -> ParsedDirective {
     let mut exit_app = false;
     let mut elevate_app = false;
     let mut latest_only = false;
+    let mut toggle_feed = false;
     let mut latest_window_secs = None;
     let mut reindex_current_scope = false;
+    let mut clear_cache = false;
     let mut toggle_tracking = false;
     let mut toggle_fullscreen = false;
     let mut toggle_fullheight = false;
     let mut result_rows_directive = false;
     let mut result_rows = None;
+    let mut drive_directive = false;
+    let mut drive_letter = None;
+    let mut scope_directive = false;
+    let mut cycle_scope = false;
+    let mut font_size_directive = false;
+    let mut font_size_percent = None;
     let mut switch_renderer_gpu = false;
     let mut switch_renderer_soft = false;
     let mut show_about = false;
+    let mut sort_directive = false;
+    let mut sort_mode = None;
+    let mut sort_descending = None;
+    let mut regex_mode = false;
+    let mut hotkey_directive = false;
+    let mut hotkey = None;
+    let mut theme_directive = false;
+    let mut theme = None;
+    let mut startup_directive = false;
+    let mut launch_at_startup = None;
+    let mut toggle_dirs = false;
+    let mut toggle_full_path = false;
+    let mut toggle_fold = false;
+    let mut toggle_density = false;
+    let mut toggle_diverse = false;
+    let mut toggle_dedup = false;
+    let mut toggle_preview = false;
+    let mut exclude_directive = false;
+    let mut exclude_pattern = None;
+    let mut gitignore_directive = false;
+    let mut respect_gitignore = None;
+    let mut hidden_directive = false;
+    let mut index_hidden = None;
+    let mut set_directive = false;
+    let mut set_key = None;
+    let mut set_value = None;
+    let mut show_history = false;
+    let mut toggle_pin = false;
+    let mut open_terminal = false;
+    let mut undo_recycle = false;
+    let mut show_pins = false;
+    let mut show_help = false;
+    let mut export_directive = false;
+    let mut copy_all = false;
+    let mut export_path: Option<String> = None;
+    let mut openwith_directive = false;
+    let mut openwith_program: Option<String> = None;
+    let mut goto_directive = false;
+    let mut goto_target: Option<String> = None;
     let mut pending_rows_value = false;
+    let mut pending_font_size_value = false;
+    let mut pending_scope_value = false;
+    let mut pending_drive_value = false;
+    let mut pending_hotkey_value = false;
+    let mut pending_theme_value = false;
+    let mut pending_startup_value = false;
+    let mut pending_exclude_value = false;
+    let mut pending_gitignore_value = false;
+    let mut pending_hidden_value = false;
+    let mut pending_set_key = false;
+    let mut pending_set_value = false;
 
     for token in input.split_whitespace() {
         let normalized = token.to_ascii_lowercase();
 
+        // Everything after `/export`, `/openwith` or `/goto` is one path, spaces included.
+        if export_directive || openwith_directive || goto_directive {
+            let target = if export_directive {
+                &mut export_path
+            } else if openwith_directive {
+                &mut openwith_program
+            } else {
+                &mut goto_target
+            };
+            let path = target.get_or_insert_with(String::new);
+            if !path.is_empty() {
+                path.push(' ');
+            }
+            path.push_str(token);
+            continue;
+        }
+
         if pending_rows_value {
             result_rows = normalized.parse::<usize>().ok();
             pending_rows_value = false;
             continue;
         }
 
+        if pending_drive_value {
+            drive_letter = parse_drive_letter(&normalized);
+            pending_drive_value = false;
+            continue;
+        }
+
+        if pending_scope_value {
+            cycle_scope = normalized == "next";
+            pending_scope_value = false;
+            continue;
+        }
+
+        if pending_font_size_value {
+            font_size_percent = normalized.trim_end_matches('%').parse::<u32>().ok();
+            pending_font_size_value = false;
+            continue;
+        }
+
+        if pending_hotkey_value {
+            hotkey = Some(token.to_string());
+            pending_hotkey_value = false;
+            continue;
+        }
+
+        if pending_theme_value {
+            theme = Some(normalized);
+            pending_theme_value = false;
+            continue;
+        }
+
+        if pending_startup_value {
+            launch_at_startup = match normalized.as_str() {
+                "on" => Some(true),
+                "off" => Some(false),
+                _ => None,
+            };
+            pending_startup_value = false;
+            continue;
+        }
+
+        if pending_exclude_value {
+            exclude_pattern = Some(token.to_string());
+            pending_exclude_value = false;
+            continue;
+        }
+
+        if pending_gitignore_value {
+            respect_gitignore = match normalized.as_str() {
+                "on" => Some(true),
+                "off" => Some(false),
+                _ => None,
+            };
+            pending_gitignore_value = false;
+            continue;
+        }
+
+        if pending_hidden_value {
+            index_hidden = match normalized.as_str() {
+                "on" => Some(true),
+                "off" => Some(false),
+                _ => None,
+            };
+            pending_hidden_value = false;
+            continue;
+        }
+
+        if pending_set_key {
+            set_key = Some(normalized);
+            pending_set_key = false;
+            pending_set_value = true;
+            continue;
+        }
+
+        if pending_set_value {
+            set_value = normalized.parse::<u64>().ok();
+            pending_set_value = false;
+            continue;
+        }
+
         if normalized == "/entire" {
             scope_override = Some(SearchScope::EntireCurrentDrive);
             continue;
@@ -77,6 +283,11 @@ pub(crate) fn parse_scope_directive(input: &str) -> ParsedDirective {
             continue;
         }
 
+        if normalized == "/feed" {
+            toggle_feed = true;
+            continue;
+        }
+
         if normalized == "/latest" || normalized == "/last" {
             latest_only = true;
             continue;
@@ -87,6 +298,11 @@ pub(crate) fn parse_scope_directive(input: &str) -> ParsedDirective {
             continue;
         }
 
+        if normalized == "/clearcache" {
+            clear_cache = true;
+            continue;
+        }
+
         if normalized == "/track" {
             toggle_tracking = true;
             continue;
@@ -97,11 +313,64 @@ pub(crate) fn parse_scope_directive(input: &str) -> ParsedDirective {
             continue;
         }
 
+        if normalized == "/dirs" {
+            toggle_dirs = true;
+            continue;
+        }
+
+        if normalized == "/fullpath" {
+            toggle_full_path = true;
+            continue;
+        }
+
+        if normalized == "/fold" {
+            toggle_fold = true;
+            continue;
+        }
+
+        if normalized == "/density" {
+            toggle_density = true;
+            continue;
+        }
+
+        if normalized == "/diverse" {
+            toggle_diverse = true;
+            continue;
+        }
+
+        if normalized == "/dedup" {
+            toggle_dedup = true;
+            continue;
+        }
+
+        if normalized == "/preview" {
+            toggle_preview = true;
+            continue;
+        }
+
         if normalized == "/fullheight" {
             toggle_fullheight = true;
             continue;
         }
 
+        if normalized == "/drive" {
+            drive_directive = true;
+            pending_drive_value = true;
+            continue;
+        }
+
+        if normalized == "/scope" {
+            scope_directive = true;
+            pending_scope_value = true;
+            continue;
+        }
+
+        if normalized == "/fontsize" {
+            font_size_directive = true;
+            pending_font_size_value = true;
+            continue;
+        }
+
         if normalized == "/rows" {
             result_rows_directive = true;
             pending_rows_value = true;
@@ -129,6 +398,136 @@ pub(crate) fn parse_scope_directive(input: &str) -> ParsedDirective {
             continue;
         }
 
+        if normalized == "/re" {
+            regex_mode = true;
+            continue;
+        }
+
+        if normalized == "/hotkey" {
+            hotkey_directive = true;
+            pending_hotkey_value = true;
+            continue;
+        }
+
+        if normalized == "/theme" {
+            theme_directive = true;
+            pending_theme_value = true;
+            continue;
+        }
+
+        if normalized == "/startup" {
+            startup_directive = true;
+            pending_startup_value = true;
+            continue;
+        }
+
+        if normalized == "/history" {
+            show_history = true;
+            continue;
+        }
+
+        if normalized == "/pin" {
+            toggle_pin = true;
+            continue;
+        }
+
+        if normalized == "/term" {
+            open_terminal = true;
+            continue;
+        }
+
+        if normalized == "/undo" {
+            undo_recycle = true;
+            continue;
+        }
+
+        if normalized == "/pins" {
+            show_pins = true;
+            continue;
+        }
+
+        if normalized == "/help" {
+            show_help = true;
+            continue;
+        }
+
+        if normalized == "/openwith" {
+            openwith_directive = true;
+            continue;
+        }
+
+        if normalized == "/export" {
+            export_directive = true;
+            continue;
+        }
+
+        if normalized == "/goto" {
+            goto_directive = true;
+            continue;
+        }
+
+        if normalized == "/copyall" {
+            copy_all = true;
+            continue;
+        }
+
+        if normalized == "/set" {
+            set_directive = true;
+            pending_set_key = true;
+            continue;
+        }
+
+        if normalized == "/memlimit" {
+            set_directive = true;
+            set_key = Some("memlimit".to_string());
+            pending_set_value = true;
+            continue;
+        }
+
+        if normalized == "/gitignore" {
+            gitignore_directive = true;
+            pending_gitignore_value = true;
+            continue;
+        }
+
+        if normalized == "/hidden" {
+            hidden_directive = true;
+            pending_hidden_value = true;
+            continue;
+        }
+
+        if normalized == "/exclude" {
+            exclude_directive = true;
+            pending_exclude_value = true;
+            continue;
+        }
+
+        if normalized == "/sort" {
+            sort_directive = true;
+            continue;
+        }
+
+        if sort_directive && sort_mode.is_none() {
+            if let Some(mode) = SortMode::from_label(&normalized) {
+                sort_mode = Some(mode);
+                continue;
+            }
+        }
+
+        if sort_directive && sort_descending.is_none() {
+            match normalized.as_str() {
+                "asc" => {
+                    sort_descending = Some(false);
+                    continue;
+                }
+                "desc" => {
+                    sort_descending = Some(true);
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
         if latest_only && latest_window_secs.is_none() {
             if let Some(seconds) = parse_latest_window_token(&normalized) {
                 latest_window_secs = Some(seconds);
@@ -150,21 +549,74 @@ pub(crate) fn parse_scope_directive(input: &str) -> ParsedDirective {
         exit_app,
         elevate_app,
         latest_only,
+        toggle_feed,
         latest_window_secs,
         reindex_current_scope,
+        clear_cache,
         toggle_tracking,
         toggle_fullscreen,
         toggle_fullheight,
         result_rows_directive,
         result_rows,
+        drive_directive,
+        drive_letter,
+        scope_directive,
+        cycle_scope,
+        font_size_directive,
+        font_size_percent,
         switch_renderer_gpu,
         switch_renderer_soft,
         show_about,
+        sort_directive,
+        sort_mode,
+        sort_descending,
+        regex_mode,
+        hotkey_directive,
+        hotkey,
+        theme_directive,
+        theme,
+        startup_directive,
+        launch_at_startup,
+        toggle_dirs,
+        toggle_full_path,
+        toggle_fold,
+        toggle_density,
+        toggle_diverse,
+        toggle_dedup,
+        toggle_preview,
+        exclude_directive,
+        exclude_pattern,
+        gitignore_directive,
+        respect_gitignore,
+        hidden_directive,
+        index_hidden,
+        set_directive,
+        set_key,
+        set_value,
+        show_history,
+        toggle_pin,
+        open_terminal,
+        undo_recycle,
+        show_pins,
+        show_help,
+        export_directive,
+        copy_all,
+        export_path: export_path
+            .map(|path| path.trim_matches('"').to_string())
+            .filter(|path| !path.is_empty()),
+        openwith_directive,
+        openwith_program: openwith_program
+            .map(|program| program.trim_matches('"').to_string())
+            .filter(|program| !program.is_empty()),
+        goto_directive,
+        goto_target: goto_target
+            .map(|target| target.trim_matches('"').to_string())
+            .filter(|target| !target.is_empty()),
     }
 }
 
 pub(crate) struct CommandMenuItem {
-    pub(crate) command: &'static str,
+    pub(crate) command: Cow<'static, str>,
     pub(crate) description: &'static str,
 }
 
@@ -174,75 +626,371 @@ pub(crate) fn command_menu_items(input: &str, tracking_enabled: bool) -> Vec<Com
         return Vec::new();
     }
 
-    let prefix = trimmed
+    let mut tokens = trimmed.split_whitespace();
+    let prefix = tokens.next().unwrap_or("").to_ascii_lowercase();
+    if matches!(
+        prefix.as_str(),
+        "/re" | "/hotkey" | "/exclude" | "/export" | "/openwith" | "/goto"
+    ) && tokens.next().is_some()
+    {
+        return Vec::new();
+    }
+
+    let (mut matches, rest): (Vec<_>, Vec<_>) = command_catalog(tracking_enabled)
+        .into_iter()
+        .partition(|item| prefix == "/" || prefix_matches(&prefix, &item.command));
+
+    // Typos like `/reindx` or `/realnd` still suggest `/reindex`, after the
+    // prefix matches and ordered by how many edits they are away.
+    let typed = &prefix[1..];
+    if typed.chars().count() >= FUZZY_COMMAND_MIN_LEN && !typed.ends_with(':') {
+        let max_edits = 1 + typed.chars().count() / 5;
+        let mut fuzzy: Vec<_> = rest
+            .into_iter()
+            .filter_map(|item| {
+                let name = item.command.split_whitespace().next()?[1..].to_ascii_lowercase();
+                let edits = prefix_edit_distance(typed, &name);
+                let score = relevance_score(typed, &name);
+                (edits <= max_edits || score.is_some()).then_some((edits, score, item))
+            })
+            .collect();
+        fuzzy.sort_by_key(|(edits, score, _)| (*edits, Reverse(*score)));
+        matches.extend(fuzzy.into_iter().map(|(_, _, item)| item));
+    }
+
+    matches
+}
+
+const FUZZY_COMMAND_MIN_LEN: usize = 3;
+
+/// Whether `command` was offered because it starts with what was typed, as
+/// opposed to being a typo guess, which Enter only fills in.
+pub(crate) fn command_matches_typed(input: &str, command: &str) -> bool {
+    let prefix = input
         .split_whitespace()
         .next()
         .unwrap_or("")
         .to_ascii_lowercase();
+    prefix == "/" || prefix_matches(&prefix, command)
+}
+
+fn prefix_matches(prefix: &str, command: &str) -> bool {
+    command.to_ascii_lowercase().starts_with(prefix)
+        || (prefix.len() == 3
+            && prefix.starts_with('/')
+            && prefix.ends_with(':')
+            && prefix.as_bytes()[1].is_ascii_alphabetic()
+            && command == "/x:")
+}
 
+/// Edits (insertions, deletions, substitutions, adjacent swaps) needed to
+/// turn `typed` into some prefix of `command`, so an unfinished command is
+/// not penalized for its missing tail.
+fn prefix_edit_distance(typed: &str, command: &str) -> usize {
+    let typed: Vec<char> = typed.chars().collect();
+    let command: Vec<char> = command.chars().collect();
+    let width = command.len() + 1;
+    let mut rows = vec![vec![0usize; width]; typed.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..width).collect();
+
+    for i in 1..=typed.len() {
+        for j in 1..width {
+            let cost = usize::from(typed[i - 1] != command[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && typed[i - 1] == command[j - 2] && typed[i - 2] == command[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+
+    rows[typed.len()].iter().copied().min().unwrap_or(0)
+}
+
+/// Every command the dropdown can offer. Completion and `/help` both read
+/// from here, so a new command only needs an entry in this list.
+fn command_catalog(tracking_enabled: bool) -> Vec<CommandMenuItem> {
     let items = [
         CommandMenuItem {
-            command: "/entire",
+            command: "/entire".into(),
             description: "Search entire current drive",
         },
         CommandMenuItem {
-            command: "/all",
+            command: "/all".into(),
             description: "Search all local drives",
         },
         CommandMenuItem {
-            command: "/x:",
+            command: "/drive".into(),
+            description: "Search one drive (example /drive d)",
+        },
+        CommandMenuItem {
+            command: "/scope next".into(),
+            description: "Cycle folder, drive and all drives (Ctrl+Tab)",
+        },
+        CommandMenuItem {
+            command: "/x:".into(),
             description: "Search specific drive (example /d:)",
         },
         CommandMenuItem {
-            command: "/testProgress",
+            command: "/testProgress".into(),
             description: "Visual progress bar test",
         },
         CommandMenuItem {
-            command: "/up",
+            command: "/up".into(),
             description: "Relaunch app elevated",
         },
         CommandMenuItem {
-            command: "/track",
+            command: "/track".into(),
             description: "Toggle live event tracking",
         },
         CommandMenuItem {
-            command: "/fullscreen",
+            command: "/dirs".into(),
+            description: "Toggle folders in results",
+        },
+        CommandMenuItem {
+            command: "/fullpath".into(),
+            description: "Toggle matching full paths instead of file names",
+        },
+        CommandMenuItem {
+            command: "/fold".into(),
+            description: "Toggle ignoring diacritics, so cafe finds café",
+        },
+        CommandMenuItem {
+            command: "/density".into(),
+            description: "Toggle compact or normal result rows",
+        },
+        CommandMenuItem {
+            command: "/diverse".into(),
+            description: "Cap results per folder so one big folder cannot fill the list",
+        },
+        CommandMenuItem {
+            command: "/dedup".into(),
+            description: "Show a hard-linked file once, under its latest name",
+        },
+        CommandMenuItem {
+            command: "/preview".into(),
+            description: "Toggle the file preview pane (F3)",
+        },
+        CommandMenuItem {
+            command: "/fullscreen".into(),
             description: "Toggle fullscreen window",
         },
         CommandMenuItem {
-            command: "/fullheight",
+            command: "/fullheight".into(),
             description: "Toggle full-height mode",
         },
         CommandMenuItem {
-            command: "/rows",
+            command: "/rows".into(),
             description: "Set visible result rows (example /rows 40)",
         },
         CommandMenuItem {
-            command: "/gpu",
-            description: "Switch to GPU renderer",
+            command: "/fontsize".into(),
+            description: "Scale result text in percent, 50 to 300 (example /fontsize 150)",
+        },
+        CommandMenuItem {
+            command: "/gpu".into(),
+            description: "Switch to the GPU renderer (saved for next start)",
         },
         CommandMenuItem {
-            command: "/soft",
-            description: "Switch to soft renderer",
+            command: "/soft".into(),
+            description: "Switch to the soft ratatui renderer (saved for next start)",
         },
         CommandMenuItem {
-            command: "/about",
+            command: "/about".into(),
             description: "Show app information",
         },
         CommandMenuItem {
-            command: "/latest",
+            command: "/help".into(),
+            description: "List every command with a description",
+        },
+        CommandMenuItem {
+            command: "/re".into(),
+            description: "Regex search on file names (example /re ^main\\.rs$)",
+        },
+        CommandMenuItem {
+            command: "/sort".into(),
+            description: "Cycle result order (example /sort size desc)",
+        },
+        CommandMenuItem {
+            command: "/sort name".into(),
+            description: "Sort results by file name",
+        },
+        CommandMenuItem {
+            command: "/sort path".into(),
+            description: "Sort results by full path",
+        },
+        CommandMenuItem {
+            command: "/sort date".into(),
+            description: "Sort results by modified date",
+        },
+        CommandMenuItem {
+            command: "/sort size".into(),
+            description: "Sort results by file size",
+        },
+        CommandMenuItem {
+            command: "/sort relevance".into(),
+            description: "Restore match order",
+        },
+        CommandMenuItem {
+            command: "/hotkey".into(),
+            description: "Rebind show/hide hotkey (example /hotkey Ctrl+Space)",
+        },
+        CommandMenuItem {
+            command: "/theme".into(),
+            description: "Cycle color theme (example /theme gruvbox)",
+        },
+        CommandMenuItem {
+            command: "/theme default".into(),
+            description: "Default dark theme",
+        },
+        CommandMenuItem {
+            command: "/theme solarized-dark".into(),
+            description: "Solarized dark theme",
+        },
+        CommandMenuItem {
+            command: "/theme gruvbox".into(),
+            description: "Gruvbox dark theme",
+        },
+        CommandMenuItem {
+            command: "/theme high-contrast".into(),
+            description: "High-contrast theme",
+        },
+        CommandMenuItem {
+            command: "/startup".into(),
+            description: "Show whether the app launches at logon",
+        },
+        CommandMenuItem {
+            command: "/startup on".into(),
+            description: "Launch hidden at Windows logon",
+        },
+        CommandMenuItem {
+            command: "/startup off".into(),
+            description: "Do not launch at Windows logon",
+        },
+        CommandMenuItem {
+            command: "/latest".into(),
             description: "Recent changes (/latest 30sec)",
         },
         CommandMenuItem {
-            command: "/last",
+            command: "/feed".into(),
+            description: "Watch live changes as they arrive, newest first",
+        },
+        CommandMenuItem {
+            command: "/last".into(),
             description: "Alias for /latest",
         },
         CommandMenuItem {
-            command: "/reindex",
+            command: "/history".into(),
+            description: "Pick a recent search (ArrowUp recalls them too)",
+        },
+        CommandMenuItem {
+            command: "/pin".into(),
+            description: "Pin or unpin the selected result (Ctrl+P)",
+        },
+        CommandMenuItem {
+            command: "/pins".into(),
+            description: "Pick a pinned file or folder to open",
+        },
+        CommandMenuItem {
+            command: "/term".into(),
+            description: "Open a terminal in the selected result's folder (Ctrl+T)",
+        },
+        CommandMenuItem {
+            command: "/undo".into(),
+            description: "Restore the last recycled items (Ctrl+Z)",
+        },
+        CommandMenuItem {
+            command: "/reindex".into(),
             description: "Reindex current scope now",
         },
         CommandMenuItem {
-            command: "/exit",
+            command: "/clearcache".into(),
+            description: "Delete index snapshots and reindex",
+        },
+        CommandMenuItem {
+            command: "/exclude".into(),
+            description: "Skip matching paths when indexing (example /exclude *\\node_modules)",
+        },
+        CommandMenuItem {
+            command: "/gitignore".into(),
+            description: "Show whether folder walks honor .gitignore",
+        },
+        CommandMenuItem {
+            command: "/gitignore on".into(),
+            description: "Skip git-ignored files when walking folders",
+        },
+        CommandMenuItem {
+            command: "/gitignore off".into(),
+            description: "Index git-ignored files when walking folders",
+        },
+        CommandMenuItem {
+            command: "/hidden".into(),
+            description: "Show whether hidden and system files are indexed",
+        },
+        CommandMenuItem {
+            command: "/hidden on".into(),
+            description: "Index hidden and system files such as desktop.ini",
+        },
+        CommandMenuItem {
+            command: "/hidden off".into(),
+            description: "Leave hidden and system files out of the index",
+        },
+        CommandMenuItem {
+            command: "/set limit".into(),
+            description: "Most results per search, 50 to 50000 (example /set limit 2000)",
+        },
+        CommandMenuItem {
+            command: "/set debounce".into(),
+            description: "Milliseconds to wait after typing before searching",
+        },
+        CommandMenuItem {
+            command: "/set batch".into(),
+            description: "Items scanned between checks for a newer query",
+        },
+        CommandMenuItem {
+            command: "/set minlen".into(),
+            description: "Characters needed before a search scans, 1 to 3",
+        },
+        CommandMenuItem {
+            command: "/set perfolder".into(),
+            description: "Most results per folder while /diverse is on",
+        },
+        CommandMenuItem {
+            command: "/set autohide".into(),
+            description: "Hide the panel after this many idle seconds, 0 for never",
+        },
+        CommandMenuItem {
+            command: "/set margin".into(),
+            description: "Rows kept visible above and below the selection, 0 to 20",
+        },
+        CommandMenuItem {
+            command: "/memlimit".into(),
+            description:
+                "Warn when the index passes this many MB, 0 for never (example /memlimit 4096)",
+        },
+        CommandMenuItem {
+            command: "/export".into(),
+            description: "Save all results as .txt, .csv or .json (example /export D:\\hits.csv)",
+        },
+        CommandMenuItem {
+            command: "/goto".into(),
+            description: "Select the first result whose path contains text (example /goto src\\ui)",
+        },
+        CommandMenuItem {
+            command: "/copyall".into(),
+            description: "Copy the paths of all results, one per line",
+        },
+        CommandMenuItem {
+            command: "/openwith".into(),
+            description: "Open the selected file in a program (example /openwith notepad)",
+        },
+        CommandMenuItem {
+            command: "/exit".into(),
             description: "Exit app immediately",
         },
     ];
@@ -250,26 +998,116 @@ pub(crate) fn command_menu_items(input: &str, tracking_enabled: bool) -> Vec<Com
     items
         .into_iter()
         .filter(|item| {
-            if !tracking_enabled && (item.command == "/latest" || item.command == "/last") {
+            if !tracking_enabled
+                && (item.command == "/latest" || item.command == "/last" || item.command == "/feed")
+            {
                 return false;
             }
             true
         })
-        .filter(|item| {
-            if prefix == "/" {
-                return true;
-            }
-
-            item.command.to_ascii_lowercase().starts_with(&prefix)
-                || (prefix.len() == 3
-                    && prefix.starts_with('/')
-                    && prefix.ends_with(':')
-                    && prefix.as_bytes()[1].is_ascii_alphabetic()
-                    && item.command == "/x:")
-        })
         .collect()
 }
 
+/// Lists all commands once `/help` has been run (the input is then
+/// `/help `), optionally narrowed by text after it that appears in a
+/// command or its description.
+pub(crate) fn help_menu_items(input: &str, tracking_enabled: bool) -> Option<Vec<CommandMenuItem>> {
+    let rest = input.trim_start();
+    let command = rest.get(..5)?;
+    if !command.eq_ignore_ascii_case("/help") || !rest[5..].starts_with(char::is_whitespace) {
+        return None;
+    }
+    let filter = rest[5..].trim().to_lowercase();
+
+    Some(
+        command_catalog(tracking_enabled)
+            .into_iter()
+            .filter(|item| item.command != "/help")
+            .filter(|item| {
+                item.command.to_lowercase().contains(&filter)
+                    || item.description.to_lowercase().contains(&filter)
+            })
+            .collect(),
+    )
+}
+
+/// The lowercased filter typed after a listing command such as `/history`,
+/// or `None` when `input` is not that command.
+fn listing_filter(input: &str, command: &str) -> Option<String> {
+    let rest = input.trim_start();
+    let typed = rest.get(..command.len())?;
+    if !typed.eq_ignore_ascii_case(command) {
+        return None;
+    }
+    let filter = rest[command.len()..].trim().to_lowercase();
+    if !filter.is_empty() && !rest[command.len()..].starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(filter)
+}
+
+/// Lists recent searches while the input is `/history`, optionally narrowed
+/// by the text after it. Returns `None` otherwise or when history is empty.
+pub(crate) fn history_menu_items(
+    input: &str,
+    history: &VecDeque<String>,
+) -> Option<Vec<CommandMenuItem>> {
+    let filter = listing_filter(input, "/history").filter(|_| !history.is_empty())?;
+
+    Some(
+        history
+            .iter()
+            .filter(|entry| entry.to_lowercase().contains(&filter))
+            .map(|entry| CommandMenuItem {
+                command: entry.clone().into(),
+                description: "Recent search",
+            })
+            .collect(),
+    )
+}
+
+/// Drives to pick from under `/drive`, narrowed by the letter typed after it.
+/// `roots` are drive roots such as `D:\`.
+pub(crate) fn drive_menu_items(input: &str, roots: &[String]) -> Option<Vec<CommandMenuItem>> {
+    let filter = listing_filter(input, "/drive")?;
+
+    Some(
+        roots
+            .iter()
+            .filter_map(|root| parse_drive_letter(&root.to_ascii_lowercase()))
+            .filter(|letter| filter.is_empty() || parse_drive_letter(&filter) == Some(*letter))
+            .map(|letter| CommandMenuItem {
+                command: format!("/drive {}", letter).into(),
+                description: "Search this drive",
+            })
+            .collect(),
+    )
+}
+
+/// Pinned paths under `/pins`, filtered like `/history`. The command of each
+/// item is the full path.
+pub(crate) fn pins_menu_items(
+    input: &str,
+    pins: &[String],
+    is_missing: impl Fn(&str) -> bool,
+) -> Option<Vec<CommandMenuItem>> {
+    let filter = listing_filter(input, "/pins").filter(|_| !pins.is_empty())?;
+
+    Some(
+        pins.iter()
+            .filter(|pin| pin.to_lowercase().contains(&filter))
+            .map(|pin| CommandMenuItem {
+                command: pin.clone().into(),
+                description: if is_missing(pin) {
+                    "Pinned (missing)"
+                } else {
+                    "Pinned"
+                },
+            })
+            .collect(),
+    )
+}
+
 pub(crate) fn apply_command_choice(raw_query: &str, command: &str) -> String {
     let trimmed = raw_query.trim_start();
     let mut parts = trimmed.split_whitespace();
@@ -283,6 +1121,13 @@ pub(crate) fn apply_command_choice(raw_query: &str, command: &str) -> String {
     }
 }
 
+/// Accepts `d`, `d:` or `d:\`.
+fn parse_drive_letter(token: &str) -> Option<char> {
+    let mut chars = token.chars();
+    let letter = chars.next().filter(char::is_ascii_alphabetic)?;
+    matches!(chars.as_str(), "" | ":" | ":\\" | ":/").then(|| letter.to_ascii_uppercase())
+}
+
 fn parse_drive_directive(token: &str) -> Option<char> {
     let bytes = token.as_bytes();
     if bytes.len() == 3 && bytes[0] == b'/' && bytes[2] == b':' && bytes[1].is_ascii_alphabetic() {
@@ -292,7 +1137,7 @@ fn parse_drive_directive(token: &str) -> Option<char> {
     }
 }
 
-fn parse_latest_window_token(token: &str) -> Option<i64> {
+pub(crate) fn parse_latest_window_token(token: &str) -> Option<i64> {
     let trimmed = token.trim().to_ascii_lowercase();
     if trimmed.is_empty() {
         return None;
@@ -344,12 +1189,43 @@ pub(crate) fn is_exact_directive_token(token: &str, tracking_enabled: bool) -> b
             | "/up"
             | "/track"
             | "/reindex"
+            | "/clearcache"
+            | "/exclude"
+            | "/gitignore"
+            | "/hidden"
+            | "/set"
+            | "/memlimit"
             | "/fullscreen"
             | "/fullheight"
             | "/rows"
+            | "/fontsize"
+            | "/scope"
+            | "/drive"
             | "/gpu"
             | "/soft"
             | "/about"
+            | "/sort"
+            | "/re"
+            | "/hotkey"
+            | "/theme"
+            | "/startup"
+            | "/dirs"
+            | "/fullpath"
+            | "/fold"
+            | "/density"
+            | "/diverse"
+            | "/dedup"
+            | "/preview"
+            | "/help"
+            | "/pin"
+            | "/pins"
+            | "/term"
+            | "/feed"
+            | "/undo"
+            | "/export"
+            | "/copyall"
+            | "/goto"
+            | "/openwith"
             | "/exit"
     ) || parse_drive_directive(token).is_some();
 
@@ -392,4 +1268,301 @@ mod tests {
         assert!(parsed.result_rows_directive);
         assert_eq!(parsed.result_rows, Some(32));
     }
+
+    #[test]
+    fn parses_drive_directive() {
+        let parsed = parse_scope_directive("/drive d:");
+        assert!(parsed.drive_directive);
+        assert_eq!(parsed.drive_letter, Some('D'));
+        assert_eq!(parse_scope_directive("/drive e:\\").drive_letter, Some('E'));
+        assert_eq!(parse_scope_directive("/drive data").drive_letter, None);
+
+        let roots = vec!["C:\\".to_string(), "D:\\".to_string()];
+        let items = drive_menu_items("/drive", &roots).unwrap();
+        assert_eq!(items.len(), 2);
+        let items = drive_menu_items("/DRIVE d", &roots).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].command, "/drive D");
+        assert!(drive_menu_items("/drives", &roots).is_none());
+    }
+
+    #[test]
+    fn parses_scope_cycle() {
+        let parsed = parse_scope_directive("/scope NEXT");
+        assert!(parsed.scope_directive);
+        assert!(parsed.cycle_scope);
+        assert!(!parse_scope_directive("/scope").cycle_scope);
+        assert!(is_exact_directive_token("/scope", true));
+    }
+
+    #[test]
+    fn parses_font_size() {
+        let parsed = parse_scope_directive("/FontSize 150%");
+        assert!(parsed.font_size_directive);
+        assert_eq!(parsed.font_size_percent, Some(150));
+        assert_eq!(parse_scope_directive("/fontsize").font_size_percent, None);
+    }
+
+    #[test]
+    fn parses_hotkey_combo_preserving_case() {
+        let parsed = parse_scope_directive("/hotkey Ctrl+Space");
+        assert!(parsed.hotkey_directive);
+        assert_eq!(parsed.hotkey.as_deref(), Some("Ctrl+Space"));
+        assert!(parsed.clean_query.is_empty());
+
+        let parsed = parse_scope_directive("/hotkey");
+        assert!(parsed.hotkey_directive);
+        assert_eq!(parsed.hotkey, None);
+    }
+
+    #[test]
+    fn parses_theme_name() {
+        let parsed = parse_scope_directive("/theme Gruvbox");
+        assert!(parsed.theme_directive);
+        assert_eq!(parsed.theme.as_deref(), Some("gruvbox"));
+
+        let parsed = parse_scope_directive("/theme");
+        assert!(parsed.theme_directive);
+        assert_eq!(parsed.theme, None);
+        assert_eq!(command_menu_items("/theme", true).len(), 5);
+    }
+
+    #[test]
+    fn parses_clear_cache() {
+        let parsed = parse_scope_directive("/clearcache");
+        assert!(parsed.clear_cache);
+        assert!(!parsed.reindex_current_scope);
+        assert!(is_exact_directive_token("/ClearCache", true));
+    }
+
+    #[test]
+    fn parses_exclude_pattern_preserving_case() {
+        let parsed = parse_scope_directive("/exclude *\\Node_Modules");
+        assert!(parsed.exclude_directive);
+        assert_eq!(parsed.exclude_pattern.as_deref(), Some("*\\Node_Modules"));
+        assert!(parsed.clean_query.is_empty());
+
+        let parsed = parse_scope_directive("/exclude /tmp/*");
+        assert_eq!(parsed.exclude_pattern.as_deref(), Some("/tmp/*"));
+
+        let parsed = parse_scope_directive("/exclude");
+        assert!(parsed.exclude_directive);
+        assert_eq!(parsed.exclude_pattern, None);
+        assert!(command_menu_items("/exclude *.tmp", true).is_empty());
+    }
+
+    #[test]
+    fn lists_history_under_the_history_command() {
+        let history: VecDeque<String> = ["invoice pdf", "notes", "Invoice 2024"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert!(parse_scope_directive("/history").show_history);
+
+        let items = history_menu_items("/history", &history).unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].command, "invoice pdf");
+
+        let items = history_menu_items("/History invoice", &history).unwrap();
+        let commands: Vec<&str> = items.iter().map(|item| item.command.as_ref()).collect();
+        assert_eq!(commands, ["invoice pdf", "Invoice 2024"]);
+
+        assert!(history_menu_items("/historyx", &history).is_none());
+        assert!(history_menu_items("/his", &history).is_none());
+        assert!(history_menu_items("/history", &VecDeque::new()).is_none());
+    }
+
+    #[test]
+    fn lists_pins_under_the_pins_command() {
+        assert!(parse_scope_directive("/pin").toggle_pin);
+        assert!(parse_scope_directive("/undo").undo_recycle);
+        assert!(parse_scope_directive("/TERM").open_terminal);
+        assert!(parse_scope_directive("/PINS").show_pins);
+
+        let pins = vec![r"C:\notes\todo.txt".to_string(), r"D:\gone.txt".to_string()];
+        let items = pins_menu_items("/pins", &pins, |pin| pin.starts_with('D')).unwrap();
+        let descriptions: Vec<&str> = items.iter().map(|item| item.description).collect();
+        assert_eq!(descriptions, ["Pinned", "Pinned (missing)"]);
+
+        let items = pins_menu_items("/pins todo", &pins, |_| false).unwrap();
+        assert_eq!(items.len(), 1);
+        assert!(pins_menu_items("/pin", &pins, |_| false).is_none());
+        assert!(pins_menu_items("/pins", &[], |_| false).is_none());
+    }
+
+    #[test]
+    fn help_lists_the_whole_catalog() {
+        assert!(parse_scope_directive("/help").show_help);
+        assert!(help_menu_items("/help", true).is_none());
+        assert!(help_menu_items("/helpme ", true).is_none());
+
+        let all = help_menu_items("/help ", true).unwrap();
+        let catalog = command_catalog(true);
+        assert_eq!(all.len(), catalog.len() - 1);
+        assert!(all.iter().all(|item| item.command != "/help"));
+        assert!(all.iter().any(|item| item.command == "/latest"));
+        assert!(!help_menu_items("/help ", false)
+            .unwrap()
+            .iter()
+            .any(|item| item.command == "/latest" || item.command == "/feed"));
+        assert!(parse_scope_directive("/feed").toggle_feed);
+
+        let filtered = help_menu_items("/HELP  Theme", true).unwrap();
+        assert!(!filtered.is_empty());
+        assert!(filtered
+            .iter()
+            .all(|item| item.command.starts_with("/theme")));
+
+        assert_eq!(command_menu_items("/he", true)[0].command, "/help");
+    }
+
+    #[test]
+    fn suggests_commands_for_typos() {
+        for typo in ["/reind", "/reidx", "/reindx", "/realnd", "/REINDX"] {
+            let items = command_menu_items(typo, true);
+            assert_eq!(items[0].command, "/reindex", "{}", typo);
+            assert!(!is_exact_directive_token(typo, true));
+        }
+
+        let items = command_menu_items("/sotr", true);
+        assert_eq!(items[0].command, "/sort");
+        assert!(items.iter().any(|item| item.command == "/sort size"));
+
+        let items = command_menu_items("/the", true);
+        assert!(items
+            .iter()
+            .take(5)
+            .all(|item| item.command.starts_with("/theme")));
+
+        assert!(command_menu_items("/zzzzzz", true).is_empty());
+        assert!(!command_matches_typed("/exti", "/exit"));
+        assert!(!command_matches_typed("/clearcache", "/clear"));
+        assert!(command_matches_typed("/REI", "/reindex"));
+        assert!(command_matches_typed("/d:", "/x:"));
+        assert_eq!(prefix_edit_distance("reidx", "reindex"), 2);
+        assert_eq!(prefix_edit_distance("eixt", "exit"), 1);
+    }
+
+    #[test]
+    fn parses_export_path_with_spaces() {
+        let parsed = parse_scope_directive(r#"/export "C:\My Files\Hits.CSV""#);
+        assert!(parsed.export_directive);
+        assert_eq!(parsed.export_path.as_deref(), Some(r"C:\My Files\Hits.CSV"));
+        assert!(parsed.clean_query.is_empty());
+
+        let parsed = parse_scope_directive("/export");
+        assert!(parsed.export_directive);
+        assert!(parsed.export_path.is_none());
+        assert!(command_menu_items("/export D:\\out.txt", true).is_empty());
+        assert!(parse_scope_directive("/CopyAll").copy_all);
+
+        let parsed = parse_scope_directive("/goto My Documents\\Tax");
+        assert!(parsed.goto_directive);
+        assert_eq!(parsed.goto_target.as_deref(), Some("My Documents\\Tax"));
+        assert!(parsed.clean_query.is_empty());
+        assert!(parse_scope_directive("/goto").goto_target.is_none());
+
+        let parsed =
+            parse_scope_directive(r#"/openwith "C:\Program Files\Notepad++\notepad++.exe""#);
+        assert!(parsed.openwith_directive);
+        assert_eq!(
+            parsed.openwith_program.as_deref(),
+            Some(r"C:\Program Files\Notepad++\notepad++.exe")
+        );
+    }
+
+    #[test]
+    fn parses_gitignore_toggle() {
+        let parsed = parse_scope_directive("/gitignore ON");
+        assert!(parsed.gitignore_directive);
+        assert_eq!(parsed.respect_gitignore, Some(true));
+
+        let parsed = parse_scope_directive("/gitignore");
+        assert!(parsed.gitignore_directive);
+        assert_eq!(parsed.respect_gitignore, None);
+        assert_eq!(command_menu_items("/gitignore", true).len(), 3);
+    }
+
+    #[test]
+    fn parses_hidden_toggle() {
+        let parsed = parse_scope_directive("/hidden off");
+        assert!(parsed.hidden_directive);
+        assert_eq!(parsed.index_hidden, Some(false));
+
+        let parsed = parse_scope_directive("/Hidden");
+        assert!(parsed.hidden_directive);
+        assert_eq!(parsed.index_hidden, None);
+        assert_eq!(command_menu_items("/hidden", true).len(), 3);
+    }
+
+    #[test]
+    fn parses_set_directive() {
+        let parsed = parse_scope_directive("/set LIMIT 2000");
+        assert!(parsed.set_directive);
+        assert_eq!(parsed.set_key.as_deref(), Some("limit"));
+        assert_eq!(parsed.set_value, Some(2000));
+
+        let parsed = parse_scope_directive("/set debounce fast");
+        assert_eq!(parsed.set_key.as_deref(), Some("debounce"));
+        assert_eq!(parsed.set_value, None);
+        assert!(is_exact_directive_token("/set", true));
+
+        let parsed = parse_scope_directive("/memlimit 4096");
+        assert!(parsed.set_directive);
+        assert_eq!(parsed.set_key.as_deref(), Some("memlimit"));
+        assert_eq!(parsed.set_value, Some(4096));
+        assert!(is_exact_directive_token("/MEMLIMIT", true));
+    }
+
+    #[test]
+    fn parses_dirs_toggle() {
+        assert!(parse_scope_directive("/DIRS").toggle_dirs);
+        assert!(is_exact_directive_token("/dirs", true));
+        assert!(!parse_scope_directive("dirs").toggle_dirs);
+        assert!(parse_scope_directive("/fullpath").toggle_full_path);
+        assert!(parse_scope_directive("/FOLD").toggle_fold);
+        assert!(parse_scope_directive("/density").toggle_density);
+        assert!(parse_scope_directive("/Diverse").toggle_diverse);
+        assert!(parse_scope_directive("/dedup").toggle_dedup);
+        assert!(parse_scope_directive("/Preview").toggle_preview);
+    }
+
+    #[test]
+    fn parses_startup_toggle() {
+        assert_eq!(
+            parse_scope_directive("/startup ON").launch_at_startup,
+            Some(true)
+        );
+        assert_eq!(
+            parse_scope_directive("/startup off").launch_at_startup,
+            Some(false)
+        );
+
+        let parsed = parse_scope_directive("/startup");
+        assert!(parsed.startup_directive);
+        assert_eq!(parsed.launch_at_startup, None);
+    }
+
+    #[test]
+    fn parses_regex_pattern_with_original_case() {
+        let parsed = parse_scope_directive("/re ^Main\\.rs$");
+        assert!(parsed.regex_mode);
+        assert_eq!(parsed.clean_query, "^Main\\.rs$");
+        assert!(command_menu_items("/re ^Main", true).is_empty());
+        assert!(!command_menu_items("/re", true).is_empty());
+    }
+
+    #[test]
+    fn parses_sort_mode_and_direction() {
+        let parsed = parse_scope_directive("/sort size desc report");
+        assert!(parsed.sort_directive);
+        assert_eq!(parsed.sort_mode, Some(SortMode::Size));
+        assert_eq!(parsed.sort_descending, Some(true));
+        assert_eq!(parsed.clean_query, "report");
+
+        let parsed = parse_scope_directive("/sort");
+        assert!(parsed.sort_directive);
+        assert_eq!(parsed.sort_mode, None);
+        assert_eq!(parsed.sort_descending, None);
+    }
 }
@@ -0,0 +1,63 @@
+use crate::search::glob_matches;
+
+/// A path exclusion such as `*\node_modules`. A glob that matches a folder
+/// also excludes everything beneath it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Glob {
+    pattern: String,
+    subtree: String,
+}
+
+impl Glob {
+    pub(crate) fn new(pattern: &str) -> Self {
+        let pattern = pattern.trim().replace('/', "\\");
+        let pattern = pattern.trim_end_matches('\\').to_string();
+        let subtree = format!("{}\\*", pattern);
+        Self { pattern, subtree }
+    }
+
+    pub(crate) fn matches(&self, path: &str) -> bool {
+        glob_matches(&self.pattern, path) || glob_matches(&self.subtree, path)
+    }
+}
+
+pub(crate) fn compile_globs(patterns: &[String]) -> Vec<Glob> {
+    patterns
+        .iter()
+        .filter(|pattern| !pattern.trim().is_empty())
+        .map(|pattern| Glob::new(pattern))
+        .collect()
+}
+
+pub(crate) fn is_excluded(path: &str, globs: &[Glob]) -> bool {
+    globs.iter().any(|glob| glob.matches(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excludes_matching_folders_and_their_contents() {
+        let globs = compile_globs(&[
+            "*\\$Recycle.Bin".to_string(),
+            "*/node_modules/".to_string(),
+            "D:\\Temp\\*.log".to_string(),
+            "  ".to_string(),
+        ]);
+        assert_eq!(globs.len(), 3);
+
+        assert!(is_excluded(r"C:\$RECYCLE.BIN", &globs));
+        assert!(is_excluded(r"C:\$Recycle.Bin\S-1-5-21\$R1.txt", &globs));
+        assert!(is_excluded(
+            r"D:\src\app\Node_Modules\left-pad\index.js",
+            &globs
+        ));
+        assert!(is_excluded(r"d:\temp\build.LOG", &globs));
+
+        assert!(!is_excluded(r"C:\Users\me\recycle.bin.txt", &globs));
+        assert!(!is_excluded(r"D:\src\app\node_modules_backup", &globs));
+        assert!(!is_excluded(r"D:\Temp\build.log.old", &globs));
+        assert!(!is_excluded(r"C:\anything", &[]));
+    }
+}
@@ -0,0 +1,159 @@
+use std::env;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::cli::CliItem;
+use crate::search::{civil_from_days, format_modified};
+use crate::{SearchItem, UNKNOWN_SIZE, UNKNOWN_TS};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Text,
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// Picks the format from the extension; a path without one is plain text.
+    fn from_path(path: &Path) -> Result<Self, String> {
+        let Some(ext) = path.extension() else {
+            return Ok(Self::Text);
+        };
+        match ext.to_string_lossy().to_ascii_lowercase().as_str() {
+            "txt" => Ok(Self::Text),
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "unsupported export format .{} (use .txt, .csv, or .json)",
+                other
+            )),
+        }
+    }
+}
+
+/// `Documents\rustsearch-export-YYYYMMDD-HHMMSS.txt` under the user profile.
+pub(crate) fn default_export_path(now_unix_secs: i64) -> PathBuf {
+    let base = env::var("USERPROFILE").unwrap_or_else(|_| ".".to_string());
+    let (year, month, day) = civil_from_days(now_unix_secs.div_euclid(86_400));
+    let secs_of_day = now_unix_secs.rem_euclid(86_400);
+    PathBuf::from(base).join("Documents").join(format!(
+        "rustsearch-export-{:04}{:02}{:02}-{:02}{:02}{:02}.txt",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    ))
+}
+
+/// Writes `items` to `path` in the format named by its extension and
+/// returns how many were written.
+pub(crate) fn write_export(path: &Path, items: &[SearchItem]) -> Result<usize, String> {
+    let format = ExportFormat::from_path(path)?;
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+
+    let file = File::create(path).map_err(|err| err.to_string())?;
+    let mut out = BufWriter::new(file);
+    let written = match format {
+        ExportFormat::Text => items
+            .iter()
+            .try_for_each(|item| writeln!(out, "{}", item.full_path())),
+        ExportFormat::Csv => write_csv(&mut out, items),
+        ExportFormat::Json => {
+            let items: Vec<CliItem> = items.iter().map(CliItem::from).collect();
+            serde_json::to_writer_pretty(&mut out, &items)
+                .map_err(std::io::Error::from)
+                .and_then(|_| writeln!(out))
+        }
+    };
+
+    written
+        .and_then(|_| out.flush())
+        .map(|_| items.len())
+        .map_err(|err| err.to_string())
+}
+
+fn write_csv(out: &mut impl Write, items: &[SearchItem]) -> std::io::Result<()> {
+    writeln!(out, "path,name,size,modified")?;
+    for item in items {
+        let size = if item.size == UNKNOWN_SIZE {
+            String::new()
+        } else {
+            item.size.to_string()
+        };
+        let modified = if item.modified_unix_secs == UNKNOWN_TS {
+            String::new()
+        } else {
+            format_modified(item.modified_unix_secs)
+        };
+        writeln!(
+            out,
+            "{},{},{},{}",
+            csv_field(&item.full_path()),
+            csv_field(&item.name),
+            size,
+            modified
+        )?;
+    }
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SearchItemKind;
+
+    #[test]
+    fn writes_each_format_by_extension() {
+        let dir = std::env::temp_dir().join(format!("rustsearch-export-{}", std::process::id()));
+        let items = vec![
+            SearchItem::new(r"C:\docs\a, b.txt", 86_400, 12, SearchItemKind::File),
+            SearchItem::new(
+                r"C:\docs\notes",
+                UNKNOWN_TS,
+                UNKNOWN_SIZE,
+                SearchItemKind::Folder,
+            ),
+        ];
+
+        assert_eq!(write_export(&dir.join("out.txt"), &items), Ok(2));
+        let text = std::fs::read_to_string(dir.join("out.txt")).unwrap();
+        assert_eq!(text, "C:\\docs\\a, b.txt\nC:\\docs\\notes\n");
+
+        assert_eq!(write_export(&dir.join("out.CSV"), &items), Ok(2));
+        let csv = std::fs::read_to_string(dir.join("out.CSV")).unwrap();
+        assert_eq!(
+            csv,
+            "path,name,size,modified\n\
+             \"C:\\docs\\a, b.txt\",\"a, b.txt\",12,1970-01-02 00:00\n\
+             C:\\docs\\notes,notes,,\n"
+        );
+
+        assert_eq!(write_export(&dir.join("out.json"), &items), Ok(2));
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("out.json")).unwrap()).unwrap();
+        assert_eq!(json[0]["size"], 12);
+        assert!(json[1]["modified_unix_secs"].is_null());
+
+        assert!(write_export(&dir.join("out.xlsx"), &items).is_err());
+        assert!(default_export_path(0)
+            .ends_with(Path::new("Documents").join("rustsearch-export-19700101-000000.txt")));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
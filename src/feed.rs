@@ -0,0 +1,179 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Most changes `/feed` keeps; older ones fall off the end.
+pub(crate) const FEED_CAPACITY: usize = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChangeKind {
+    Created,
+    Modified,
+    Renamed,
+    Deleted,
+}
+
+impl ChangeKind {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Modified => "modified",
+            Self::Renamed => "renamed",
+            Self::Deleted => "deleted",
+        }
+    }
+
+    /// What several changes to one path in a batch add up to: a file created
+    /// and then written is still new, and a renamed one that was written is
+    /// still renamed.
+    pub(crate) fn merge(self, later: ChangeKind) -> ChangeKind {
+        let weight = |kind: ChangeKind| match kind {
+            Self::Modified => 0,
+            Self::Renamed => 1,
+            Self::Created => 2,
+            Self::Deleted => 3,
+        };
+        if weight(later) > weight(self) {
+            later
+        } else {
+            self
+        }
+    }
+}
+
+/// The last change seen for a path while tracking is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RecentEvent {
+    pub(crate) at_unix_secs: i64,
+    pub(crate) kind: ChangeKind,
+}
+
+pub(crate) type RecentEvents = HashMap<Box<str>, RecentEvent>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FeedEntry {
+    pub(crate) path: Box<str>,
+    pub(crate) kind: ChangeKind,
+    /// When the change reached the app, not the file's modification time.
+    pub(crate) at_unix_secs: i64,
+}
+
+/// Live index changes in arrival order, bounded to `FEED_CAPACITY`. The
+/// counts cover every change since the feed was cleared, including dropped ones.
+#[derive(Debug, Default)]
+pub(crate) struct ChangeFeed {
+    entries: VecDeque<FeedEntry>,
+    pub(crate) created: usize,
+    pub(crate) modified: usize,
+    pub(crate) renamed: usize,
+    pub(crate) deleted: usize,
+}
+
+impl ChangeFeed {
+    pub(crate) fn push(&mut self, path: impl Into<Box<str>>, kind: ChangeKind, at_unix_secs: i64) {
+        match kind {
+            ChangeKind::Created => self.created += 1,
+            ChangeKind::Modified => self.modified += 1,
+            ChangeKind::Renamed => self.renamed += 1,
+            ChangeKind::Deleted => self.deleted += 1,
+        }
+        if self.entries.len() == FEED_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(FeedEntry {
+            path: path.into(),
+            kind,
+            at_unix_secs,
+        });
+    }
+
+    /// Newest first.
+    pub(crate) fn newest(&self) -> impl Iterator<Item = &FeedEntry> {
+        self.entries.iter().rev()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub(crate) fn counts_label(&self) -> String {
+        format!(
+            "+{} created  ~{} modified  >{} renamed  -{} deleted",
+            self.created, self.modified, self.renamed, self.deleted
+        )
+    }
+}
+
+/// `HH:MM:SS` in UTC, like the other times the app shows.
+pub(crate) fn format_feed_time(unix_secs: i64) -> String {
+    let secs_of_day = unix_secs.rem_euclid(86_400);
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_newest_changes_and_counts_them_all() {
+        let mut feed = ChangeFeed::default();
+        for index in 0..FEED_CAPACITY + 5 {
+            let kind = if index % 2 == 0 {
+                ChangeKind::Created
+            } else {
+                ChangeKind::Modified
+            };
+            feed.push(format!("C:\\f{}", index), kind, index as i64);
+        }
+        feed.push("C:\\gone", ChangeKind::Deleted, 0);
+        feed.push("C:\\moved", ChangeKind::Renamed, 0);
+
+        assert_eq!(feed.len(), FEED_CAPACITY);
+        let newest: Vec<&str> = feed
+            .newest()
+            .take(2)
+            .map(|entry| entry.path.as_ref())
+            .collect();
+        assert_eq!(newest, ["C:\\moved", "C:\\gone"]);
+        assert_eq!(
+            (feed.created, feed.modified, feed.renamed, feed.deleted),
+            (503, 502, 1, 1)
+        );
+
+        feed.clear();
+        assert!(feed.is_empty());
+        assert_eq!(
+            feed.counts_label(),
+            "+0 created  ~0 modified  >0 renamed  -0 deleted"
+        );
+    }
+
+    #[test]
+    fn merged_changes_keep_the_most_telling_kind() {
+        use ChangeKind::*;
+        assert_eq!(Created.merge(Modified), Created);
+        assert_eq!(Modified.merge(Created), Created);
+        assert_eq!(Modified.merge(Renamed), Renamed);
+        assert_eq!(Renamed.merge(Modified), Renamed);
+        assert_eq!(Modified.merge(Modified), Modified);
+    }
+
+    #[test]
+    fn formats_feed_times_in_utc() {
+        assert_eq!(
+            format_feed_time(86_400 + 13 * 3600 + 5 * 60 + 9),
+            "13:05:09"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// Skips entries listed in `.gitignore` and `.ignore` files during a directory
+/// walk. Ignore files are read once per folder, including folders above the
+/// walk root, and deeper files override shallower ones as in git. Matching
+/// ignores case, as Windows does.
+#[derive(Default)]
+pub(crate) struct GitignoreFilter {
+    rules_by_dir: HashMap<PathBuf, Option<Gitignore>>,
+}
+
+impl GitignoreFilter {
+    pub(crate) fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        let Some(name) = path.file_name() else {
+            return false;
+        };
+        if is_dir && name == ".git" {
+            return true;
+        }
+
+        let mut ancestors: Vec<&Path> = path.ancestors().skip(1).collect();
+        ancestors.reverse();
+
+        let mut ignored = false;
+        for dir in ancestors {
+            let rules = self
+                .rules_by_dir
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| load_rules(dir));
+            let Some(rules) = rules else {
+                continue;
+            };
+
+            let matched = rules.matched(path, is_dir);
+            if matched.is_ignore() {
+                ignored = true;
+            } else if matched.is_whitelist() {
+                ignored = false;
+            }
+        }
+
+        ignored
+    }
+}
+
+/// The rules of the ignore files in `dir`, or `None` when it has none. Lines
+/// that fail to parse are skipped and the rest still apply.
+fn load_rules(dir: &Path) -> Option<Gitignore> {
+    let mut builder = GitignoreBuilder::new(dir);
+    builder.case_insensitive(true).ok()?;
+    for file in IGNORE_FILES {
+        let path = dir.join(file);
+        if path.is_file() {
+            let _ = builder.add(path);
+        }
+    }
+    builder.build().ok().filter(|rules| !rules.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_nested_ignore_files() {
+        let root =
+            std::env::temp_dir().join(format!("rustsearch-gitignore-{}", std::process::id()));
+        let sub = root.join("sub");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::write(
+            root.join(".gitignore"),
+            "target/\n*.log\n!keep.log\n/docs/*.pdf\n",
+        )
+        .unwrap();
+        std::fs::write(sub.join(".ignore"), "secret.txt\n!debug.log\n").unwrap();
+
+        let mut filter = GitignoreFilter::default();
+        assert!(filter.is_ignored(&root.join(".git"), true));
+        assert!(filter.is_ignored(&root.join("target"), true));
+        assert!(!filter.is_ignored(&root.join("target"), false));
+        assert!(filter.is_ignored(&root.join("build.LOG"), false));
+        assert!(!filter.is_ignored(&root.join("keep.log"), false));
+        assert!(filter.is_ignored(&root.join("docs").join("guide.pdf"), false));
+        assert!(!filter.is_ignored(&sub.join("docs").join("guide.pdf"), false));
+        assert!(filter.is_ignored(&sub.join("secret.txt"), false));
+        assert!(!filter.is_ignored(&root.join("secret.txt"), false));
+        assert!(!filter.is_ignored(&sub.join("debug.log"), false));
+        assert!(filter.is_ignored(&sub.join("trace.log"), false));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn wildcards_follow_path_segments() {
+        let root = std::env::temp_dir().join(format!(
+            "rustsearch-gitignore-segments-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(
+            root.join(".gitignore"),
+            "/docs/*.pdf\na/**/b\n# comment\n\\#notes\n",
+        )
+        .unwrap();
+
+        let mut filter = GitignoreFilter::default();
+        assert!(filter.is_ignored(&root.join("docs").join("b.pdf"), false));
+        assert!(!filter.is_ignored(&root.join("docs").join("a").join("b.pdf"), false));
+        assert!(filter.is_ignored(&root.join("a").join("b"), false));
+        assert!(filter.is_ignored(&root.join("a").join("x").join("y").join("b"), false));
+        assert!(filter.is_ignored(&root.join("#notes"), false));
+        assert!(!filter.is_ignored(&root.join("# comment"), false));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
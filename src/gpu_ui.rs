@@ -2,10 +2,28 @@ use std::time::Duration;
 
 use eframe::egui;
 
-use crate::app_state::AppState;
-use crate::commands::{command_menu_items, format_latest_window};
-use crate::search::{file_name_from_path, truncate_middle};
-use crate::{format_bytes, SearchItemKind, FILE_PATH_MAX_CHARS};
+use crate::app_state::{AppState, RowAction, ROW_ACTIONS};
+use crate::feed::format_feed_time;
+use crate::preview::{PreviewContent, PREVIEW_MAX_BYTES};
+use crate::search::{format_modified, highlight_terms, truncate_middle, DateFilter};
+use crate::view_model::{footer_fields, result_row, search_busy_label, status_line, FOOTER_KEYS};
+use crate::{format_bytes, format_item_size};
+
+/// A result row the user clicked, applied to `AppState` after drawing.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ResultClick {
+    pub(crate) row: usize,
+    pub(crate) double_click: bool,
+    /// Set when an entry of the row's context menu was picked.
+    pub(crate) action: Option<RowAction>,
+}
+
+/// What was clicked this frame: a result row or a date chip.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum UiClick {
+    Result(ResultClick),
+    DateChip(DateFilter),
+}
 
 pub(crate) fn draw(
     ctx: &egui::Context,
@@ -13,11 +31,13 @@ pub(crate) fn draw(
     app: &AppState,
     frame_time_ms: f32,
     repaint_after: Duration,
-) {
+) -> Option<UiClick> {
+    let palette = app.palette();
     let mut results_rect = egui::Rect::NOTHING;
+    let mut click = None;
+    let mut chip = None;
     let full_rect = ui.max_rect();
-    ui.painter()
-        .rect_filled(full_rect, 0.0, egui::Color32::from_rgb(10, 14, 20));
+    ui.painter().rect_filled(full_rect, 0.0, palette.background);
     ui.set_min_size(full_rect.size());
 
     let mut remaining_h = ui.available_height();
@@ -32,11 +52,27 @@ pub(crate) fn draw(
             remaining_h -= 38.0;
         }
 
-        ui.add_space(6.0);
-        remaining_h -= 6.0;
+        ui.add_space(4.0);
+        chip = draw_date_chips(ui, app);
+        remaining_h -= 30.0;
 
         let results_h = (remaining_h - 48.0).max(120.0);
-        results_rect = draw_results(ui, app, results_h);
+        if app.settings.show_preview {
+            ui.horizontal_top(|ui| {
+                let preview_w = (ui.available_width() * 0.4).floor();
+                let results_w = ui.available_width() - preview_w - ui.spacing().item_spacing.x;
+                ui.allocate_ui_with_layout(
+                    egui::vec2(results_w, results_h),
+                    egui::Layout::top_down(egui::Align::Min),
+                    |ui| {
+                        (results_rect, click) = draw_results(ui, app, results_h);
+                    },
+                );
+                draw_preview(ui, app, results_h);
+            });
+        } else {
+            (results_rect, click) = draw_results(ui, app, results_h);
+        }
 
         ui.add_space(4.0);
         draw_status(ui, app);
@@ -44,40 +80,54 @@ pub(crate) fn draw(
     });
 
     draw_command_popup(ctx, app, results_rect);
+    draw_action_palette(ctx, app, results_rect);
     draw_notice_overlay(ctx, app);
+    chip.map(UiClick::DateChip).or(click.map(UiClick::Result))
+}
+
+/// The Today / 7 days / 30 days / This year row; returns the chip clicked.
+fn draw_date_chips(ui: &mut egui::Ui, app: &AppState) -> Option<DateFilter> {
+    let palette = app.palette();
+    let mut clicked = None;
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("Modified").color(palette.label).small());
+        for filter in DateFilter::ALL {
+            let active = app.date_filter == Some(filter);
+            let text = egui::RichText::new(filter.label())
+                .small()
+                .color(if active { palette.accent } else { palette.text });
+            if ui.selectable_label(active, text).clicked() {
+                clicked = Some(filter);
+            }
+        }
+    });
+    clicked
 }
 
 fn draw_prompt(ui: &mut egui::Ui, app: &AppState) {
+    let palette = app.palette();
     egui::Frame::default()
-        .fill(egui::Color32::from_rgb(15, 20, 28))
-        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(62, 72, 86)))
+        .fill(palette.panel.into())
+        .stroke(egui::Stroke::new(1.0, palette.border))
         .inner_margin(egui::Margin::same(8))
         .show(ui, |ui| {
             ui.set_width(ui.available_width());
-            let title = if app.indexing_in_progress {
-                "Indexing"
-            } else {
-                "Search"
+            let (title, text) = match &app.renaming {
+                Some((_, name)) => ("Rename", name.as_str()),
+                None if app.indexing_in_progress => ("Indexing", app.raw_query.as_str()),
+                None => ("Search", app.raw_query.as_str()),
             };
-            ui.label(
-                egui::RichText::new(title)
-                    .color(egui::Color32::from_rgb(155, 168, 185))
-                    .small(),
-            );
+            ui.label(egui::RichText::new(title).color(palette.label).small());
             ui.horizontal(|ui| {
-                ui.label(
-                    egui::RichText::new(">")
-                        .color(egui::Color32::from_rgb(255, 213, 128))
-                        .strong(),
-                );
+                ui.label(egui::RichText::new(">").color(palette.accent).strong());
                 let w = ui.available_width();
                 ui.allocate_ui_with_layout(
                     egui::vec2(w, 18.0),
                     egui::Layout::left_to_right(egui::Align::Center),
                     |ui| {
                         ui.label(
-                            egui::RichText::new(format!("{}{}", app.raw_query, "█"))
-                                .color(egui::Color32::from_rgb(236, 239, 244))
+                            egui::RichText::new(format!("{}{}", text, "█"))
+                                .color(palette.text)
                                 .monospace(),
                         );
                     },
@@ -87,22 +137,25 @@ fn draw_prompt(ui: &mut egui::Ui, app: &AppState) {
 }
 
 fn draw_progress(ui: &mut egui::Ui, app: &AppState) {
+    let palette = app.palette();
     let (label, value, fill) = if app.indexing_in_progress {
         (
             format!(
-                "{} {}",
+                "{} {} · {}",
                 index_phase_label(app.indexing_phase),
-                app.scope.label()
+                app.scope.label(),
+                app.indexing_detail()
             ),
             app.indexing_progress,
-            egui::Color32::from_rgb(178, 126, 28),
+            palette.indexing,
         )
     } else {
         let total = app.all_items.len().max(1);
+        let ratio = (app.active_search_cursor as f32 / total as f32).clamp(0.0, 1.0);
         (
-            "search".to_string(),
-            (app.active_search_cursor as f32 / total as f32).clamp(0.0, 1.0),
-            egui::Color32::from_rgb(56, 122, 168),
+            format!("search {:.0}%", ratio * 100.0),
+            ratio,
+            palette.searching,
         )
     };
 
@@ -114,8 +167,8 @@ fn draw_progress(ui: &mut egui::Ui, app: &AppState) {
         |ui| {
             ui.set_width(ui.available_width());
             egui::Frame::default()
-                .fill(egui::Color32::from_rgb(12, 16, 22))
-                .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(62, 72, 86)))
+                .fill(palette.panel.into())
+                .stroke(egui::Stroke::new(1.0, palette.border))
                 .inner_margin(egui::Margin::same(6))
                 .show(ui, |ui| {
                     let bar_h = 18.0;
@@ -125,11 +178,11 @@ fn draw_progress(ui: &mut egui::Ui, app: &AppState) {
                     );
 
                     let painter = ui.painter();
-                    painter.rect_filled(bar_rect, 0.0, egui::Color32::from_rgb(24, 30, 40));
+                    painter.rect_filled(bar_rect, 0.0, palette.track);
                     painter.rect_stroke(
                         bar_rect,
                         0.0,
-                        egui::Stroke::new(1.0, egui::Color32::from_rgb(62, 72, 86)),
+                        egui::Stroke::new(1.0, palette.border),
                         egui::StrokeKind::Outside,
                     );
 
@@ -143,19 +196,25 @@ fn draw_progress(ui: &mut egui::Ui, app: &AppState) {
                     painter.text(
                         bar_rect.center(),
                         egui::Align2::CENTER_CENTER,
-                        format!("{} {:.0}%", label, ratio * 100.0),
+                        label,
                         egui::FontId::monospace(12.0),
-                        egui::Color32::WHITE,
+                        palette.text.into(),
                     );
                 });
         },
     );
 }
 
-fn draw_results(ui: &mut egui::Ui, app: &AppState, target_height: f32) -> egui::Rect {
+fn draw_results(
+    ui: &mut egui::Ui,
+    app: &AppState,
+    target_height: f32,
+) -> (egui::Rect, Option<ResultClick>) {
+    let palette = app.palette();
+    let mut click = None;
     let frame = egui::Frame::default()
-        .fill(egui::Color32::from_rgb(10, 14, 20))
-        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(62, 72, 86)))
+        .fill(palette.background.into())
+        .stroke(egui::Stroke::new(1.0, palette.border))
         .inner_margin(egui::Margin::same(0));
 
     let out = ui
@@ -167,140 +226,341 @@ fn draw_results(ui: &mut egui::Ui, app: &AppState, target_height: f32) -> egui::
                 frame.show(ui, |ui| {
                     ui.set_min_size(egui::vec2(ui.available_width(), target_height));
                     ui.set_min_width(ui.available_width());
-                    ui.label(
-                        egui::RichText::new("Results")
-                            .color(egui::Color32::from_rgb(155, 168, 185))
-                            .small(),
-                    );
+                    if app.feed_mode {
+                        draw_feed(ui, app);
+                        return;
+                    }
+                    ui.label(egui::RichText::new("Results").color(palette.label).small());
+                    if let Some(message) = app.empty_state_message() {
+                        ui.add_space(12.0);
+                        ui.vertical_centered(|ui| {
+                            ui.label(egui::RichText::new(message).monospace().color(palette.dim));
+                        });
+                    }
 
-                    let row_h = 20.0;
+                    let density = app.settings.density;
+                    let row_h = density.row_height();
+                    let font_size = density.font_size();
                     let list_h = (ui.available_height() - 2.0).max(80.0);
                     egui::ScrollArea::vertical()
                         .id_salt("results-scroll")
                         .auto_shrink([false, false])
                         .max_height(list_h)
-                        .show(ui, |ui| {
-                            for (row, item) in app.items.iter().enumerate() {
-                                let selected = row == app.selected;
-                                let name = file_name_from_path(item.path.as_ref());
-                                let path = truncate_middle(item.path.as_ref(), FILE_PATH_MAX_CHARS);
-                                let kind = if item.kind == SearchItemKind::Folder {
-                                    "[D]"
+                        .show_rows(ui, row_h, app.items.len(), |ui, rows| {
+                            // Only follow the selection when it moved, so the wheel can scroll freely.
+                            let follow_id = ui.id().with("followed-selection");
+                            let followed = (app.selected, app.items.len());
+                            let follow_selection = ui
+                                .data(|data| data.get_temp::<(usize, usize)>(follow_id))
+                                != Some(followed);
+                            let terms = highlight_terms(&app.query);
+                            for row in rows.clone() {
+                                let view = result_row(app, row, &app.items[row], &terms);
+                                let selected = view.selected;
+                                let marked = view.marked;
+                                let color: egui::Color32 = if selected {
+                                    palette.accent.into()
                                 } else {
-                                    "   "
+                                    view.color.into()
+                                };
+                                let highlight: egui::Color32 = if selected {
+                                    palette.text.into()
+                                } else {
+                                    palette.accent.into()
                                 };
 
-                                let text = format!(
-                                    "{} {} {}  {}",
-                                    if selected { ">" } else { " " },
-                                    kind,
-                                    name,
-                                    path
+                                let mut job = egui::text::LayoutJob::default();
+                                let mut append = |text: &str, color: egui::Color32| {
+                                    job.append(
+                                        text,
+                                        0.0,
+                                        egui::TextFormat::simple(
+                                            egui::FontId::monospace(font_size),
+                                            color,
+                                        ),
+                                    );
+                                };
+                                append(
+                                    &format!(
+                                        "{} {} {:>10}  {:>16}  {}",
+                                        view.marker,
+                                        view.kind,
+                                        view.size,
+                                        view.modified,
+                                        view.change
+                                            .map(|kind| format!("{:<9}", kind.label()))
+                                            .unwrap_or_default(),
+                                    ),
+                                    color,
                                 );
+                                for &(segment, matched) in &view.name_segments {
+                                    append(segment, if matched { highlight } else { color });
+                                }
+                                if view.is_folder {
+                                    append("\\", color);
+                                }
+                                append(&format!("  {}", view.path), color);
 
                                 let (row_rect, response) = ui.allocate_exact_size(
                                     egui::vec2(ui.available_width(), row_h),
-                                    egui::Sense::hover(),
+                                    egui::Sense::click(),
                                 );
 
                                 if selected {
+                                    ui.painter().rect_filled(row_rect, 0.0, palette.selection);
+                                } else if marked {
                                     ui.painter().rect_filled(
                                         row_rect,
                                         0.0,
-                                        egui::Color32::from_rgb(58, 84, 122),
+                                        egui::Color32::from(palette.selection).gamma_multiply(0.35),
                                     );
+                                } else if response.hovered() {
+                                    ui.painter().rect_filled(
+                                        row_rect,
+                                        0.0,
+                                        egui::Color32::from(palette.selection).gamma_multiply(0.5),
+                                    );
+                                }
+                                if response.double_clicked() {
+                                    click = Some(ResultClick {
+                                        row,
+                                        double_click: true,
+                                        action: None,
+                                    });
+                                } else if response.clicked() || response.secondary_clicked() {
+                                    click = Some(ResultClick {
+                                        row,
+                                        double_click: false,
+                                        action: None,
+                                    });
                                 }
+                                response.context_menu(|ui| {
+                                    for (label, action) in ROW_ACTIONS {
+                                        if ui.button(label).clicked() {
+                                            click = Some(ResultClick {
+                                                row,
+                                                double_click: false,
+                                                action: Some(action),
+                                            });
+                                            ui.close();
+                                        }
+                                    }
+                                });
 
-                                ui.painter().text(
-                                    egui::pos2(row_rect.left() + 2.0, row_rect.center().y),
-                                    egui::Align2::LEFT_CENTER,
-                                    text,
-                                    egui::FontId::monospace(13.0),
-                                    if selected {
-                                        egui::Color32::from_rgb(255, 213, 128)
-                                    } else {
-                                        file_color(name, item.kind)
-                                    },
+                                let galley = ui.painter().layout_job(job);
+                                let pos = egui::pos2(
+                                    row_rect.left() + 2.0,
+                                    row_rect.center().y - galley.size().y / 2.0,
                                 );
+                                ui.painter().galley(pos, galley, color);
+                            }
 
-                                if selected {
-                                    ui.scroll_to_rect(response.rect, Some(egui::Align::Center));
-                                }
+                            if follow_selection && app.selected < app.items.len() {
+                                // The selected row may be off screen and not laid out, so
+                                // place it from its index. Scroll only as far as needed
+                                // to keep the margin rows in view.
+                                let pitch = row_h + ui.spacing().item_spacing.y;
+                                let top = ui.max_rect().top() - rows.start as f32 * pitch;
+                                let selected_rect = egui::Rect::from_min_size(
+                                    egui::pos2(
+                                        ui.max_rect().left(),
+                                        top + app.selected as f32 * pitch,
+                                    ),
+                                    egui::vec2(ui.max_rect().width(), row_h),
+                                );
+                                let viewport_rows = (ui.clip_rect().height() / pitch) as usize;
+                                let margin = app
+                                    .settings
+                                    .scroll_margin_rows()
+                                    .min(viewport_rows.saturating_sub(1) / 2);
+                                let context = egui::vec2(0.0, margin as f32 * pitch);
+                                ui.scroll_to_rect(selected_rect.expand2(context), None);
                             }
+
+                            // A clicked row is already on screen; don't recenter on it.
+                            let followed = match click {
+                                Some(click) => (click.row, app.items.len()),
+                                None => followed,
+                            };
+                            ui.data_mut(|data| data.insert_temp(follow_id, followed));
                         });
                 })
             },
         )
         .inner;
 
-    out.response.rect
+    (out.response.rect, click)
 }
 
-fn draw_status(ui: &mut egui::Ui, app: &AppState) {
-    let status = format!(
-        "{}SCOPE: {}{} | MEM: {} | CHG: +{} ~{} -{} | RESULTS: {} | LAST: {}",
-        if app.is_elevated {
-            ""
-        } else {
-            "[NOT ELEVATED] "
-        },
-        app.scope.label(),
-        if app.latest_only_mode {
-            format!(
-                " | FILTER: latest-{}",
-                format_latest_window(app.latest_window_secs)
-            )
-        } else {
-            String::new()
-        },
-        format_bytes(app.index_memory_bytes),
-        app.changes_added_since_index,
-        app.changes_updated_since_index,
-        app.changes_deleted_since_index,
-        app.items.len(),
-        app.last_action
+/// `/feed`: live changes newest first, colored by what happened to the path.
+fn draw_feed(ui: &mut egui::Ui, app: &AppState) {
+    let palette = app.palette();
+    let feed = &app.change_feed;
+    ui.label(
+        egui::RichText::new(format!("Live feed  {}", feed.counts_label()))
+            .color(palette.label)
+            .small(),
     );
+    if feed.is_empty() {
+        ui.add_space(12.0);
+        ui.vertical_centered(|ui| {
+            ui.label(
+                egui::RichText::new("Waiting for changes...")
+                    .monospace()
+                    .color(palette.dim),
+            );
+        });
+        return;
+    }
 
-    ui.add_sized(
-        [ui.available_width(), 18.0],
-        egui::Label::new(
-            egui::RichText::new(status)
-                .monospace()
-                .color(egui::Color32::from_rgb(160, 168, 178)),
-        )
-        .truncate(),
-    );
+    let density = app.settings.density;
+    let path_chars = app.path_max_chars();
+    egui::ScrollArea::vertical()
+        .id_salt("feed-scroll")
+        .auto_shrink([false, false])
+        .max_height((ui.available_height() - 2.0).max(80.0))
+        .show_rows(ui, density.row_height(), feed.len(), |ui, rows| {
+            for entry in feed.newest().skip(rows.start).take(rows.len()) {
+                let line = format!(
+                    "{}  {:<8}  {}",
+                    format_feed_time(entry.at_unix_secs),
+                    entry.kind.label(),
+                    truncate_middle(&entry.path, path_chars)
+                );
+                ui.add_sized(
+                    egui::vec2(ui.available_width(), density.row_height()),
+                    egui::Label::new(
+                        egui::RichText::new(line)
+                            .font(egui::FontId::monospace(density.font_size()))
+                            .color(palette.change_color(entry.kind)),
+                    )
+                    .truncate(),
+                );
+            }
+        });
+}
+
+fn draw_preview(ui: &mut egui::Ui, app: &AppState, target_height: f32) {
+    let palette = app.palette();
+    egui::Frame::default()
+        .fill(palette.panel.into())
+        .stroke(egui::Stroke::new(1.0, palette.border))
+        .inner_margin(egui::Margin::same(6))
+        .show(ui, |ui| {
+            ui.set_min_size(egui::vec2(ui.available_width(), target_height - 12.0));
+            ui.set_max_width(ui.available_width());
+            ui.label(egui::RichText::new("Preview").color(palette.label).small());
+
+            let Some(preview) = &app.preview else {
+                let note = if app.preview_requested.is_some() {
+                    "Loading..."
+                } else {
+                    "Nothing selected"
+                };
+                ui.label(egui::RichText::new(note).color(palette.dim).monospace());
+                return;
+            };
+
+            let mut details = vec![format_modified(preview.modified_unix_secs)];
+            match &preview.content {
+                PreviewContent::Folder => details.insert(0, "folder".to_string()),
+                _ => details.insert(0, format_item_size(preview.size)),
+            }
+            ui.label(
+                egui::RichText::new(details.join("  "))
+                    .color(palette.status)
+                    .monospace(),
+            );
+            ui.separator();
+
+            let (body, color) = match &preview.content {
+                PreviewContent::Text { text, truncated } => {
+                    if *truncated {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "first {} shown",
+                                format_bytes(PREVIEW_MAX_BYTES)
+                            ))
+                            .color(palette.dim)
+                            .small(),
+                        );
+                    }
+                    (text.as_str(), palette.text)
+                }
+                PreviewContent::Binary { hex } => {
+                    ui.label(
+                        egui::RichText::new("Binary file")
+                            .color(palette.warn)
+                            .small(),
+                    );
+                    (hex.as_str(), palette.dim)
+                }
+                PreviewContent::Folder => return,
+                PreviewContent::Error(err) => (err.as_str(), palette.danger),
+            };
+
+            egui::ScrollArea::both()
+                .id_salt(("preview-scroll", preview.path.as_str()))
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::Label::new(egui::RichText::new(body).color(color).monospace())
+                            .extend(),
+                    );
+                });
+        });
+}
+
+fn draw_status(ui: &mut egui::Ui, app: &AppState) {
+    let palette = app.palette();
+    let status = status_line(app);
+
+    ui.horizontal(|ui| {
+        if let Some(busy) = search_busy_label(app) {
+            ui.label(
+                egui::RichText::new(format!("{} |", busy))
+                    .monospace()
+                    .color(palette.searching),
+            );
+        }
+        ui.add_sized(
+            [ui.available_width(), 18.0],
+            egui::Label::new(
+                egui::RichText::new(status)
+                    .monospace()
+                    .color(palette.status),
+            )
+            .truncate(),
+        );
+    });
 }
 
 fn draw_footer(ui: &mut egui::Ui, app: &AppState, frame_time_ms: f32, repaint_after: Duration) {
+    let palette = app.palette();
+    let footer = footer_fields(app);
     ui.add_sized(
         [ui.available_width(), 18.0],
         egui::Label::new(
             egui::RichText::new(format!(
-                "Enter open | Alt+Enter reveal | Esc hide | IDX: {} | LIVE: {} | STATE: {} | RENDER: gpu {:.1}ms | TICK: {}ms",
-                app.index_backend.label(),
-                if app.index_backend.live_updates() {
-                    "on"
-                } else {
-                    "off"
-                },
-                if app.indexing_in_progress {
-                    "indexing"
-                } else {
-                    "idle"
-                },
+                "{} | IDX: {} | LIVE: {} | STATE: {} | JOB: {} | RENDER: gpu {:.1}ms | TICK: {}ms",
+                FOOTER_KEYS,
+                footer.backend,
+                if footer.live { "on" } else { "off" },
+                footer.state,
+                footer.job,
                 frame_time_ms,
                 repaint_after.as_millis(),
             ))
             .monospace()
-            .color(egui::Color32::from_rgb(150, 162, 178)),
+            .color(palette.status),
         )
         .truncate(),
     );
 }
 
 fn draw_command_popup(ctx: &egui::Context, app: &AppState, results_rect: egui::Rect) {
-    let items = command_menu_items(&app.raw_query, app.tracking_enabled);
+    let palette = app.palette();
+    let items = app.command_suggestions();
     if items.is_empty() || !results_rect.is_positive() {
         return;
     }
@@ -311,16 +571,12 @@ fn draw_command_popup(ctx: &egui::Context, app: &AppState, results_rect: egui::R
         .fixed_pos(pos)
         .show(ctx, |ui| {
             egui::Frame::popup(ui.style())
-                .fill(egui::Color32::from_rgb(20, 26, 36))
-                .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(78, 92, 112)))
+                .fill(palette.popup.into())
+                .stroke(egui::Stroke::new(1.0, palette.popup_border))
                 .show(ui, |ui| {
                     ui.set_max_width(640.0);
                     ui.set_min_width(500.0);
-                    ui.label(
-                        egui::RichText::new("Commands")
-                            .color(egui::Color32::from_rgb(160, 170, 190))
-                            .small(),
-                    );
+                    ui.label(egui::RichText::new("Commands").color(palette.label).small());
 
                     egui::ScrollArea::vertical()
                         .max_height((results_rect.height() - 20.0).max(140.0))
@@ -328,11 +584,11 @@ fn draw_command_popup(ctx: &egui::Context, app: &AppState, results_rect: egui::R
                             for (idx, item) in items.iter().enumerate() {
                                 let selected = idx == app.command_selected;
                                 let color = if selected {
-                                    egui::Color32::from_rgb(255, 213, 128)
+                                    palette.accent
                                 } else {
-                                    egui::Color32::from_rgb(210, 220, 235)
+                                    palette.text
                                 };
-                                ui.label(
+                                let response = ui.label(
                                     egui::RichText::new(format!(
                                         "{} {:<12} {}",
                                         if selected { ">" } else { " " },
@@ -342,21 +598,75 @@ fn draw_command_popup(ctx: &egui::Context, app: &AppState, results_rect: egui::R
                                     .monospace()
                                     .color(color),
                                 );
+                                if selected {
+                                    ui.scroll_to_rect(response.rect, None);
+                                }
                             }
                         });
                 });
         });
 }
 
+fn draw_action_palette(ctx: &egui::Context, app: &AppState, results_rect: egui::Rect) {
+    let palette = app.palette();
+    let Some(item) = app.items.get(app.selected) else {
+        return;
+    };
+    if !app.action_palette_open || !results_rect.is_positive() {
+        return;
+    }
+
+    let pos = egui::pos2(results_rect.left() + 8.0, results_rect.top() + 8.0);
+    egui::Area::new(egui::Id::new("action-palette"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(pos)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style())
+                .fill(palette.popup.into())
+                .stroke(egui::Stroke::new(1.0, palette.popup_border))
+                .show(ui, |ui| {
+                    ui.set_min_width(320.0);
+                    ui.label(
+                        egui::RichText::new(format!("Actions: {}", item.name))
+                            .color(palette.label)
+                            .small(),
+                    );
+                    for (idx, (label, _)) in ROW_ACTIONS.iter().enumerate() {
+                        let selected = idx == app.action_selected;
+                        let color = if selected {
+                            palette.accent
+                        } else {
+                            palette.text
+                        };
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{} {}",
+                                if selected { ">" } else { " " },
+                                label
+                            ))
+                            .monospace()
+                            .color(color),
+                        );
+                    }
+                });
+        });
+}
+
 fn draw_notice_overlay(ctx: &egui::Context, app: &AppState) {
+    let palette = app.palette();
     if !app.show_quick_help_overlay && !app.show_privilege_overlay && !app.show_about_overlay {
         return;
     }
 
+    let hotkey_line = format!(
+        "Hotkey: {} toggles panel | Enter opens | Alt+Enter reveals | Ctrl+Enter opens folder",
+        app.settings.hotkey
+    );
+    let quick_hotkey_line = format!("Press {} to show or hide RustSearch", app.settings.hotkey);
     let (title, color, lines): (&str, egui::Color32, Vec<&str>) = if app.show_privilege_overlay {
         (
             "Notice",
-            egui::Color32::from_rgb(230, 80, 80),
+            palette.danger.into(),
             vec![
                 "███    ██  ██████  ████████     ███████ ██      ███████ ██    ██  █████  ████████ ███████ ██████  ",
                 "_████   ██ ██    ██    ██        ██      ██      ██      ██    ██ ██   ██    ██    ██      ██   ██ ",
@@ -372,13 +682,13 @@ fn draw_notice_overlay(ctx: &egui::Context, app: &AppState) {
     } else if app.show_about_overlay {
         (
             "About",
-            egui::Color32::from_rgb(130, 210, 255),
+            palette.info.into(),
             vec![
                 "NTFSSearch",
                 "made by IvRogoz - 2026",
                 "Rendering: egui native GPU UI (fallback: /soft)",
                 "Indexing: NTFS/USN live when elevated, DIRWALK fallback otherwise",
-                "Hotkey: ` toggles panel | Enter opens | Alt+Enter reveals",
+                &hotkey_line,
                 "Commands: /all /entire /reindex /up /track /latest /fullscreen /fullheight",
                 "",
                 "Press any key to close",
@@ -387,10 +697,10 @@ fn draw_notice_overlay(ctx: &egui::Context, app: &AppState) {
     } else {
         (
             "Notice",
-            egui::Color32::from_rgb(130, 210, 255),
+            palette.info.into(),
             vec![
                 "Quick Start",
-                "Press ` to show or hide RustSearch",
+                &quick_hotkey_line,
                 "Type to search, Enter to open, Alt+Enter to reveal",
                 "Use / for commands: /all /entire /reindex /track /exit",
             ],
@@ -405,7 +715,7 @@ fn draw_notice_overlay(ctx: &egui::Context, app: &AppState) {
         .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
         .show(ctx, |ui| {
             egui::Frame::popup(ui.style())
-                .fill(egui::Color32::from_rgb(18, 22, 30))
+                .fill(palette.popup.into())
                 .stroke(egui::Stroke::new(1.0, color))
                 .inner_margin(egui::Margin::same(12))
                 .show(ui, |ui| {
@@ -441,31 +751,10 @@ fn draw_notice_overlay(ctx: &egui::Context, app: &AppState) {
         });
 }
 
-fn file_color(name: &str, kind: SearchItemKind) -> egui::Color32 {
-    if kind == SearchItemKind::Folder {
-        return egui::Color32::from_rgb(130, 210, 255);
-    }
-
-    let lower = name.to_ascii_lowercase();
-    if lower.ends_with(".rs") {
-        egui::Color32::from_rgb(255, 153, 85)
-    } else if lower.ends_with(".ts") || lower.ends_with(".tsx") {
-        egui::Color32::from_rgb(99, 179, 237)
-    } else if lower.ends_with(".js") || lower.ends_with(".jsx") {
-        egui::Color32::from_rgb(246, 224, 94)
-    } else if lower.ends_with(".json") {
-        egui::Color32::from_rgb(104, 211, 145)
-    } else if lower.ends_with(".md") {
-        egui::Color32::from_rgb(180, 178, 255)
-    } else {
-        egui::Color32::from_rgb(220, 220, 220)
-    }
-}
-
 fn index_phase_label(phase: &str) -> &'static str {
     match phase {
         "snapshot" => "reading snapshot",
-        "index" => "reading index",
+        "index" => "building index",
         "write" => "finalizing index",
         "live" => "live updates",
         "done" => "ready",
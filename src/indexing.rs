@@ -1,33 +1,97 @@
-use std::{env, sync::mpsc};
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Instant;
 
+use serde_json::json;
 use walkdir::WalkDir;
 
-use crate::indexing_ntfs::{run_ntfs_live_index_job, try_index_ntfs_volume};
+use crate::exclude::{compile_globs, is_excluded, Glob};
+use crate::gitignore::GitignoreFilter;
+use crate::indexing_ntfs::{
+    letterless_volume_roots, run_ntfs_live_index_job, try_index_ntfs_volume, volume_guid_key,
+    volume_mount_paths,
+};
+use crate::path_table::PathTable;
+use crate::platform::{
+    drive_kind, file_attributes, logical_drive_roots, volume_file_system, DriveKind,
+};
+use crate::settings::Settings;
 use crate::storage::{load_scope_snapshot, persist_scope_snapshot_async};
 use crate::{
-    debug_log, IndexBackend, IndexEvent, SearchItem, SearchItemKind, SearchScope, UNKNOWN_TS,
+    debug_event, debug_log, DebugCategory, IndexBackend, IndexEvent, SearchItem, SearchItemKind,
+    SearchScope, UNKNOWN_SIZE, UNKNOWN_TS,
 };
 
+/// How an index job treats the roots in its scope.
+pub(crate) struct IndexOptions {
+    /// Walk folders when a volume cannot be read through NTFS.
+    pub(crate) allow_dirwalk_fallback: bool,
+    pub(crate) excludes: Vec<Glob>,
+    pub(crate) respect_gitignore: bool,
+    /// Create the USN journal on NTFS volumes that have none.
+    pub(crate) create_usn_journal: bool,
+    /// Keep files and folders marked hidden or system.
+    pub(crate) include_hidden: bool,
+    /// Index removable and network drives in the all-drives scope.
+    pub(crate) include_non_fixed_drives: bool,
+}
+
+impl IndexOptions {
+    pub(crate) fn from_settings(settings: &Settings, allow_dirwalk_fallback: bool) -> Self {
+        Self {
+            allow_dirwalk_fallback,
+            excludes: compile_globs(&settings.exclude_paths),
+            respect_gitignore: settings.respect_gitignore,
+            create_usn_journal: settings.create_usn_journal,
+            include_hidden: settings.index_hidden,
+            include_non_fixed_drives: settings.index_non_fixed_drives,
+        }
+    }
+}
+
 pub(crate) fn run_index_job(
     scope: SearchScope,
     job_id: u64,
     tx: mpsc::Sender<IndexEvent>,
-    allow_dirwalk_fallback: bool,
+    cancel: Arc<AtomicBool>,
+    options: IndexOptions,
 ) {
+    let started = Instant::now();
     debug_log(&format!(
         "run_index_job start job_id={} scope={}",
         job_id,
         scope.label()
     ));
+    debug_event(
+        DebugCategory::Index,
+        job_id,
+        json!({ "event": "start", "scope": scope.label() }),
+    );
 
     #[cfg(target_os = "windows")]
     {
-        if run_ntfs_live_index_job(scope.clone(), job_id, &tx) {
+        if run_ntfs_live_index_job(scope.clone(), job_id, &tx, &cancel, &options) {
             debug_log(&format!(
                 "run_index_job live index active job_id={} scope={}",
                 job_id,
                 scope.label()
             ));
+            debug_event(
+                DebugCategory::Index,
+                job_id,
+                json!({ "event": "live_stopped", "elapsed_ms": started.elapsed().as_millis() as u64 }),
+            );
+            return;
+        }
+
+        if cancel.load(Ordering::Relaxed) {
+            debug_log(&format!("run_index_job cancelled job_id={}", job_id));
+            debug_event(
+                DebugCategory::Index,
+                job_id,
+                json!({ "event": "cancelled" }),
+            );
             return;
         }
 
@@ -36,21 +100,43 @@ pub(crate) fn run_index_job(
             job_id,
             scope.label()
         ));
+        debug_event(
+            DebugCategory::Index,
+            job_id,
+            json!({ "event": "live_unavailable" }),
+        );
     }
 
     let _ = tx.send(IndexEvent::Progress {
         job_id,
         current: 0,
         total: 1,
+        scanned: 0,
+        total_is_estimate: false,
         phase: "snapshot",
     });
 
     if let Some(items) = load_scope_snapshot(&scope) {
+        debug_event(
+            DebugCategory::Index,
+            job_id,
+            json!({ "event": "snapshot_loaded", "items": items.len() }),
+        );
         let _ = tx.send(IndexEvent::SnapshotLoaded { job_id, items });
     }
 
     let (items, backend) =
-        index_files_for_scope_with_progress(scope.clone(), job_id, &tx, allow_dirwalk_fallback);
+        index_files_for_scope_with_progress(scope.clone(), job_id, &tx, &cancel, &options);
+    // A superseded job only has a partial corpus, which must not replace the snapshot.
+    if cancel.load(Ordering::Relaxed) {
+        debug_log(&format!("run_index_job cancelled job_id={}", job_id));
+        debug_event(
+            DebugCategory::Index,
+            job_id,
+            json!({ "event": "cancelled" }),
+        );
+        return;
+    }
     persist_scope_snapshot_async(scope.clone(), items.clone());
     debug_log(&format!(
         "run_index_job finished job_id={} items={} backend= {}",
@@ -58,6 +144,16 @@ pub(crate) fn run_index_job(
         items.len(),
         backend.label()
     ));
+    debug_event(
+        DebugCategory::Index,
+        job_id,
+        json!({
+            "event": "finished",
+            "items": items.len(),
+            "backend": backend.label(),
+            "elapsed_ms": started.elapsed().as_millis() as u64,
+        }),
+    );
     let _ = tx.send(IndexEvent::Done {
         job_id,
         items,
@@ -65,101 +161,129 @@ pub(crate) fn run_index_job(
     });
 }
 
+pub(crate) fn index_files_for_scope(scope: SearchScope) -> Vec<SearchItem> {
+    let (tx, _rx) = mpsc::channel();
+    let options = IndexOptions::from_settings(&Settings::load_or_default(), true);
+    index_files_for_scope_with_progress(scope, 0, &tx, &AtomicBool::new(false), &options).0
+}
+
 fn index_files_for_scope_with_progress(
     scope: SearchScope,
     job_id: u64,
     tx: &mpsc::Sender<IndexEvent>,
-    allow_dirwalk_fallback: bool,
+    cancel: &AtomicBool,
+    options: &IndexOptions,
 ) -> (Vec<SearchItem>, IndexBackend) {
-    let roots = scope_roots(&scope);
+    let allow_dirwalk_fallback = options.allow_dirwalk_fallback;
+    let excludes = options.excludes.as_slice();
+    let respect_gitignore = options.respect_gitignore;
+    let roots = scope_roots(&scope, options.include_non_fixed_drives);
     let mut out = Vec::new();
     let mut scanned = 0usize;
     let mut used_ntfs = false;
     let mut used_walkdir = false;
+    let mut skipped_volumes = 0usize;
 
     for root in roots {
-        let Some(drive_letter) = drive_letter_from_root_str(&root) else {
-            if !allow_dirwalk_fallback {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+
+        // A folder below the drive root is cheaper to walk than to find among
+        // every record in the volume's MFT.
+        let walk_folder = matches!(scope, SearchScope::CurrentFolder) && !is_volume_root(&root);
+        let Some(volume_root) = volume_root_for(&root).filter(|_| !walk_folder) else {
+            if !allow_dirwalk_fallback && !walk_folder {
+                debug_log(&format!(
+                    "index skipped root={} reason=not a volume and folder walks are off",
+                    root
+                ));
+                debug_event(
+                    DebugCategory::Index,
+                    job_id,
+                    json!({ "event": "root_skipped", "root": root, "reason": "not_a_volume" }),
+                );
+                skipped_volumes += 1;
                 continue;
             }
 
             used_walkdir = true;
-            for entry in WalkDir::new(&root)
-                .follow_links(false)
-                .into_iter()
-                .filter_map(Result::ok)
-            {
-                if !entry.file_type().is_file() && !entry.file_type().is_dir() {
-                    continue;
-                }
-
-                out.push(search_item_from_walkdir_entry(&entry));
-                scanned += 1;
-
-                if scanned.is_multiple_of(500) {
-                    let _ = tx.send(IndexEvent::Progress {
-                        job_id,
-                        current: scanned,
-                        total: 0,
-                        phase: "index",
-                    });
-                }
-            }
+            out.extend(walk_root_items(
+                &root,
+                job_id,
+                tx,
+                cancel,
+                excludes,
+                respect_gitignore,
+                &mut scanned,
+            ));
             continue;
         };
 
-        let volume_root = format!("{}:\\", drive_letter);
-
-        if let Some(mut ntfs_items) = try_index_ntfs_volume(&volume_root, job_id, tx) {
-            used_ntfs = true;
-
-            if matches!(scope, SearchScope::CurrentFolder) {
-                let prefix = normalized_folder_prefix(&root);
-                ntfs_items.retain(|item| path_starts_with_folder(item.path.as_ref(), &prefix));
-            }
-
-            scanned += ntfs_items.len();
-            out.extend(ntfs_items);
-
-            continue;
-        }
+        // The MFT reader only understands NTFS; FAT and exFAT drives are walked
+        // even when walks are otherwise off.
+        let non_ntfs = is_non_ntfs_volume(&volume_root);
+        if !non_ntfs {
+            if let Some(mut ntfs_items) =
+                try_index_ntfs_volume(&volume_root, job_id, tx, cancel, options)
+            {
+                used_ntfs = true;
 
-        if !allow_dirwalk_fallback {
-            continue;
-        }
+                if !excludes.is_empty() {
+                    ntfs_items.retain(|item| !is_excluded(&item.full_path(), excludes));
+                }
 
-        used_walkdir = true;
+                scanned += ntfs_items.len();
+                out.extend(ntfs_items);
 
-        for entry in WalkDir::new(&root)
-            .follow_links(false)
-            .into_iter()
-            .filter_map(Result::ok)
-        {
-            if !entry.file_type().is_file() && !entry.file_type().is_dir() {
                 continue;
             }
 
-            out.push(search_item_from_walkdir_entry(&entry));
-            scanned += 1;
-
-            if scanned.is_multiple_of(500) {
-                let _ = tx.send(IndexEvent::Progress {
+            if !allow_dirwalk_fallback {
+                debug_log(&format!(
+                    "index skipped root={} reason=NTFS volume could not be opened and folder walks are off",
+                    root
+                ));
+                debug_event(
+                    DebugCategory::Index,
                     job_id,
-                    current: scanned,
-                    total: 0,
-                    phase: "index",
-                });
+                    json!({ "event": "root_skipped", "root": root, "reason": "ntfs_open_failed" }),
+                );
+                skipped_volumes += 1;
+                continue;
             }
         }
+
+        let Some(walk_root) = walk_root_for(&root) else {
+            debug_log(&format!(
+                "index skipped root={} reason=volume has no mount folder to walk",
+                root
+            ));
+            skipped_volumes += 1;
+            continue;
+        };
+        used_walkdir = true;
+        out.extend(walk_root_items(
+            &walk_root,
+            job_id,
+            tx,
+            cancel,
+            excludes,
+            respect_gitignore,
+            &mut scanned,
+        ));
     }
 
     let _ = tx.send(IndexEvent::Progress {
         job_id,
         current: scanned,
         total: scanned.max(1),
+        scanned,
+        total_is_estimate: false,
         phase: "index",
     });
-    let backend = if used_ntfs && used_walkdir {
+    // Volumes that could not be read leave the corpus partial, shown as mixed.
+    let backend = if used_ntfs && (used_walkdir || skipped_volumes > 0) {
         IndexBackend::Mixed
     } else if used_ntfs {
         IndexBackend::NtfsMft
@@ -171,57 +295,162 @@ fn index_files_for_scope_with_progress(
     (out, backend)
 }
 
-fn search_item_from_walkdir_entry(entry: &walkdir::DirEntry) -> SearchItem {
-    let modified_unix_secs = entry
-        .metadata()
-        .ok()
+/// Indexes `root` with the directory walker, adding to `scanned` and
+/// reporting progress as it goes.
+pub(crate) fn walk_root_items(
+    root: &str,
+    job_id: u64,
+    tx: &mpsc::Sender<IndexEvent>,
+    cancel: &AtomicBool,
+    excludes: &[Glob],
+    respect_gitignore: bool,
+    scanned: &mut usize,
+) -> Vec<SearchItem> {
+    let mut out = Vec::new();
+    let mut folders = PathTable::default();
+    for entry in walk_entries(root, excludes, respect_gitignore) {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        if !entry.file_type().is_file() && !entry.file_type().is_dir() {
+            continue;
+        }
+
+        out.push(search_item_from_walkdir_entry(&entry, &mut folders));
+        *scanned += 1;
+
+        if scanned.is_multiple_of(500) {
+            let _ = tx.send(IndexEvent::Progress {
+                job_id,
+                current: *scanned,
+                total: 0,
+                scanned: *scanned,
+                total_is_estimate: false,
+                phase: "index",
+            });
+        }
+    }
+    out
+}
+
+/// True for a volume that reports a file system other than NTFS. Volumes that
+/// cannot be queried are left to the NTFS reader to accept or reject.
+pub(crate) fn is_non_ntfs_volume(root: &str) -> bool {
+    match volume_file_system(root) {
+        Some(file_system) if !file_system.eq_ignore_ascii_case("NTFS") => {
+            debug_log(&format!(
+                "index walking root={} reason={} volume",
+                root, file_system
+            ));
+            true
+        }
+        _ => false,
+    }
+}
+
+fn walk_entries<'a>(
+    root: &str,
+    excludes: &'a [Glob],
+    respect_gitignore: bool,
+) -> impl Iterator<Item = walkdir::DirEntry> + 'a {
+    let mut gitignore = respect_gitignore.then(GitignoreFilter::default);
+    WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(move |entry| {
+            if is_excluded(&entry.path().to_string_lossy(), excludes) {
+                return false;
+            }
+            !gitignore
+                .as_mut()
+                .is_some_and(|filter| filter.is_ignored(entry.path(), entry.file_type().is_dir()))
+        })
+        .filter_map(Result::ok)
+}
+
+fn search_item_from_walkdir_entry(
+    entry: &walkdir::DirEntry,
+    folders: &mut PathTable,
+) -> SearchItem {
+    let metadata = entry.metadata().ok();
+    let modified_unix_secs = metadata
+        .as_ref()
         .and_then(|metadata| metadata.modified().ok())
         .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
         .map(|duration| duration.as_secs() as i64)
         .unwrap_or(UNKNOWN_TS);
+    let size = metadata
+        .as_ref()
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .unwrap_or(UNKNOWN_SIZE);
+
+    folders
+        .item(
+            &entry.path().to_string_lossy(),
+            modified_unix_secs,
+            size,
+            if entry.file_type().is_dir() {
+                SearchItemKind::Folder
+            } else {
+                SearchItemKind::File
+            },
+        )
+        .with_attrs(metadata.as_ref().map(file_attributes).unwrap_or(0))
+}
 
-    SearchItem {
-        path: entry.path().to_string_lossy().into_owned().into_boxed_str(),
-        modified_unix_secs,
-        kind: if entry.file_type().is_dir() {
-            SearchItemKind::Folder
-        } else {
-            SearchItemKind::File
-        },
-    }
+pub(crate) fn current_folder_root() -> String {
+    env::current_dir()
+        .unwrap_or_else(|_| "C:\\".into())
+        .to_string_lossy()
+        .to_string()
 }
 
-pub(crate) fn scope_roots(scope: &SearchScope) -> Vec<String> {
+pub(crate) fn scope_roots(scope: &SearchScope, include_non_fixed: bool) -> Vec<String> {
     match scope {
-        SearchScope::CurrentFolder => vec![env::current_dir()
-            .unwrap_or_else(|_| "C:\\".into())
-            .to_string_lossy()
-            .to_string()],
+        SearchScope::CurrentFolder => vec![current_folder_root()],
         SearchScope::EntireCurrentDrive => {
             let cwd = env::current_dir().unwrap_or_else(|_| "C:\\".into());
             let drive = drive_letter_from_path(&cwd).unwrap_or('C');
             vec![format!("{}:\\", drive.to_ascii_uppercase())]
         }
-        SearchScope::AllLocalDrives => available_drive_roots(),
+        SearchScope::AllLocalDrives => {
+            let mut roots = available_drive_roots();
+            roots.extend(letterless_volume_roots());
+            roots.retain(|root| include_drive_root(root, drive_kind(root), include_non_fixed));
+            if roots.is_empty() {
+                roots.push("C:\\".to_string());
+            }
+            roots
+        }
         SearchScope::Drive(letter) => vec![format!("{}:\\", letter.to_ascii_uppercase())],
     }
 }
 
-fn available_drive_roots() -> Vec<String> {
-    let mut roots = Vec::new();
-
-    for letter in 'A'..='Z' {
-        let root = format!("{}:\\", letter);
-        if std::path::Path::new(&root).exists() {
-            roots.push(root);
-        }
+pub(crate) fn available_drive_roots() -> Vec<String> {
+    let roots = logical_drive_roots();
+    if !roots.is_empty() {
+        return roots;
     }
 
-    if roots.is_empty() {
-        roots.push("C:\\".to_string());
-    }
+    ('A'..='Z')
+        .map(|letter| format!("{}:\\", letter))
+        .filter(|root| std::path::Path::new(root).exists())
+        .collect()
+}
 
-    roots
+/// `/all` sticks to fixed disks unless the settings opt in to the rest, since
+/// a slow share or an ejected stick can hold up the whole index.
+fn include_drive_root(root: &str, kind: DriveKind, include_non_fixed: bool) -> bool {
+    if kind == DriveKind::Fixed || include_non_fixed {
+        return true;
+    }
+    debug_log(&format!(
+        "index skipped root={} reason={} drive",
+        root,
+        kind.label()
+    ));
+    false
 }
 
 fn drive_letter_from_path(path: &std::path::Path) -> Option<char> {
@@ -234,6 +463,25 @@ fn drive_letter_from_path(path: &std::path::Path) -> Option<char> {
     }
 }
 
+/// The volume an NTFS scan of `root` should read: its drive, or the root itself
+/// for a `\\?\Volume{GUID}\` volume without a drive letter.
+fn volume_root_for(root: &str) -> Option<String> {
+    if volume_guid_key(root).is_some() {
+        return Some(root.to_string());
+    }
+    drive_letter_from_root_str(root).map(|letter| format!("{}:\\", letter))
+}
+
+/// Where the directory walker starts for `root`. A volume without a drive
+/// letter is walked through the folder it is mounted on, so its paths are
+/// the same ones the NTFS reader lists and the shell can open.
+fn walk_root_for(root: &str) -> Option<String> {
+    if volume_guid_key(root).is_none() {
+        return Some(root.to_string());
+    }
+    volume_mount_paths(root).into_iter().next()
+}
+
 fn drive_letter_from_root_str(root: &str) -> Option<char> {
     let bytes = root.as_bytes();
     if bytes.len() >= 2 && bytes[1] == b':' && bytes[0].is_ascii_alphabetic() {
@@ -243,15 +491,34 @@ fn drive_letter_from_root_str(root: &str) -> Option<char> {
     }
 }
 
-fn normalized_folder_prefix(path: &str) -> String {
-    let mut normalized = path.replace('/', "\\").to_ascii_lowercase();
-    if !normalized.ends_with('\\') {
-        normalized.push('\\');
+fn is_volume_root(root: &str) -> bool {
+    if volume_guid_key(root).is_some() {
+        return true;
     }
-    normalized
+    let trimmed = root.trim_end_matches(['\\', '/']);
+    trimmed.len() == 2 && drive_letter_from_root_str(trimmed).is_some()
 }
 
-fn path_starts_with_folder(path: &str, folder_prefix: &str) -> bool {
-    let normalized = path.replace('/', "\\").to_ascii_lowercase();
-    normalized.starts_with(folder_prefix)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_volume_roots() {
+        assert!(is_volume_root("C:\\"));
+        assert!(is_volume_root("d:"));
+        assert!(is_volume_root(
+            r"\\?\Volume{0b9b8b1c-0000-0000-0000-100000000000}\"
+        ));
+        assert!(!is_volume_root(r"C:\Users\me"));
+        assert!(!is_volume_root("relative"));
+    }
+
+    #[test]
+    fn all_drives_keeps_fixed_disks_unless_widened() {
+        assert!(include_drive_root("C:\\", DriveKind::Fixed, false));
+        assert!(!include_drive_root("E:\\", DriveKind::Removable, false));
+        assert!(!include_drive_root("Z:\\", DriveKind::Network, false));
+        assert!(include_drive_root("Z:\\", DriveKind::Network, true));
+    }
 }
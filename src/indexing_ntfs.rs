@@ -3,32 +3,56 @@ mod imp {
     use std::collections::{HashMap, HashSet};
     use std::ffi::c_void;
     use std::os::windows::ffi::OsStrExt;
+    use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::mpsc;
+    use std::thread;
     use std::time::{Duration, Instant};
-    use std::{env, thread};
 
     use serde::{Deserialize, Serialize};
+    use serde_json::json;
 
-    use crate::indexing::scope_roots;
-    use crate::storage::persist_scope_snapshot_async;
+    use super::journal_recovery::{recovery_backoff, MAX_FAILED_RECOVERIES};
+    use super::tree::{
+        build_children_map, collect_descendants, link_child, refresh_paths, remove_subtree,
+        resolve_path, unlink_child, PathEnd, MAX_PATH_DEPTH,
+    };
+    use super::volume_paths::{split_multi_sz, volume_error_message, volume_guid_key};
+    use crate::exclude::{is_excluded, Glob};
+    use crate::feed::ChangeKind;
+    use crate::indexing::{
+        current_folder_root, is_non_ntfs_volume, scope_roots, walk_root_items, IndexOptions,
+    };
+    use crate::path_table::PathTable;
+    use crate::storage::{
+        encode_snapshot, persist_scope_snapshot_async, snapshots_dir, usn_checkpoint_path,
+        write_atomically,
+    };
     use crate::{
-        debug_log, IndexBackend, IndexEvent, SearchItem, SearchItemKind, SearchScope, UNKNOWN_TS,
+        debug_event, debug_log, DebugCategory, IndexBackend, IndexEvent, SearchItem,
+        SearchItemKind, SearchScope, UNKNOWN_SIZE, UNKNOWN_TS,
     };
     use windows_sys::Win32::Foundation::{
-        CloseHandle, GetLastError, ERROR_HANDLE_EOF, ERROR_INVALID_FUNCTION, HANDLE,
-        INVALID_HANDLE_VALUE,
+        CloseHandle, GetLastError, ERROR_HANDLE_EOF, ERROR_INVALID_FUNCTION,
+        ERROR_JOURNAL_NOT_ACTIVE, HANDLE, INVALID_HANDLE_VALUE,
     };
     use windows_sys::Win32::Storage::FileSystem::{
-        CreateFileW, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_NORMAL, FILE_GENERIC_READ,
+        CreateFileW, FindFirstVolumeW, FindNextVolumeW, FindVolumeClose,
+        GetVolumePathNamesForVolumeNameW, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_HIDDEN,
+        FILE_ATTRIBUTE_NORMAL, FILE_ATTRIBUTE_SYSTEM, FILE_GENERIC_READ, FILE_GENERIC_WRITE,
         FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
     };
     use windows_sys::Win32::System::Ioctl::{
-        FSCTL_ENUM_USN_DATA, FSCTL_QUERY_USN_JOURNAL, FSCTL_READ_USN_JOURNAL, MFT_ENUM_DATA_V0,
+        CREATE_USN_JOURNAL_DATA, FSCTL_CREATE_USN_JOURNAL, FSCTL_ENUM_USN_DATA,
+        FSCTL_QUERY_USN_JOURNAL, FSCTL_READ_USN_JOURNAL, MFT_ENUM_DATA_V0,
         READ_USN_JOURNAL_DATA_V0, USN_JOURNAL_DATA_V0, USN_REASON_FILE_CREATE,
-        USN_REASON_FILE_DELETE, USN_REASON_RENAME_NEW_NAME, USN_RECORD_V2,
+        USN_REASON_FILE_DELETE, USN_REASON_RENAME_NEW_NAME, USN_REASON_RENAME_OLD_NAME,
+        USN_RECORD_V2,
     };
     use windows_sys::Win32::System::IO::DeviceIoControl;
 
+    const USN_JOURNAL_MAX_SIZE: u64 = 32 * 1024 * 1024;
+    const USN_JOURNAL_ALLOCATION_DELTA: u64 = 8 * 1024 * 1024;
+
     #[derive(Clone, Serialize, Deserialize)]
     struct NtfsNode {
         parent_id: u64,
@@ -36,25 +60,82 @@ mod imp {
         is_dir: bool,
         modified_unix_secs: i64,
         file_attributes: u32,
+        size: u64,
+    }
+
+    /// Identifies a volume by drive letter or, for volumes mounted into a folder,
+    /// by its `\\?\Volume{GUID}\` name.
+    #[derive(Clone)]
+    struct NtfsVolume {
+        /// `C` or `Volume{GUID}`; names the snapshot and checkpoint entries.
+        key: String,
+        /// Path opened with `CreateFileW`, such as `\\.\C:`.
+        device_path: String,
+        /// Prefix paths are materialized under, such as `C:\` or `D:\Mounts\Data\`.
+        prefix: String,
+    }
+
+    impl NtfsVolume {
+        fn from_root(root: &str) -> Option<NtfsVolume> {
+            if let Some(letter) = parse_drive_root_letter(root) {
+                return Some(NtfsVolume {
+                    key: letter.to_string(),
+                    device_path: format!(r"\\.\{}:", letter),
+                    prefix: format!("{}:\\", letter),
+                });
+            }
+
+            let key = volume_guid_key(root)?;
+            let volume_name = format!(r"\\?\{}\", key);
+            let prefix = volume_mount_paths(&volume_name)
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| volume_name.clone());
+            Some(NtfsVolume {
+                key: key.to_string(),
+                device_path: volume_name.trim_end_matches('\\').to_string(),
+                prefix,
+            })
+        }
     }
 
     struct NtfsVolumeState {
-        drive_letter: char,
+        volume: NtfsVolume,
         drive_prefix: String,
         handle: HANDLE,
         journal_id: u64,
         next_usn: i64,
         nodes: HashMap<u64, NtfsNode>,
+        children: HashMap<u64, HashSet<u64>>,
         path_cache: HashMap<u64, String>,
         id_to_path: HashMap<u64, String>,
         last_snapshot_write: Instant,
         changed_since_snapshot: usize,
+        /// Hidden and system entries are only turned into items when set.
+        include_hidden: bool,
+        /// Journal recoveries that failed in a row, and when to try again.
+        failed_recoveries: u32,
+        retry_at: Option<Instant>,
+    }
+
+    // The volume handle is owned by the state and only used by whichever thread
+    // holds it, so states can be opened on worker threads and handed back.
+    unsafe impl Send for NtfsVolumeState {}
+
+    /// Latest `IndexEvent::Progress` reported by one drive's worker.
+    #[derive(Clone, Copy)]
+    struct VolumeProgress {
+        current: usize,
+        total: usize,
+        scanned: usize,
+        total_is_estimate: bool,
+        phase: &'static str,
     }
 
     #[derive(Serialize, Deserialize)]
     struct NtfsSnapshot {
         version: u32,
-        drive_letter: char,
+        volume: String,
         journal_id: u64,
         next_usn: i64,
         nodes: Vec<NtfsSnapshotNode>,
@@ -70,11 +151,14 @@ mod imp {
         modified_unix_secs: i64,
         #[serde(default)]
         file_attributes: u32,
+        #[serde(default = "unknown_size")]
+        size: u64,
     }
 
     struct JournalBatch {
         upserts: Vec<SearchItem>,
         deleted_paths: Vec<String>,
+        kinds: HashMap<String, ChangeKind>,
         changed_entries: usize,
     }
 
@@ -88,40 +172,52 @@ mod imp {
         scope: SearchScope,
         job_id: u64,
         tx: &mpsc::Sender<IndexEvent>,
+        cancel: &AtomicBool,
+        options: &IndexOptions,
     ) -> bool {
-        let mut states = Vec::new();
-        for root in live_volume_roots(&scope) {
-            debug_log(&format!(
-                "run_ntfs_live_index_job opening state start job_id={} root={}",
-                job_id, root
-            ));
-            if let Some(state) = open_ntfs_volume_state(&root, job_id, tx) {
-                debug_log(&format!(
-                    "run_ntfs_live_index_job opening state success job_id={} root={} nodes={}",
-                    job_id,
-                    root,
-                    state.nodes.len()
-                ));
-                states.push(state);
-            } else {
-                debug_log(&format!(
-                    "run_ntfs_live_index_job opening state failed job_id={} root={}",
-                    job_id, root
-                ));
-            }
-        }
-
+        let excludes = options.excludes.as_slice();
+        let (roots, walked_roots) = live_volume_roots(&scope, options.include_non_fixed_drives);
+        let mut states = open_ntfs_volume_states(&scope, roots, job_id, tx, cancel, options);
         if states.is_empty() {
             return false;
         }
 
-        let initial = filter_items_for_scope(&scope, collect_items_from_ntfs_states(&mut states));
+        // Volumes without a journal are walked once; the NTFS ones stay live.
+        let mut scanned = 0;
+        let walked_items: Vec<SearchItem> = walked_roots
+            .iter()
+            .flat_map(|root| {
+                walk_root_items(
+                    root,
+                    job_id,
+                    tx,
+                    cancel,
+                    excludes,
+                    options.respect_gitignore,
+                    &mut scanned,
+                )
+            })
+            .collect();
+        let backend = if walked_roots.is_empty() {
+            IndexBackend::NtfsUsnLive
+        } else {
+            IndexBackend::Mixed
+        };
+        if cancel.load(Ordering::Relaxed) {
+            for state in states {
+                let _ = unsafe { CloseHandle(state.handle) };
+            }
+            return true;
+        }
+
+        let mut initial = collect_items_from_ntfs_states(&mut states, &scope, excludes);
+        initial.extend(walked_items.iter().cloned());
         persist_scope_snapshot_async(scope.clone(), initial.clone());
         if tx
             .send(IndexEvent::Done {
                 job_id,
                 items: initial,
-                backend: IndexBackend::NtfsUsnLive,
+                backend,
             })
             .is_err()
         {
@@ -132,15 +228,17 @@ mod imp {
         }
 
         let mut keep_running = true;
-        while keep_running {
+        let mut recovered = false;
+        while keep_running && !cancel.load(Ordering::Relaxed) {
             for state in &mut states {
+                if state.retry_at.is_some_and(|at| Instant::now() < at) {
+                    continue;
+                }
                 match poll_ntfs_journal(state) {
                     Some(batch) => {
-                        persist_usn_checkpoint(
-                            state.drive_letter,
-                            state.journal_id,
-                            state.next_usn,
-                        );
+                        state.failed_recoveries = 0;
+                        state.retry_at = None;
+                        persist_usn_checkpoint(&state.volume.key, state.journal_id, state.next_usn);
 
                         if batch.changed_entries > 0 {
                             state.changed_since_snapshot += batch.changed_entries;
@@ -148,13 +246,29 @@ mod imp {
 
                         maybe_persist_ntfs_snapshot(state);
 
-                        let batch = filter_journal_batch_for_scope(&scope, batch);
+                        let changed_entries = batch.changed_entries;
+                        let batch = filter_journal_batch_for_scope(&scope, batch, excludes);
+                        if changed_entries > 0 {
+                            debug_event(
+                                DebugCategory::Usn,
+                                job_id,
+                                json!({
+                                    "event": "batch",
+                                    "volume": state.volume.key,
+                                    "changed": changed_entries,
+                                    "upserts": batch.upserts.len(),
+                                    "deleted": batch.deleted_paths.len(),
+                                    "next_usn": state.next_usn,
+                                }),
+                            );
+                        }
                         if (!batch.upserts.is_empty() || !batch.deleted_paths.is_empty())
                             && tx
                                 .send(IndexEvent::Delta {
                                     job_id,
                                     upserts: batch.upserts,
                                     deleted_paths: batch.deleted_paths,
+                                    kinds: batch.kinds,
                                 })
                                 .is_err()
                         {
@@ -163,30 +277,69 @@ mod imp {
                         }
                     }
                     None => {
-                        if !recover_ntfs_state(state, job_id, tx) {
+                        let ok = recover_ntfs_state(state, job_id, tx, cancel);
+                        debug_event(
+                            DebugCategory::Usn,
+                            job_id,
+                            json!({
+                                "event": "read_failed",
+                                "volume": state.volume.key,
+                                "recovered": ok,
+                                "failures": state.failed_recoveries,
+                            }),
+                        );
+                        if ok {
+                            if state.failed_recoveries > 0 {
+                                debug_log(&format!(
+                                    "usn recovery succeeded volume={} after={} failures",
+                                    state.volume.key, state.failed_recoveries
+                                ));
+                            }
+                            state.failed_recoveries = 0;
+                            state.retry_at = None;
+                            recovered = true;
                             continue;
                         }
 
-                        let items = filter_items_for_scope(
-                            &scope,
-                            collect_items_from_ntfs_states(std::slice::from_mut(state)),
-                        );
-                        persist_scope_snapshot_async(scope.clone(), items.clone());
-                        if tx
-                            .send(IndexEvent::Done {
+                        state.failed_recoveries += 1;
+                        if state.failed_recoveries >= MAX_FAILED_RECOVERIES {
+                            debug_log(&format!(
+                                "usn recovery gave up volume={} failures={}",
+                                state.volume.key, state.failed_recoveries
+                            ));
+                            let _ = tx.send(IndexEvent::LiveUpdatesLost {
                                 job_id,
-                                items,
-                                backend: IndexBackend::NtfsUsnLive,
-                            })
-                            .is_err()
-                        {
+                                volume: state.drive_prefix.trim_end_matches('\\').to_string(),
+                            });
                             keep_running = false;
                             break;
                         }
+                        let delay = recovery_backoff(state.failed_recoveries);
+                        debug_log(&format!(
+                            "usn recovery failed volume={} failures={} retry_in_ms={}",
+                            state.volume.key,
+                            state.failed_recoveries,
+                            delay.as_millis()
+                        ));
+                        state.retry_at = Some(Instant::now() + delay);
                     }
                 }
             }
 
+            if keep_running && recovered {
+                recovered = false;
+                let mut items = collect_items_from_ntfs_states(&mut states, &scope, excludes);
+                items.extend(walked_items.iter().cloned());
+                persist_scope_snapshot_async(scope.clone(), items.clone());
+                keep_running = tx
+                    .send(IndexEvent::Done {
+                        job_id,
+                        items,
+                        backend,
+                    })
+                    .is_ok();
+            }
+
             if keep_running {
                 thread::sleep(Duration::from_millis(300));
             }
@@ -202,33 +355,164 @@ mod imp {
         true
     }
 
-    pub(crate) fn try_index_ntfs_volume(
-        root: &str,
+    /// Enumerates every volume in the scope on its own thread. Progress from the
+    /// workers is summed into one stream, and when more than one drive is being
+    /// indexed each finished drive is sent as `IndexEvent::VolumeLoaded` so its
+    /// results show up before the slower drives are done.
+    fn open_ntfs_volume_states(
+        scope: &SearchScope,
+        roots: Vec<String>,
         job_id: u64,
         tx: &mpsc::Sender<IndexEvent>,
-    ) -> Option<Vec<SearchItem>> {
-        let drive = parse_drive_root_letter(root)?;
-        let handle = open_volume_handle(drive)?;
+        cancel: &AtomicBool,
+        options: &IndexOptions,
+    ) -> Vec<NtfsVolumeState> {
+        let excludes = options.excludes.as_slice();
+        let announce_volumes = roots.len() > 1;
+        let mut states = Vec::new();
 
-        let mut journal = USN_JOURNAL_DATA_V0::default();
-        let mut bytes_returned = 0u32;
-        let query_ok = unsafe {
-            DeviceIoControl(
-                handle,
-                FSCTL_QUERY_USN_JOURNAL,
-                std::ptr::null_mut(),
-                0,
-                &mut journal as *mut _ as *mut c_void,
-                std::mem::size_of::<USN_JOURNAL_DATA_V0>() as u32,
-                &mut bytes_returned,
-                std::ptr::null_mut(),
-            )
-        };
+        thread::scope(|s| {
+            let mut workers: Vec<_> = roots
+                .iter()
+                .map(|root| {
+                    let (worker_tx, worker_rx) = mpsc::channel();
+                    let handle = s.spawn(move || {
+                        debug_log(&format!(
+                            "run_ntfs_live_index_job opening state start job_id={} root={}",
+                            job_id, root
+                        ));
+                        let state = open_ntfs_volume_state(root, job_id, &worker_tx, cancel, options);
+                        match &state {
+                            Some(state) => debug_log(&format!(
+                                "run_ntfs_live_index_job opening state success job_id={} root={} nodes={}",
+                                job_id,
+                                root,
+                                state.nodes.len()
+                            )),
+                            None => debug_log(&format!(
+                                "run_ntfs_live_index_job opening state failed job_id={} root={}",
+                                job_id, root
+                            )),
+                        }
+                        debug_event(
+                            DebugCategory::Index,
+                            job_id,
+                            json!({
+                                "event": "volume_opened",
+                                "root": root,
+                                "ok": state.is_some(),
+                                "nodes": state.as_ref().map(|state| state.nodes.len()),
+                            }),
+                        );
+                        state
+                    });
+                    (worker_rx, Some(handle))
+                })
+                .collect();
+            let mut progress: Vec<Option<VolumeProgress>> = vec![None; workers.len()];
+
+            while workers.iter().any(|(_, handle)| handle.is_some()) {
+                thread::sleep(Duration::from_millis(100));
+
+                let mut progress_changed = false;
+                for (index, (worker_rx, _)) in workers.iter().enumerate() {
+                    while let Ok(event) = worker_rx.try_recv() {
+                        if matches!(event, IndexEvent::Error { .. }) {
+                            let _ = tx.send(event);
+                        } else if let IndexEvent::Progress {
+                            current,
+                            total,
+                            scanned,
+                            total_is_estimate,
+                            phase,
+                            ..
+                        } = event
+                        {
+                            progress[index] = Some(VolumeProgress {
+                                current,
+                                total,
+                                scanned,
+                                total_is_estimate,
+                                phase,
+                            });
+                            progress_changed = true;
+                        }
+                    }
+                }
+                if progress_changed {
+                    let _ = tx.send(combined_progress(job_id, &progress));
+                }
 
-        if query_ok == 0 {
-            let _ = unsafe { CloseHandle(handle) };
-            return None;
+                for (worker_rx, handle) in &mut workers {
+                    if !handle.as_ref().is_some_and(|handle| handle.is_finished()) {
+                        continue;
+                    }
+                    for event in worker_rx.try_iter() {
+                        if matches!(event, IndexEvent::Error { .. }) {
+                            let _ = tx.send(event);
+                        }
+                    }
+                    let Some(mut state) = handle
+                        .take()
+                        .and_then(|handle| handle.join().ok())
+                        .flatten()
+                    else {
+                        continue;
+                    };
+
+                    if announce_volumes && !cancel.load(Ordering::Relaxed) {
+                        let items = collect_items_from_ntfs_states(
+                            std::slice::from_mut(&mut state),
+                            scope,
+                            excludes,
+                        );
+                        let _ = tx.send(IndexEvent::VolumeLoaded {
+                            job_id,
+                            volume: state.volume.prefix.clone(),
+                            items,
+                        });
+                    }
+                    states.push(state);
+                }
+            }
+        });
+
+        states
+    }
+
+    fn combined_progress(job_id: u64, progress: &[Option<VolumeProgress>]) -> IndexEvent {
+        let reported: Vec<VolumeProgress> = progress.iter().flatten().copied().collect();
+        IndexEvent::Progress {
+            job_id,
+            current: reported.iter().map(|p| p.current).sum(),
+            total: reported.iter().map(|p| p.total).sum(),
+            scanned: reported.iter().map(|p| p.scanned).sum(),
+            total_is_estimate: reported.iter().any(|p| p.total_is_estimate),
+            phase: reported
+                .iter()
+                .map(|p| p.phase)
+                .find(|phase| *phase == "index")
+                .or_else(|| reported.first().map(|p| p.phase))
+                .unwrap_or("index"),
         }
+    }
+
+    pub(crate) fn try_index_ntfs_volume(
+        root: &str,
+        job_id: u64,
+        tx: &mpsc::Sender<IndexEvent>,
+        cancel: &AtomicBool,
+        options: &IndexOptions,
+    ) -> Option<Vec<SearchItem>> {
+        let volume = NtfsVolume::from_root(root)?;
+        let (handle, journal) =
+            match open_volume_and_query_journal(&volume, options.create_usn_journal) {
+                Ok(opened) => opened,
+                Err(code) => {
+                    report_volume_error(root, code, job_id, tx);
+                    return None;
+                }
+            };
 
         let mut enum_data = MFT_ENUM_DATA_V0 {
             StartFileReferenceNumber: 0,
@@ -244,6 +528,11 @@ mod imp {
         let mut buffer = vec![0u8; 1024 * 1024];
 
         loop {
+            if cancel.load(Ordering::Relaxed) {
+                let _ = unsafe { CloseHandle(handle) };
+                return None;
+            }
+
             let mut out_bytes = 0u32;
             let ok = unsafe {
                 DeviceIoControl(
@@ -296,6 +585,7 @@ mod imp {
                                 modified_unix_secs: filetime_100ns_to_unix_secs(rec.TimeStamp)
                                     .unwrap_or(UNKNOWN_TS),
                                 file_attributes: rec.FileAttributes,
+                                size: UNKNOWN_SIZE,
                             },
                         );
 
@@ -306,6 +596,8 @@ mod imp {
                                 job_id,
                                 current: current.min(usn_total),
                                 total: usn_total,
+                                scanned,
+                                total_is_estimate: true,
                                 phase: "index",
                             });
                         }
@@ -318,17 +610,30 @@ mod imp {
 
         let _ = unsafe { CloseHandle(handle) };
 
-        let drive_prefix = format!("{}:\\", drive.to_ascii_uppercase());
+        let drive_prefix = volume.prefix;
         let mut path_cache: HashMap<u64, String> = HashMap::new();
+        let mut folders = PathTable::default();
         let mut out = Vec::new();
 
-        for (id, node) in &raw_nodes {
+        for (idx, (id, node)) in raw_nodes.iter().enumerate() {
+            if idx.is_multiple_of(5000) && cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+            if !options.include_hidden && is_hidden_or_system(node) {
+                continue;
+            }
             let path = materialize_full_path(*id, &raw_nodes, &mut path_cache, &drive_prefix);
-            out.push(SearchItem {
-                path: path.into_boxed_str(),
-                modified_unix_secs: node.modified_unix_secs,
-                kind: search_item_kind(node),
-            });
+            out.push(
+                folders
+                    .item(
+                        &path,
+                        node.modified_unix_secs,
+                        node.size,
+                        search_item_kind(node),
+                    )
+                    .with_attrs(node.file_attributes)
+                    .with_file_id(*id),
+            );
         }
 
         Some(out)
@@ -338,32 +643,53 @@ mod imp {
         root: &str,
         job_id: u64,
         tx: &mpsc::Sender<IndexEvent>,
+        cancel: &AtomicBool,
+        options: &IndexOptions,
     ) -> Option<NtfsVolumeState> {
-        let drive = parse_drive_root_letter(root)?;
-        let (handle, journal) = open_volume_and_query_journal(drive)?;
+        let volume = NtfsVolume::from_root(root)?;
+        let (handle, journal) =
+            match open_volume_and_query_journal(&volume, options.create_usn_journal) {
+                Ok(opened) => opened,
+                Err(code) => {
+                    report_volume_error(root, code, job_id, tx);
+                    return None;
+                }
+            };
 
-        let Some(nodes) =
-            enumerate_ntfs_nodes(handle, journal.FirstUsn, journal.NextUsn, job_id, tx)
-        else {
+        let Some(nodes) = enumerate_ntfs_nodes(
+            handle,
+            journal.FirstUsn,
+            journal.NextUsn,
+            job_id,
+            tx,
+            cancel,
+        ) else {
             let _ = unsafe { CloseHandle(handle) };
             return None;
         };
 
         let mut state = NtfsVolumeState {
-            drive_letter: drive,
-            drive_prefix: format!("{}:\\", drive.to_ascii_uppercase()),
+            drive_prefix: volume.prefix.clone(),
+            volume,
             handle,
             journal_id: journal.UsnJournalID,
             next_usn: journal.NextUsn,
+            children: build_children_map(nodes.iter().map(|(id, node)| (*id, node.parent_id))),
             nodes,
             path_cache: HashMap::new(),
             id_to_path: HashMap::new(),
             last_snapshot_write: Instant::now(),
             changed_since_snapshot: 0,
+            include_hidden: options.include_hidden,
+            failed_recoveries: 0,
+            retry_at: None,
         };
 
-        initialize_id_path_map(&mut state, job_id, tx);
-        persist_usn_checkpoint(drive, state.journal_id, state.next_usn);
+        if !initialize_id_path_map(&mut state, job_id, tx, cancel) {
+            let _ = unsafe { CloseHandle(state.handle) };
+            return None;
+        }
+        persist_usn_checkpoint(&state.volume.key, state.journal_id, state.next_usn);
         Some(state)
     }
 
@@ -373,6 +699,7 @@ mod imp {
         high_usn: i64,
         job_id: u64,
         tx: &mpsc::Sender<IndexEvent>,
+        cancel: &AtomicBool,
     ) -> Option<HashMap<u64, NtfsNode>> {
         let mut enum_data = MFT_ENUM_DATA_V0 {
             StartFileReferenceNumber: 0,
@@ -388,6 +715,10 @@ mod imp {
         let mut buffer = vec![0u8; 1024 * 1024];
 
         loop {
+            if cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+
             let mut out_bytes = 0u32;
             let ok = unsafe {
                 DeviceIoControl(
@@ -437,6 +768,7 @@ mod imp {
                                 modified_unix_secs: filetime_100ns_to_unix_secs(rec.TimeStamp)
                                     .unwrap_or(UNKNOWN_TS),
                                 file_attributes: rec.FileAttributes,
+                                size: UNKNOWN_SIZE,
                             },
                         );
 
@@ -447,6 +779,8 @@ mod imp {
                                 job_id,
                                 current: current.min(progress_total),
                                 total: progress_total,
+                                scanned,
+                                total_is_estimate: true,
                                 phase: "index",
                             });
                         }
@@ -492,6 +826,7 @@ mod imp {
                 return Some(JournalBatch {
                     upserts: Vec::new(),
                     deleted_paths: Vec::new(),
+                    kinds: HashMap::new(),
                     changed_entries: 0,
                 });
             }
@@ -502,6 +837,7 @@ mod imp {
             return Some(JournalBatch {
                 upserts: Vec::new(),
                 deleted_paths: Vec::new(),
+                kinds: HashMap::new(),
                 changed_entries: 0,
             });
         }
@@ -509,7 +845,9 @@ mod imp {
         state.next_usn = unsafe { *(buffer.as_ptr() as *const i64) };
 
         let mut changed_ids: HashSet<u64> = HashSet::new();
+        let mut change_kinds: HashMap<u64, ChangeKind> = HashMap::new();
         let mut deleted_ids: Vec<u64> = Vec::new();
+        let mut moved_dirs: Vec<u64> = Vec::new();
         let mut offset = 8usize;
 
         while offset < out_bytes as usize {
@@ -524,7 +862,10 @@ mod imp {
                 let id = rec.FileReferenceNumber;
 
                 if (reason & USN_REASON_FILE_DELETE) != 0 {
-                    let removed_ids = remove_ntfs_node_and_descendants(&mut state.nodes, id);
+                    let removed_ids =
+                        remove_subtree(&mut state.nodes, &mut state.children, id, |node| {
+                            node.parent_id
+                        });
                     if !removed_ids.is_empty() {
                         deleted_ids.extend(removed_ids);
                     }
@@ -532,8 +873,28 @@ mod imp {
                     continue;
                 }
 
+                // The old-name record carries the pre-rename name; the matching
+                // RENAME_NEW_NAME record updates the node, so only mark it here.
+                if (reason & (USN_REASON_RENAME_OLD_NAME | USN_REASON_RENAME_NEW_NAME))
+                    == USN_REASON_RENAME_OLD_NAME
+                {
+                    if state.nodes.contains_key(&id) {
+                        changed_ids.insert(id);
+                        note_change(&mut change_kinds, id, ChangeKind::Renamed);
+                    }
+                    offset += record_len;
+                    continue;
+                }
+
                 let name = read_usn_v2_name(buffer.as_ptr(), offset, rec);
                 if !name.is_empty() {
+                    let kind = if (reason & USN_REASON_FILE_CREATE) != 0 {
+                        ChangeKind::Created
+                    } else if (reason & USN_REASON_RENAME_NEW_NAME) != 0 {
+                        ChangeKind::Renamed
+                    } else {
+                        ChangeKind::Modified
+                    };
                     let is_dir = (rec.FileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0;
                     let new_node = NtfsNode {
                         parent_id: rec.ParentFileReferenceNumber,
@@ -542,6 +903,7 @@ mod imp {
                         modified_unix_secs: filetime_100ns_to_unix_secs(rec.TimeStamp)
                             .unwrap_or(UNKNOWN_TS),
                         file_attributes: rec.FileAttributes,
+                        size: UNKNOWN_SIZE,
                     };
 
                     let needs_update = state.nodes.get(&id).is_none_or(|existing| {
@@ -553,12 +915,26 @@ mod imp {
                     });
 
                     if needs_update {
-                        state.nodes.insert(id, new_node);
+                        let new_parent_id = new_node.parent_id;
+                        let dir_moved = state.nodes.get(&id).is_some_and(|existing| {
+                            existing.is_dir
+                                && (existing.parent_id != new_node.parent_id
+                                    || existing.name != new_node.name)
+                        });
+                        if let Some(old) = state.nodes.insert(id, new_node) {
+                            unlink_child(&mut state.children, old.parent_id, id);
+                        }
+                        if dir_moved {
+                            moved_dirs.push(id);
+                        }
+                        link_child(&mut state.children, new_parent_id, id);
                         changed_ids.insert(id);
+                        note_change(&mut change_kinds, id, kind);
                     }
 
                     if (reason & (USN_REASON_FILE_CREATE | USN_REASON_RENAME_NEW_NAME)) != 0 {
                         changed_ids.insert(id);
+                        note_change(&mut change_kinds, id, kind);
                     }
                 }
             }
@@ -577,25 +953,67 @@ mod imp {
             }
         }
 
+        // Descendants of a renamed or moved folder get no records of their own,
+        // so their paths have to be re-emitted from here.
+        let mut moved_descendants: HashSet<u64> = HashSet::new();
+        for dir_id in moved_dirs {
+            for descendant in collect_descendants(&state.children, dir_id) {
+                if !changed_ids.contains(&descendant) {
+                    moved_descendants.insert(descendant);
+                }
+            }
+        }
+
+        let (refreshed, stale_paths) = refresh_paths(
+            changed_ids.iter().chain(moved_descendants.iter()).copied(),
+            &mut state.id_to_path,
+            |id| {
+                state.nodes.contains_key(&id).then(|| {
+                    materialize_full_path(
+                        id,
+                        &state.nodes,
+                        &mut state.path_cache,
+                        &state.drive_prefix,
+                    )
+                })
+            },
+        );
+        deleted_paths.extend(stale_paths);
+
         let mut upserts = Vec::new();
-        for id in changed_ids {
-            let Some(node) = state.nodes.get(&id) else {
+        let mut kinds = HashMap::new();
+        for (id, path) in refreshed {
+            let Some(node) = state.nodes.get_mut(&id) else {
                 continue;
             };
-
-            let path =
-                materialize_full_path(id, &state.nodes, &mut state.path_cache, &state.drive_prefix);
-            if let Some(old_path) = state.id_to_path.insert(id, path.clone()) {
-                if old_path != path {
-                    deleted_paths.push(old_path);
-                }
+            if !state.include_hidden && is_hidden_or_system(node) {
+                continue;
+            }
+            if !node.is_dir && !moved_descendants.contains(&id) {
+                node.size = std::fs::metadata(&path)
+                    .map(|metadata| metadata.len())
+                    .unwrap_or(UNKNOWN_SIZE);
             }
 
-            upserts.push(SearchItem {
-                path: path.into_boxed_str(),
-                modified_unix_secs: node.modified_unix_secs,
-                kind: search_item_kind(node),
-            });
+            upserts.push(
+                SearchItem::new(
+                    &path,
+                    node.modified_unix_secs,
+                    node.size,
+                    search_item_kind(node),
+                )
+                .with_attrs(node.file_attributes)
+                .with_file_id(id),
+            );
+            // Items under a moved folder only changed path.
+            let kind = if moved_descendants.contains(&id) {
+                Some(ChangeKind::Renamed)
+            } else {
+                change_kinds.get(&id).copied()
+            };
+            if let Some(kind) = kind {
+                kinds.insert(path, kind);
+            }
         }
 
         let changed_entries = upserts.len() + deleted_paths.len();
@@ -603,65 +1021,88 @@ mod imp {
         Some(JournalBatch {
             upserts,
             deleted_paths,
+            kinds,
             changed_entries,
         })
     }
 
-    fn remove_ntfs_node_and_descendants(nodes: &mut HashMap<u64, NtfsNode>, id: u64) -> Vec<u64> {
-        if !nodes.contains_key(&id) {
-            return Vec::new();
-        }
-
-        let mut to_remove = vec![id];
-        let mut index = 0usize;
-
-        while index < to_remove.len() {
-            let parent = to_remove[index];
-            for (candidate_id, node) in nodes.iter() {
-                if node.parent_id == parent && *candidate_id != parent {
-                    to_remove.push(*candidate_id);
-                }
-            }
-            index += 1;
-        }
-
-        let mut removed_ids = Vec::new();
-        for target in to_remove {
-            if nodes.remove(&target).is_some() {
-                removed_ids.push(target);
-            }
-        }
-
-        removed_ids
+    fn note_change(kinds: &mut HashMap<u64, ChangeKind>, id: u64, kind: ChangeKind) {
+        kinds
+            .entry(id)
+            .and_modify(|known| *known = known.merge(kind))
+            .or_insert(kind);
     }
 
-    fn collect_items_from_ntfs_states(states: &mut [NtfsVolumeState]) -> Vec<SearchItem> {
+    /// For a `CurrentFolder` scope only the folder's subtree is turned into
+    /// items, instead of every record on the volume.
+    fn collect_items_from_ntfs_states(
+        states: &mut [NtfsVolumeState],
+        scope: &SearchScope,
+        excludes: &[Glob],
+    ) -> Vec<SearchItem> {
+        let folder = match scope {
+            SearchScope::CurrentFolder => Some(current_folder_root()),
+            _ => None,
+        };
+        let mut folders = PathTable::default();
         let mut out = Vec::new();
 
         for state in states {
-            for (id, node) in &state.nodes {
+            let ids: Vec<u64> = match folder.as_deref().and_then(|f| folder_node_id(state, f)) {
+                Some(folder_id) => {
+                    let mut ids = collect_descendants(&state.children, folder_id);
+                    ids.push(folder_id);
+                    ids
+                }
+                None => state.nodes.keys().copied().collect(),
+            };
+
+            for id in ids {
+                let Some(node) = state.nodes.get(&id) else {
+                    continue;
+                };
+                if !state.include_hidden && is_hidden_or_system(node) {
+                    continue;
+                }
                 let path = materialize_full_path(
-                    *id,
+                    id,
                     &state.nodes,
                     &mut state.path_cache,
                     &state.drive_prefix,
                 );
-                out.push(SearchItem {
-                    path: path.into_boxed_str(),
-                    modified_unix_secs: node.modified_unix_secs,
-                    kind: search_item_kind(node),
-                });
+                if folder
+                    .as_deref()
+                    .is_some_and(|folder| !path_is_in_folder_scope(&path, folder))
+                {
+                    continue;
+                }
+                if is_excluded(&path, excludes) {
+                    continue;
+                }
+                out.push(
+                    folders
+                        .item(
+                            &path,
+                            node.modified_unix_secs,
+                            node.size,
+                            search_item_kind(node),
+                        )
+                        .with_attrs(node.file_attributes)
+                        .with_file_id(id),
+                );
             }
         }
 
         out
     }
 
+    /// Returns `false` if the job was cancelled before every path was built.
     fn initialize_id_path_map(
         state: &mut NtfsVolumeState,
         job_id: u64,
         tx: &mpsc::Sender<IndexEvent>,
-    ) {
+        cancel: &AtomicBool,
+    ) -> bool {
         state.id_to_path.clear();
 
         let ids: Vec<u64> = state.nodes.keys().copied().collect();
@@ -674,29 +1115,36 @@ mod imp {
             state.id_to_path.insert(id, path);
 
             if (idx + 1).is_multiple_of(5000) {
+                if cancel.load(Ordering::Relaxed) {
+                    return false;
+                }
                 let _ = tx.send(IndexEvent::Progress {
                     job_id,
                     current: idx + 1,
                     total: ids_total,
+                    scanned: idx + 1,
+                    total_is_estimate: false,
                     phase: "write",
                 });
             }
         }
+        true
     }
 
     fn recover_ntfs_state(
         state: &mut NtfsVolumeState,
         job_id: u64,
         tx: &mpsc::Sender<IndexEvent>,
+        cancel: &AtomicBool,
     ) -> bool {
         let old_handle = state.handle;
 
-        let Some((new_handle, journal)) = open_volume_and_query_journal(state.drive_letter) else {
+        let Ok((new_handle, journal)) = open_volume_and_query_journal(&state.volume, false) else {
             return false;
         };
 
         let mut resume_usn = state.next_usn;
-        if let Some(saved) = load_usn_checkpoint(state.drive_letter) {
+        if let Some(saved) = load_usn_checkpoint(&state.volume.key) {
             if saved.journal_id == journal.UsnJournalID {
                 resume_usn = resume_usn.max(saved.next_usn);
             }
@@ -716,14 +1164,24 @@ mod imp {
         if can_continue {
             state.handle = new_handle;
             state.next_usn = resume_usn;
-            persist_usn_checkpoint(state.drive_letter, state.journal_id, state.next_usn);
+            persist_usn_checkpoint(&state.volume.key, state.journal_id, state.next_usn);
             let _ = unsafe { CloseHandle(old_handle) };
+            debug_event(
+                DebugCategory::Usn,
+                job_id,
+                json!({ "event": "resumed", "volume": state.volume.key, "next_usn": resume_usn }),
+            );
             return true;
         }
 
-        let Some(nodes) =
-            enumerate_ntfs_nodes(new_handle, journal.FirstUsn, journal.NextUsn, job_id, tx)
-        else {
+        let Some(nodes) = enumerate_ntfs_nodes(
+            new_handle,
+            journal.FirstUsn,
+            journal.NextUsn,
+            job_id,
+            tx,
+            cancel,
+        ) else {
             let _ = unsafe { CloseHandle(new_handle) };
             return false;
         };
@@ -731,20 +1189,75 @@ mod imp {
         state.handle = new_handle;
         state.journal_id = journal.UsnJournalID;
         state.next_usn = journal.NextUsn;
+        state.children = build_children_map(nodes.iter().map(|(id, node)| (*id, node.parent_id)));
         state.nodes = nodes;
         state.path_cache.clear();
-        initialize_id_path_map(state, job_id, tx);
+        if !initialize_id_path_map(state, job_id, tx, cancel) {
+            let _ = unsafe { CloseHandle(old_handle) };
+            return false;
+        }
         state.changed_since_snapshot = 0;
         state.last_snapshot_write = Instant::now();
-        persist_usn_checkpoint(state.drive_letter, state.journal_id, state.next_usn);
+        persist_usn_checkpoint(&state.volume.key, state.journal_id, state.next_usn);
+        debug_event(
+            DebugCategory::Usn,
+            job_id,
+            json!({
+                "event": "reenumerated",
+                "volume": state.volume.key,
+                "journal_id": state.journal_id,
+                "nodes": state.nodes.len(),
+            }),
+        );
 
         let _ = unsafe { CloseHandle(old_handle) };
         true
     }
 
-    fn open_volume_and_query_journal(drive: char) -> Option<(HANDLE, USN_JOURNAL_DATA_V0)> {
-        let handle = open_volume_handle(drive)?;
+    /// Sends why a volume could not be read to the app, which shows it once
+    /// the job settles instead of leaving the results silently empty.
+    fn report_volume_error(root: &str, code: u32, job_id: u64, tx: &mpsc::Sender<IndexEvent>) {
+        let message = volume_error_message(root, code);
+        debug_log(&format!(
+            "ntfs volume error job_id={} root={} code={} message={}",
+            job_id, root, code, message
+        ));
+        let _ = tx.send(IndexEvent::Error {
+            job_id,
+            drive: root.to_string(),
+            message,
+        });
+    }
 
+    /// Opens the volume and reads its journal position; errors carry the
+    /// `GetLastError` code of the call that failed. With `create_missing` a
+    /// volume without a journal gets one before the query is retried.
+    fn open_volume_and_query_journal(
+        volume: &NtfsVolume,
+        create_missing: bool,
+    ) -> Result<(HANDLE, USN_JOURNAL_DATA_V0), u32> {
+        let handle = open_volume_handle(volume)?;
+        match query_journal(handle) {
+            Ok(journal) => Ok((handle, journal)),
+            Err(ERROR_JOURNAL_NOT_ACTIVE) if create_missing => {
+                let created = create_usn_journal(volume);
+                let result = created.and_then(|()| query_journal(handle));
+                match result {
+                    Ok(journal) => Ok((handle, journal)),
+                    Err(err) => {
+                        let _ = unsafe { CloseHandle(handle) };
+                        Err(err)
+                    }
+                }
+            }
+            Err(err) => {
+                let _ = unsafe { CloseHandle(handle) };
+                Err(err)
+            }
+        }
+    }
+
+    fn query_journal(handle: HANDLE) -> Result<USN_JOURNAL_DATA_V0, u32> {
         let mut journal = USN_JOURNAL_DATA_V0::default();
         let mut bytes_returned = 0u32;
         let query_ok = unsafe {
@@ -761,17 +1274,75 @@ mod imp {
         };
 
         if query_ok == 0 {
-            let _ = unsafe { CloseHandle(handle) };
-            return None;
+            return Err(unsafe { GetLastError() });
         }
 
-        Some((handle, journal))
+        Ok(journal)
     }
 
-    fn open_volume_handle(drive: char) -> Option<HANDLE> {
-        let volume_path = format!(r"\\.\{}:", drive.to_ascii_uppercase());
-        let volume_wide = to_wide(&volume_path);
+    /// Creates the USN journal with the sizes `fsutil usn createjournal` would
+    /// pick for a small volume. Needs a handle opened for writing, which in
+    /// turn needs an elevated process.
+    fn create_usn_journal(volume: &NtfsVolume) -> Result<(), u32> {
+        debug_log(&format!(
+            "ntfs creating usn journal volume={} max_size={} allocation_delta={}",
+            volume.key, USN_JOURNAL_MAX_SIZE, USN_JOURNAL_ALLOCATION_DELTA
+        ));
+        let volume_wide = to_wide(&volume.device_path);
+        let handle = unsafe {
+            CreateFileW(
+                volume_wide.as_ptr(),
+                FILE_GENERIC_READ | FILE_GENERIC_WRITE,
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                std::ptr::null(),
+                OPEN_EXISTING,
+                FILE_ATTRIBUTE_NORMAL,
+                std::ptr::null_mut(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            let err = unsafe { GetLastError() };
+            debug_log(&format!(
+                "ntfs create usn journal open failed volume={} err={}",
+                volume.key, err
+            ));
+            return Err(err);
+        }
+
+        let data = CREATE_USN_JOURNAL_DATA {
+            MaximumSize: USN_JOURNAL_MAX_SIZE,
+            AllocationDelta: USN_JOURNAL_ALLOCATION_DELTA,
+        };
+        let mut bytes_returned = 0u32;
+        let ok = unsafe {
+            DeviceIoControl(
+                handle,
+                FSCTL_CREATE_USN_JOURNAL,
+                &data as *const _ as *const c_void,
+                std::mem::size_of::<CREATE_USN_JOURNAL_DATA>() as u32,
+                std::ptr::null_mut(),
+                0,
+                &mut bytes_returned,
+                std::ptr::null_mut(),
+            )
+        };
+        let result = if ok == 0 {
+            Err(unsafe { GetLastError() })
+        } else {
+            Ok(())
+        };
+        let _ = unsafe { CloseHandle(handle) };
+        debug_log(&format!(
+            "ntfs create usn journal volume={} result={:?}",
+            volume.key, result
+        ));
+        result
+    }
 
+    fn open_volume_handle(volume: &NtfsVolume) -> Result<HANDLE, u32> {
+        let volume_wide = to_wide(&volume.device_path);
+
+        let mut err = 0;
         for desired_access in [FILE_GENERIC_READ, 0] {
             let handle = unsafe {
                 CreateFileW(
@@ -786,32 +1357,32 @@ mod imp {
             };
 
             if handle != INVALID_HANDLE_VALUE {
-                return Some(handle);
+                return Ok(handle);
+            }
+            // The read attempt explains the failure better than the retry.
+            if err == 0 {
+                err = unsafe { GetLastError() };
             }
         }
 
-        None
+        Err(err)
     }
 
     fn checkpoint_file_path() -> std::path::PathBuf {
-        let base = env::var("LOCALAPPDATA").unwrap_or_else(|_| ".".to_string());
-        std::path::PathBuf::from(base)
-            .join("WizMini")
-            .join("usn_checkpoints.txt")
+        usn_checkpoint_path()
     }
 
-    fn load_usn_checkpoint(drive: char) -> Option<UsnCheckpoint> {
+    fn load_usn_checkpoint(key: &str) -> Option<UsnCheckpoint> {
         let path = checkpoint_file_path();
         let content = std::fs::read_to_string(path).ok()?;
-        let key = drive.to_ascii_uppercase();
 
         for line in content.lines() {
             let mut parts = line.split(',');
-            let drive_part = parts.next()?.chars().next()?.to_ascii_uppercase();
+            let key_part = parts.next()?;
             let journal_id = parts.next()?.parse::<u64>().ok()?;
             let next_usn = parts.next()?.parse::<i64>().ok()?;
 
-            if drive_part == key {
+            if key_part.eq_ignore_ascii_case(key) {
                 return Some(UsnCheckpoint {
                     journal_id,
                     next_usn,
@@ -822,7 +1393,7 @@ mod imp {
         None
     }
 
-    fn persist_usn_checkpoint(drive: char, journal_id: u64, next_usn: i64) {
+    fn persist_usn_checkpoint(key: &str, journal_id: u64, next_usn: i64) {
         let path = checkpoint_file_path();
         if let Some(dir) = path.parent() {
             if std::fs::create_dir_all(dir).is_err() {
@@ -830,11 +1401,11 @@ mod imp {
             }
         }
 
-        let mut map: HashMap<char, UsnCheckpoint> = HashMap::new();
+        let mut map: HashMap<String, UsnCheckpoint> = HashMap::new();
         if let Ok(content) = std::fs::read_to_string(&path) {
             for line in content.lines() {
                 let mut parts = line.split(',');
-                let Some(drive_part) = parts.next().and_then(|v| v.chars().next()) else {
+                let Some(key_part) = parts.next().filter(|v| !v.is_empty()) else {
                     continue;
                 };
                 let Some(journal) = parts.next().and_then(|v| v.parse::<u64>().ok()) else {
@@ -844,7 +1415,7 @@ mod imp {
                     continue;
                 };
                 map.insert(
-                    drive_part.to_ascii_uppercase(),
+                    key_part.to_ascii_uppercase(),
                     UsnCheckpoint {
                         journal_id: journal,
                         next_usn: usn,
@@ -854,7 +1425,7 @@ mod imp {
         }
 
         map.insert(
-            drive.to_ascii_uppercase(),
+            key.to_ascii_uppercase(),
             UsnCheckpoint {
                 journal_id,
                 next_usn,
@@ -862,28 +1433,26 @@ mod imp {
         );
 
         let mut lines = Vec::new();
-        for (letter, entry) in map {
-            lines.push(format!(
-                "{},{},{}",
-                letter, entry.journal_id, entry.next_usn
-            ));
+        for (key, entry) in map {
+            lines.push(format!("{},{},{}", key, entry.journal_id, entry.next_usn));
         }
 
         let _ = std::fs::write(path, lines.join("\n"));
     }
 
-    fn snapshot_file_path(drive: char) -> std::path::PathBuf {
-        let base = env::var("LOCALAPPDATA").unwrap_or_else(|_| ".".to_string());
-        std::path::PathBuf::from(base)
-            .join("WizMini")
-            .join("snapshots")
-            .join(format!("{}.bin", drive.to_ascii_uppercase()))
+    fn snapshot_file_path(key: &str) -> std::path::PathBuf {
+        snapshots_dir().join(format!("{}.bin", key))
     }
 
-    fn live_volume_roots(scope: &SearchScope) -> Vec<String> {
+    /// The volumes in `scope`, split into NTFS volumes for the journal and
+    /// volumes with another file system that have to be walked instead.
+    fn live_volume_roots(
+        scope: &SearchScope,
+        include_non_fixed: bool,
+    ) -> (Vec<String>, Vec<String>) {
         let mut roots = Vec::new();
-        for root in scope_roots(scope) {
-            if parse_drive_root_letter(&root).is_some() {
+        for root in scope_roots(scope, include_non_fixed) {
+            if parse_drive_root_letter(&root).is_some() || volume_guid_key(&root).is_some() {
                 roots.push(root);
             } else if let Some(letter) = drive_letter_from_path_str(&root) {
                 roots.push(format!("{}:\\", letter.to_ascii_uppercase()));
@@ -892,48 +1461,58 @@ mod imp {
         roots.sort();
         roots.dedup();
         roots
+            .into_iter()
+            .partition(|root| !is_non_ntfs_volume(root))
     }
 
-    fn filter_items_for_scope(scope: &SearchScope, items: Vec<SearchItem>) -> Vec<SearchItem> {
-        if !matches!(scope, SearchScope::CurrentFolder) {
-            return items;
-        }
-
-        let roots = scope_roots(scope);
-        let Some(folder) = roots.first() else {
-            return items;
-        };
-        items
-            .into_iter()
-            .filter(|item| path_is_in_folder_scope(item.path.as_ref(), folder))
-            .collect()
+    /// Finds the directory record for `folder` among the paths already built
+    /// for the journal, without allocating per record.
+    fn folder_node_id(state: &NtfsVolumeState, folder: &str) -> Option<u64> {
+        let folder = folder.replace('/', "\\");
+        let folder = folder.trim_end_matches('\\');
+        state
+            .id_to_path
+            .iter()
+            .find(|(id, path)| {
+                path.trim_end_matches('\\').eq_ignore_ascii_case(folder)
+                    && state.nodes.get(id).is_some_and(|node| node.is_dir)
+            })
+            .map(|(id, _)| *id)
     }
 
-    fn filter_journal_batch_for_scope(scope: &SearchScope, batch: JournalBatch) -> JournalBatch {
+    fn filter_journal_batch_for_scope(
+        scope: &SearchScope,
+        mut batch: JournalBatch,
+        excludes: &[Glob],
+    ) -> JournalBatch {
+        if !excludes.is_empty() {
+            batch
+                .upserts
+                .retain(|item| !is_excluded(&item.full_path(), excludes));
+            batch.changed_entries = batch.upserts.len() + batch.deleted_paths.len();
+        }
         if !matches!(scope, SearchScope::CurrentFolder) {
             return batch;
         }
 
-        let roots = scope_roots(scope);
-        let Some(folder) = roots.first() else {
-            return batch;
-        };
+        let folder = current_folder_root();
 
         let upserts: Vec<SearchItem> = batch
             .upserts
             .into_iter()
-            .filter(|item| path_is_in_folder_scope(item.path.as_ref(), folder))
+            .filter(|item| path_is_in_folder_scope(&item.full_path(), &folder))
             .collect();
         let deleted_paths: Vec<String> = batch
             .deleted_paths
             .into_iter()
-            .filter(|path| path_is_in_folder_scope(path, folder))
+            .filter(|path| path_is_in_folder_scope(path, &folder))
             .collect();
         let changed_entries = upserts.len() + deleted_paths.len();
 
         JournalBatch {
             upserts,
             deleted_paths,
+            kinds: batch.kinds,
             changed_entries,
         }
     }
@@ -967,6 +1546,10 @@ mod imp {
         }
     }
 
+    fn is_hidden_or_system(node: &NtfsNode) -> bool {
+        node.file_attributes & (FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM) != 0
+    }
+
     fn search_item_kind(node: &NtfsNode) -> SearchItemKind {
         if node.is_dir {
             SearchItemKind::Folder
@@ -989,7 +1572,7 @@ mod imp {
     }
 
     fn persist_ntfs_snapshot(state: &mut NtfsVolumeState) {
-        let path = snapshot_file_path(state.drive_letter);
+        let path = snapshot_file_path(&state.volume.key);
         if let Some(parent) = path.parent() {
             if std::fs::create_dir_all(parent).is_err() {
                 return;
@@ -1005,22 +1588,23 @@ mod imp {
                 is_dir: node.is_dir,
                 modified_unix_secs: node.modified_unix_secs,
                 file_attributes: node.file_attributes,
+                size: node.size,
             });
         }
 
         let snapshot = NtfsSnapshot {
-            version: 1,
-            drive_letter: state.drive_letter,
+            version: 5,
+            volume: state.volume.key.clone(),
             journal_id: state.journal_id,
             next_usn: state.next_usn,
             nodes,
         };
 
-        let Ok(file) = std::fs::File::create(path) else {
+        let Some(bytes) = encode_snapshot(&snapshot) else {
             return;
         };
 
-        if bincode::serialize_into(file, &snapshot).is_ok() {
+        if write_atomically(&path, &bytes).is_ok() {
             state.last_snapshot_write = Instant::now();
             state.changed_since_snapshot = 0;
         }
@@ -1041,6 +1625,61 @@ mod imp {
         }
     }
 
+    /// Lists volumes that have no drive letter but are mounted into a folder, as
+    /// `\\?\Volume{GUID}\` names.
+    pub(crate) fn letterless_volume_roots() -> Vec<String> {
+        let mut roots = Vec::new();
+        let mut name = [0u16; 64];
+        let find = unsafe { FindFirstVolumeW(name.as_mut_ptr(), name.len() as u32) };
+        if find == INVALID_HANDLE_VALUE {
+            return roots;
+        }
+
+        loop {
+            if let Some(volume_name) = split_multi_sz(&name).into_iter().next() {
+                let paths = volume_mount_paths(&volume_name);
+                let has_letter = paths
+                    .iter()
+                    .any(|path| parse_drive_root_letter(path).is_some());
+                if !has_letter && !paths.is_empty() {
+                    roots.push(volume_name);
+                }
+            }
+
+            if unsafe { FindNextVolumeW(find, name.as_mut_ptr(), name.len() as u32) } == 0 {
+                break;
+            }
+        }
+
+        unsafe { FindVolumeClose(find) };
+        roots
+    }
+
+    /// The folders a `\\?\Volume{GUID}\` volume is mounted on, drive roots included.
+    pub(crate) fn volume_mount_paths(volume_name: &str) -> Vec<String> {
+        let volume_wide = to_wide(volume_name);
+        let mut buffer = vec![0u16; 512];
+        let mut needed = 0u32;
+        for _ in 0..2 {
+            let ok = unsafe {
+                GetVolumePathNamesForVolumeNameW(
+                    volume_wide.as_ptr(),
+                    buffer.as_mut_ptr(),
+                    buffer.len() as u32,
+                    &mut needed,
+                )
+            };
+            if ok != 0 {
+                return split_multi_sz(&buffer);
+            }
+            if needed as usize <= buffer.len() {
+                break;
+            }
+            buffer.resize(needed as usize, 0);
+        }
+        Vec::new()
+    }
+
     fn to_wide(value: &str) -> Vec<u16> {
         std::ffi::OsStr::new(value)
             .encode_wide()
@@ -1070,31 +1709,18 @@ mod imp {
             return found.clone();
         }
 
-        let mut parts = Vec::new();
-        let mut current = id;
-        let mut depth = 0usize;
-
-        while depth < 1024 {
-            let Some(node) = raw_nodes.get(&current) else {
-                break;
-            };
-
-            parts.push(node.name.clone());
-            if node.parent_id == current {
-                break;
-            }
-
-            current = node.parent_id;
-            depth += 1;
+        let (path, end) = resolve_path(id, drive_prefix, |current| {
+            raw_nodes
+                .get(&current)
+                .map(|node| (node.parent_id, node.name.as_str()))
+        });
+        if end == PathEnd::DepthCap {
+            debug_log(&format!(
+                "ntfs path of {:#x} passed {} parents, likely a cycle; indexed as {}",
+                id, MAX_PATH_DEPTH, path
+            ));
         }
 
-        parts.reverse();
-        let path = if parts.is_empty() {
-            drive_prefix.to_string()
-        } else {
-            format!("{}{}", drive_prefix, parts.join("\\"))
-        };
-
         path_cache.insert(id, path.clone());
         path
     }
@@ -1112,16 +1738,251 @@ mod imp {
     fn unknown_ts() -> i64 {
         UNKNOWN_TS
     }
+
+    fn unknown_size() -> u64 {
+        UNKNOWN_SIZE
+    }
 }
 
+#[cfg(any(target_os = "windows", test))]
+mod tree {
+    use std::collections::{HashMap, HashSet};
+
+    pub(super) fn build_children_map(
+        links: impl Iterator<Item = (u64, u64)>,
+    ) -> HashMap<u64, HashSet<u64>> {
+        let mut children = HashMap::new();
+        for (id, parent_id) in links {
+            link_child(&mut children, parent_id, id);
+        }
+        children
+    }
+
+    pub(super) fn link_child(children: &mut HashMap<u64, HashSet<u64>>, parent_id: u64, id: u64) {
+        if parent_id != id {
+            children.entry(parent_id).or_default().insert(id);
+        }
+    }
+
+    pub(super) fn unlink_child(children: &mut HashMap<u64, HashSet<u64>>, parent_id: u64, id: u64) {
+        if let Some(siblings) = children.get_mut(&parent_id) {
+            siblings.remove(&id);
+            if siblings.is_empty() {
+                children.remove(&parent_id);
+            }
+        }
+    }
+
+    pub(super) fn remove_subtree<N>(
+        nodes: &mut HashMap<u64, N>,
+        children: &mut HashMap<u64, HashSet<u64>>,
+        id: u64,
+        parent_of: impl Fn(&N) -> u64,
+    ) -> Vec<u64> {
+        let Some(parent_id) = nodes.get(&id).map(&parent_of) else {
+            return Vec::new();
+        };
+        unlink_child(children, parent_id, id);
+
+        let mut to_remove = vec![id];
+        let mut index = 0usize;
+        while index < to_remove.len() {
+            if let Some(descendants) = children.remove(&to_remove[index]) {
+                to_remove.extend(descendants);
+            }
+            index += 1;
+        }
+
+        to_remove
+            .into_iter()
+            .filter(|target| nodes.remove(target).is_some())
+            .collect()
+    }
+
+    pub(super) fn collect_descendants(children: &HashMap<u64, HashSet<u64>>, id: u64) -> Vec<u64> {
+        let mut out = Vec::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            if let Some(direct) = children.get(&current) {
+                for child in direct {
+                    if *child != id {
+                        out.push(*child);
+                        stack.push(*child);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Most parent links followed before a path is taken to be a cycle.
+    pub(super) const MAX_PATH_DEPTH: usize = 1024;
+
+    /// Stands in for the part of a path that could not be resolved. `?` cannot
+    /// appear in a Windows file name, so these never collide with real paths.
+    pub(super) const UNRESOLVED_MARKER: &str = "?\\";
+
+    /// How a walk up the parent links ended.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub(super) enum PathEnd {
+        Root,
+        MissingParent,
+        DepthCap,
+    }
+
+    /// The root directory of an NTFS volume is always MFT record 5; the top
+    /// 16 bits of a file reference are its sequence number.
+    fn is_root_record(id: u64) -> bool {
+        id & 0x0000_FFFF_FFFF_FFFF == 5
+    }
+
+    /// Builds the path of `id` under `drive_prefix`, with `node_of` giving a
+    /// node's parent and name. When a parent is missing the names found so far
+    /// go under `?\`; when the walk hits `MAX_PATH_DEPTH` only the name is kept.
+    pub(super) fn resolve_path<'a>(
+        id: u64,
+        drive_prefix: &str,
+        node_of: impl Fn(u64) -> Option<(u64, &'a str)>,
+    ) -> (String, PathEnd) {
+        let mut parts = Vec::new();
+        let mut current = id;
+        let end = loop {
+            if parts.len() == MAX_PATH_DEPTH {
+                break PathEnd::DepthCap;
+            }
+            let Some((parent_id, name)) = node_of(current) else {
+                break PathEnd::MissingParent;
+            };
+            parts.push(name);
+            if parent_id == current || is_root_record(parent_id) {
+                break PathEnd::Root;
+            }
+            current = parent_id;
+        };
+
+        let path = match end {
+            PathEnd::Root => {
+                parts.reverse();
+                format!("{}{}", drive_prefix, parts.join("\\"))
+            }
+            PathEnd::MissingParent => {
+                parts.reverse();
+                format!("{}{}{}", drive_prefix, UNRESOLVED_MARKER, parts.join("\\"))
+            }
+            PathEnd::DepthCap => format!("{}{}{}", drive_prefix, UNRESOLVED_MARKER, parts[0]),
+        };
+        (path, end)
+    }
+
+    /// Recomputes paths for `ids`, returning the current `(id, path)` pairs and
+    /// any previously emitted paths that no longer apply.
+    pub(super) fn refresh_paths(
+        ids: impl IntoIterator<Item = u64>,
+        id_to_path: &mut HashMap<u64, String>,
+        mut path_of: impl FnMut(u64) -> Option<String>,
+    ) -> (Vec<(u64, String)>, Vec<String>) {
+        let mut refreshed = Vec::new();
+        let mut stale = Vec::new();
+        for id in ids {
+            let Some(path) = path_of(id) else {
+                continue;
+            };
+            if let Some(old_path) = id_to_path.insert(id, path.clone()) {
+                if old_path != path {
+                    stale.push(old_path);
+                }
+            }
+            refreshed.push((id, path));
+        }
+        (refreshed, stale)
+    }
+}
+
+#[cfg(any(target_os = "windows", test))]
+mod journal_recovery {
+    use std::time::Duration;
+
+    /// Failed journal recoveries in a row before a volume's live updates are
+    /// given up and the scope is reindexed.
+    pub(super) const MAX_FAILED_RECOVERIES: u32 = 5;
+
+    /// Wait before the next recovery: one second, doubling with each failure
+    /// up to half a minute.
+    pub(super) fn recovery_backoff(failures: u32) -> Duration {
+        let secs = 1u64 << failures.saturating_sub(1).min(5);
+        Duration::from_secs(secs.min(30))
+    }
+}
+
+mod volume_paths {
+    /// Returns `Volume{GUID}` for a `\\?\Volume{GUID}\` root.
+    pub(crate) fn volume_guid_key(root: &str) -> Option<&str> {
+        let key = root.strip_prefix(r"\\?\")?.trim_end_matches('\\');
+        let is_guid_name = key.len() > "Volume{}".len()
+            && key.starts_with("Volume{")
+            && key.ends_with('}')
+            && !key.contains('\\');
+        is_guid_name.then_some(key)
+    }
+
+    /// Turns a volume open or journal query failure into what to do about it.
+    #[cfg(any(target_os = "windows", test))]
+    pub(super) fn volume_error_message(root: &str, code: u32) -> String {
+        use windows_sys::Win32::Foundation::{
+            ERROR_ACCESS_DENIED, ERROR_FILE_NOT_FOUND, ERROR_INVALID_FUNCTION,
+            ERROR_JOURNAL_DELETE_IN_PROGRESS, ERROR_JOURNAL_NOT_ACTIVE, ERROR_NOT_READY,
+            ERROR_PATH_NOT_FOUND,
+        };
+
+        let drive = root.trim_end_matches('\\');
+        match code {
+            ERROR_ACCESS_DENIED => format!("{} access denied - run elevated", drive),
+            ERROR_JOURNAL_NOT_ACTIVE => format!("USN journal disabled on {}", drive),
+            ERROR_JOURNAL_DELETE_IN_PROGRESS => {
+                format!("USN journal on {} is being deleted", drive)
+            }
+            ERROR_INVALID_FUNCTION => format!("{} has no USN journal (not NTFS?)", drive),
+            ERROR_NOT_READY => format!("{} is not ready", drive),
+            ERROR_FILE_NOT_FOUND | ERROR_PATH_NOT_FOUND => format!("{} not found", drive),
+            _ => format!("{} could not be read (error {})", drive, code),
+        }
+    }
+
+    /// Splits a double-NUL-terminated UTF-16 string list.
+    #[cfg(any(target_os = "windows", test))]
+    pub(super) fn split_multi_sz(buffer: &[u16]) -> Vec<String> {
+        buffer
+            .split(|unit| *unit == 0)
+            .take_while(|part| !part.is_empty())
+            .map(String::from_utf16_lossy)
+            .collect()
+    }
+}
+
+pub(crate) use volume_paths::volume_guid_key;
+
 #[cfg(target_os = "windows")]
-pub(crate) use imp::{run_ntfs_live_index_job, try_index_ntfs_volume};
+pub(crate) use imp::{
+    letterless_volume_roots, run_ntfs_live_index_job, try_index_ntfs_volume, volume_mount_paths,
+};
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn letterless_volume_roots() -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn volume_mount_paths(_volume_name: &str) -> Vec<String> {
+    Vec::new()
+}
 
 #[cfg(not(target_os = "windows"))]
 pub(crate) fn run_ntfs_live_index_job(
     _scope: crate::SearchScope,
     _job_id: u64,
     _tx: &std::sync::mpsc::Sender<crate::IndexEvent>,
+    _cancel: &std::sync::atomic::AtomicBool,
+    _options: &crate::indexing::IndexOptions,
 ) -> bool {
     false
 }
@@ -1131,6 +1992,188 @@ pub(crate) fn try_index_ntfs_volume(
     _root: &str,
     _job_id: u64,
     _tx: &std::sync::mpsc::Sender<crate::IndexEvent>,
+    _cancel: &std::sync::atomic::AtomicBool,
+    _options: &crate::indexing::IndexOptions,
 ) -> Option<Vec<crate::SearchItem>> {
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::journal_recovery::{recovery_backoff, MAX_FAILED_RECOVERIES};
+    use super::tree::{
+        build_children_map, collect_descendants, link_child, refresh_paths, remove_subtree,
+        resolve_path, unlink_child, PathEnd,
+    };
+    use super::volume_paths::{split_multi_sz, volume_error_message, volume_guid_key};
+
+    fn test_path(nodes: &HashMap<u64, (u64, &str)>, id: u64) -> Option<String> {
+        let mut parts = Vec::new();
+        let mut current = id;
+        while let Some((parent, name)) = nodes.get(&current) {
+            parts.push(*name);
+            if *parent == current {
+                break;
+            }
+            current = *parent;
+        }
+        if parts.is_empty() {
+            return None;
+        }
+        parts.reverse();
+        Some(parts.join("/"))
+    }
+
+    #[test]
+    fn resolves_paths_through_broken_parent_links() {
+        let root = 0x0005_0000_0000_0005;
+        let nodes: HashMap<u64, (u64, &str)> = HashMap::from([
+            (100, (root, "docs")),
+            (101, (100, "a.txt")),
+            // 200's parent was never enumerated.
+            (200, (999, "lost")),
+            (201, (200, "b.txt")),
+            // 300 and 301 are each other's parent.
+            (300, (301, "x")),
+            (301, (300, "y")),
+            (302, (300, "c.txt")),
+            (400, (400, "self")),
+        ]);
+        let resolve = |id| resolve_path(id, "C:\\", |current| nodes.get(&current).copied());
+
+        assert_eq!(resolve(101), ("C:\\docs\\a.txt".to_string(), PathEnd::Root));
+        assert_eq!(
+            resolve(201),
+            ("C:\\?\\lost\\b.txt".to_string(), PathEnd::MissingParent)
+        );
+        assert_eq!(
+            resolve(302),
+            ("C:\\?\\c.txt".to_string(), PathEnd::DepthCap)
+        );
+        assert_eq!(resolve(400), ("C:\\self".to_string(), PathEnd::Root));
+    }
+
+    #[test]
+    fn recognizes_volume_guid_roots() {
+        let root = r"\\?\Volume{0b5c2f4e-1a2b-4c3d-8e9f-001122334455}\";
+        assert_eq!(
+            volume_guid_key(root),
+            Some("Volume{0b5c2f4e-1a2b-4c3d-8e9f-001122334455}")
+        );
+        assert_eq!(volume_guid_key(r"C:\"), None);
+        assert_eq!(volume_guid_key(r"\\?\C:\"), None);
+        assert_eq!(volume_guid_key(r"\\?\Volume{}\"), None);
+    }
+
+    #[test]
+    fn splits_mount_path_lists() {
+        let buffer: Vec<u16> = "C:\\Mounts\\Data\\\0E:\\\0\0\0junk"
+            .encode_utf16()
+            .collect();
+        assert_eq!(
+            split_multi_sz(&buffer),
+            vec![r"C:\Mounts\Data\".to_string(), r"E:\".to_string()]
+        );
+        assert!(split_multi_sz(&[0, 0]).is_empty());
+    }
+
+    #[test]
+    fn explains_volume_errors() {
+        assert_eq!(
+            volume_error_message(r"C:\", 5),
+            "C: access denied - run elevated"
+        );
+        assert_eq!(
+            volume_error_message(r"D:\", 1179),
+            "USN journal disabled on D:"
+        );
+        assert_eq!(
+            volume_error_message(r"\\?\Volume{1234}\", 87),
+            r"\\?\Volume{1234} could not be read (error 87)"
+        );
+    }
+
+    #[test]
+    fn removing_moved_folder_drops_whole_subtree() {
+        // 1 = root, 2 = docs, 3 = archive, 4/5 = files in docs, 6 = nested folder, 7 = nested file.
+        let mut nodes: HashMap<u64, u64> =
+            HashMap::from([(1, 1), (2, 1), (3, 1), (4, 2), (5, 2), (6, 2), (7, 6)]);
+        let mut children = build_children_map(nodes.iter().map(|(id, parent)| (*id, *parent)));
+
+        unlink_child(&mut children, 1, 2);
+        nodes.insert(2, 3);
+        link_child(&mut children, 3, 2);
+
+        let mut removed = remove_subtree(&mut nodes, &mut children, 2, |parent| *parent);
+        removed.sort_unstable();
+
+        assert_eq!(removed, [2, 4, 5, 6, 7]);
+        assert_eq!(nodes, HashMap::from([(1, 1), (3, 1)]));
+        assert!(!children.contains_key(&3));
+        assert!(!children.contains_key(&2));
+        assert!(!children[&1].contains(&2));
+        assert!(remove_subtree(&mut nodes, &mut children, 2, |parent| *parent).is_empty());
+    }
+
+    #[test]
+    fn renaming_folder_reemits_nested_paths() {
+        // 1 = root, 2 = docs, 3/4 = files in docs, 5 = nested folder, 6 = nested file.
+        let mut nodes: HashMap<u64, (u64, &str)> = HashMap::from([
+            (1, (1, "C:")),
+            (2, (1, "docs")),
+            (3, (2, "a.txt")),
+            (4, (2, "b.txt")),
+            (5, (2, "sub")),
+            (6, (5, "c.txt")),
+        ]);
+        let children = build_children_map(nodes.iter().map(|(id, (parent, _))| (*id, *parent)));
+        let mut id_to_path = HashMap::new();
+        let all_ids: Vec<u64> = nodes.keys().copied().collect();
+        refresh_paths(all_ids, &mut id_to_path, |id| test_path(&nodes, id));
+
+        nodes.insert(2, (1, "papers"));
+        let mut affected = collect_descendants(&children, 2);
+        affected.sort_unstable();
+        assert_eq!(affected, [3, 4, 5, 6]);
+        affected.push(2);
+
+        let (mut refreshed, mut stale) =
+            refresh_paths(affected, &mut id_to_path, |id| test_path(&nodes, id));
+        refreshed.sort_unstable();
+        stale.sort_unstable();
+
+        assert_eq!(
+            stale,
+            [
+                "C:/docs",
+                "C:/docs/a.txt",
+                "C:/docs/b.txt",
+                "C:/docs/sub",
+                "C:/docs/sub/c.txt",
+            ]
+        );
+        assert_eq!(
+            refreshed,
+            [
+                (2, "C:/papers".to_string()),
+                (3, "C:/papers/a.txt".to_string()),
+                (4, "C:/papers/b.txt".to_string()),
+                (5, "C:/papers/sub".to_string()),
+                (6, "C:/papers/sub/c.txt".to_string()),
+            ]
+        );
+        assert_eq!(id_to_path[&6], "C:/papers/sub/c.txt");
+    }
+
+    #[test]
+    fn journal_recovery_backs_off_then_gives_up() {
+        let delays: Vec<u64> = (1..MAX_FAILED_RECOVERIES)
+            .map(|failures| recovery_backoff(failures).as_secs())
+            .collect();
+        assert_eq!(delays, [1, 2, 4, 8]);
+        assert_eq!(recovery_backoff(0).as_secs(), 1);
+        assert_eq!(recovery_backoff(40).as_secs(), 30);
+    }
+}
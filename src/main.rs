@@ -1,37 +1,49 @@
 #![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
 
 mod app_state;
+mod cli;
 mod commands;
+mod exclude;
+mod export;
+mod feed;
+mod gitignore;
 mod gpu_ui;
 mod indexing;
 mod indexing_ntfs;
+mod path_table;
+mod pipe_server;
 mod platform;
+mod preview;
 mod search;
 mod search_worker;
+mod settings;
+mod sizes;
 mod storage;
+mod theme;
 mod tui_view;
+mod view_model;
 
+use std::collections::HashMap;
 use std::env;
 use std::io::Write;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 
 use app_state::AppState;
 use eframe::egui;
 use egui_ratatui::RataguiBackend;
+use feed::ChangeKind;
 use ratatui::style::Color;
 use ratatui::Terminal;
 use soft_ratatui::embedded_graphics_unicodefonts::{
     mono_8x13_atlas, mono_8x13_bold_atlas, mono_8x13_italic_atlas,
 };
 use soft_ratatui::{EmbeddedGraphics, SoftBackend};
+use theme::Palette;
 
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
 
-const VISIBLE_RESULTS_LIMIT: usize = 600;
-const QUERY_DEBOUNCE_DELAY: Duration = Duration::from_millis(70);
-const SEARCH_BATCH_SIZE: usize = 12_000;
 const FILENAME_INDEX_BUILD_BATCH: usize = 1_000;
 const DEFAULT_LATEST_WINDOW_SECS: i64 = 5 * 60;
 const DELTA_REFRESH_COOLDOWN: Duration = Duration::from_millis(300);
@@ -47,7 +59,9 @@ const POLL_INTERVAL_ACTIVE: Duration = Duration::from_millis(16);
 const POLL_INTERVAL_IDLE: Duration = Duration::from_millis(55);
 const POLL_INTERVAL_HIDDEN: Duration = Duration::from_millis(80);
 const UNKNOWN_TS: i64 = i64::MIN;
+const UNKNOWN_SIZE: u64 = u64::MAX;
 const KEYBOARD_PAGE_JUMP: usize = 12;
+const RECYCLE_UNDO_DEPTH: usize = 10;
 const WINDOW_WIDTH: f32 = 980.0;
 const WINDOW_HEIGHT: f32 = 560.0;
 const PANEL_ANIMATION_DURATION: Duration = Duration::from_millis(180);
@@ -55,22 +69,47 @@ const PANEL_SHOWN_Y: f32 = 0.0;
 const PANEL_HIDDEN_Y_EXTRA: f32 = 24.0;
 
 static DEBUG_LOG_FILES: OnceLock<std::sync::Mutex<Vec<std::fs::File>>> = OnceLock::new();
+static DEBUG_EVENT_FILES: OnceLock<std::sync::Mutex<Vec<std::fs::File>>> = OnceLock::new();
 static DEBUG_ENABLED: OnceLock<bool> = OnceLock::new();
 
 fn main() -> eframe::Result {
-    let _ = DEBUG_ENABLED.set(env::var("WIZMINI_DEBUG").ok().as_deref() == Some("1"));
+    let debug_level = env::var("WIZMINI_DEBUG").ok();
+    let _ = DEBUG_ENABLED.set(matches!(debug_level.as_deref(), Some("1" | "2")));
     let _ = init_debug_log_file();
+    if debug_level.as_deref() == Some("2") {
+        let _ = init_debug_event_file();
+    }
     std::panic::set_hook(Box::new(|info| {
         debug_log(&format!("panic: {}", info));
     }));
 
-    let window_width = default_window_width();
-    let window_height = window_height_for_rows(DEFAULT_RESULT_ROWS);
+    if cli::serve_requested(env::args().skip(1)) {
+        let scope = startup_scope_override_from_args().unwrap_or(SearchScope::CurrentFolder);
+        std::process::exit(pipe_server::run_pipe_server(scope));
+    }
+    if let Some(request) = cli::benchmark_request_from_args(env::args().skip(1)) {
+        std::process::exit(cli::run_benchmark(request));
+    }
+    if let Some(request) = cli::cli_request_from_args(env::args().skip(1)) {
+        std::process::exit(cli::run_cli_request(request));
+    }
+
+    let window_prefs = storage::load_window_prefs();
+    let window_width = window_prefs
+        .width
+        .map(clamp_window_width)
+        .unwrap_or_else(default_window_width);
+    let window_height = if window_prefs.fullheight {
+        screen_height()
+    } else {
+        window_height_for_rows(DEFAULT_RESULT_ROWS)
+    };
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_title("RustSearch")
             .with_inner_size([window_width, window_height])
+            .with_fullscreen(window_prefs.fullscreen)
             .with_decorations(false),
         ..Default::default()
     };
@@ -87,6 +126,7 @@ fn main() -> eframe::Result {
                 startup_scope.clone(),
                 window_width,
                 window_height,
+                window_prefs,
             )))
         }),
     )
@@ -99,6 +139,11 @@ struct RustSearchEguiApp {
     panel_anim_last_tick: Option<Instant>,
     window_width: f32,
     window_height: f32,
+    /// A width the user resized the window to; without one the width follows
+    /// the monitor the app starts on.
+    saved_width: Option<f32>,
+    /// The user resized the window and the new width is not saved yet.
+    resize_pending: bool,
     fullscreen_enabled: bool,
     fullheight_enabled: bool,
     fullheight_before_fullscreen: bool,
@@ -112,19 +157,23 @@ impl RustSearchEguiApp {
         startup_scope: Option<SearchScope>,
         window_width: f32,
         window_height: f32,
+        window_prefs: storage::WindowPrefs,
     ) -> Self {
-        let renderer = Renderer::from_env();
+        let runtime = AppState::new(start_visible, startup_scope);
+        let renderer = Renderer::initial(runtime.settings.renderer);
 
         Self {
-            runtime: AppState::new(start_visible, startup_scope),
+            runtime,
             renderer,
             panel_progress: if start_visible { 1.0 } else { 0.0 },
             panel_anim_last_tick: None,
             window_width,
             window_height,
-            fullscreen_enabled: false,
-            fullheight_enabled: false,
-            fullheight_before_fullscreen: false,
+            saved_width: window_prefs.width,
+            resize_pending: false,
+            fullscreen_enabled: window_prefs.fullscreen,
+            fullheight_enabled: window_prefs.fullheight && !window_prefs.fullscreen,
+            fullheight_before_fullscreen: window_prefs.fullheight,
             last_frame_instant: Instant::now(),
             frame_time_ema_ms: 0.0,
         }
@@ -182,6 +231,43 @@ impl RustSearchEguiApp {
                 )));
             }
         }
+
+        self.persist_window_prefs();
+    }
+
+    fn persist_window_prefs(&self) {
+        storage::persist_window_prefs(storage::WindowPrefs {
+            width: self.saved_width,
+            fullheight: if self.fullscreen_enabled {
+                self.fullheight_before_fullscreen
+            } else {
+                self.fullheight_enabled
+            },
+            fullscreen: self.fullscreen_enabled,
+        });
+    }
+
+    /// Adopts a width the user dragged the window to, and saves it once it
+    /// holds for a frame rather than on every step of the drag.
+    fn track_user_resize(&mut self, ctx: &egui::Context) {
+        let (width, resizable) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (
+                viewport.inner_rect.map(|rect| rect.width()),
+                viewport.fullscreen != Some(true) && viewport.maximized != Some(true),
+            )
+        });
+        let Some(width) = width.filter(|_| resizable && !self.fullscreen_enabled) else {
+            return;
+        };
+        if (width - self.window_width).abs() >= 1.0 {
+            self.window_width = width;
+            self.saved_width = Some(width);
+            self.resize_pending = true;
+        } else if self.resize_pending {
+            self.resize_pending = false;
+            self.persist_window_prefs();
+        }
     }
 
     fn sync_window_slide(&mut self, ctx: &egui::Context) {
@@ -280,19 +366,71 @@ impl RustSearchEguiApp {
             return;
         }
 
+        if self.runtime.renaming.is_some() {
+            ctx.input(|i| {
+                if i.key_pressed(egui::Key::Escape) {
+                    self.runtime.cancel_rename();
+                } else if i.key_pressed(egui::Key::Enter) {
+                    self.runtime.commit_rename();
+                }
+            });
+            return;
+        }
+
         let mut enter_pressed = false;
         let mut alt_enter = false;
+        let mut ctrl_enter = false;
+        let mut shift_enter = false;
+        let mut copy_requested = false;
 
         ctx.input(|i| {
             if i.key_pressed(egui::Key::Escape) {
                 self.runtime.on_escape();
             }
+            if i.key_pressed(egui::Key::F2) {
+                self.runtime.begin_rename();
+            }
+            if i.key_pressed(egui::Key::F3) {
+                self.runtime.toggle_preview();
+            }
             if i.key_pressed(egui::Key::ArrowDown) {
-                self.runtime.on_move_down();
+                if i.modifiers.shift {
+                    self.runtime.on_extend_selection(true);
+                } else {
+                    self.runtime.on_move_down();
+                }
             }
             if i.key_pressed(egui::Key::ArrowUp) {
-                self.runtime.on_move_up();
+                if i.modifiers.shift {
+                    self.runtime.on_extend_selection(false);
+                } else {
+                    self.runtime.on_move_up();
+                }
+            }
+            if i.key_pressed(egui::Key::P) && i.modifiers.command {
+                self.runtime.toggle_pin_selected();
+            }
+            if i.key_pressed(egui::Key::T) && i.modifiers.command {
+                self.runtime.open_terminal_selected();
             }
+            if i.key_pressed(egui::Key::Delete) && i.modifiers.command {
+                self.runtime.recycle_targets();
+            }
+            if i.key_pressed(egui::Key::Z) && i.modifiers.command {
+                self.runtime.undo_recycle();
+            }
+            if i.key_pressed(egui::Key::Tab) && i.modifiers.command {
+                self.runtime.cycle_scope();
+            } else if i.key_pressed(egui::Key::Tab) {
+                self.runtime.toggle_action_palette();
+            }
+            if i.key_pressed(egui::Key::D) && i.modifiers.command {
+                self.runtime.cycle_date_filter();
+            }
+            copy_requested = i
+                .events
+                .iter()
+                .any(|event| matches!(event, egui::Event::Copy));
             if i.key_pressed(egui::Key::PageDown) {
                 self.runtime.on_page_down();
             }
@@ -309,12 +447,24 @@ impl RustSearchEguiApp {
             if i.key_pressed(egui::Key::Enter) {
                 enter_pressed = true;
                 alt_enter = i.modifiers.alt;
+                ctrl_enter = i.modifiers.command;
+                shift_enter = i.modifiers.shift;
             }
         });
 
+        if copy_requested {
+            if let Some(text) = self.runtime.copy_targets_text() {
+                ctx.copy_text(text);
+            }
+        }
+
         if enter_pressed {
             if alt_enter {
                 self.runtime.on_alt_enter();
+            } else if ctrl_enter {
+                self.runtime.on_ctrl_enter();
+            } else if shift_enter {
+                self.runtime.on_shift_enter();
             } else {
                 self.runtime.activate_selected();
             }
@@ -332,23 +482,28 @@ impl RustSearchEguiApp {
             return;
         }
 
-        let mut raw = self.runtime.raw_query.clone();
+        let renaming = self.runtime.renaming.as_ref().map(|(_, name)| name.clone());
+        let mut raw = renaming
+            .clone()
+            .unwrap_or_else(|| self.runtime.raw_query.clone());
         let mut changed = false;
+        let mut toggle_mark = false;
 
         ctx.input(|i| {
             for event in &i.events {
                 match event {
-                    egui::Event::Text(text) => {
-                        if !text.is_empty() {
-                            raw.push_str(text);
-                            changed = true;
-                        }
+                    // A space that could not change the query marks the row instead.
+                    egui::Event::Text(text)
+                        if text == " "
+                            && renaming.is_none()
+                            && (raw.is_empty() || raw.ends_with(' '))
+                            && !raw.starts_with('/') =>
+                    {
+                        toggle_mark = true;
                     }
-                    egui::Event::Paste(text) => {
-                        if !text.is_empty() {
-                            raw.push_str(text);
-                            changed = true;
-                        }
+                    egui::Event::Text(text) | egui::Event::Paste(text) if !text.is_empty() => {
+                        raw.push_str(text);
+                        changed = true;
                     }
                     egui::Event::Key {
                         key,
@@ -365,16 +520,12 @@ impl RustSearchEguiApp {
                         }
 
                         match key {
-                            egui::Key::Backspace => {
-                                if raw.pop().is_some() {
-                                    changed = true;
-                                }
+                            egui::Key::Backspace if raw.pop().is_some() => {
+                                changed = true;
                             }
-                            egui::Key::Delete => {
-                                if !raw.is_empty() {
-                                    raw.clear();
-                                    changed = true;
-                                }
+                            egui::Key::Delete if !raw.is_empty() => {
+                                raw.clear();
+                                changed = true;
                             }
                             _ => {}
                         }
@@ -384,8 +535,17 @@ impl RustSearchEguiApp {
             }
         });
 
-        if changed && raw != self.runtime.raw_query {
-            self.runtime.on_query_changed(raw);
+        if toggle_mark {
+            self.runtime.toggle_mark_selected();
+        }
+        if !changed {
+            return;
+        }
+        match renaming {
+            Some(name) if raw != name => self.runtime.on_rename_changed(raw),
+            Some(_) => {}
+            None if raw != self.runtime.raw_query => self.runtime.on_query_changed(raw),
+            None => {}
         }
     }
 }
@@ -416,6 +576,9 @@ impl eframe::App for RustSearchEguiApp {
         };
         ctx.request_repaint_after(repaint_after);
 
+        if ctx.input(|i| !i.events.is_empty()) {
+            self.runtime.note_interaction();
+        }
         let tick = self.runtime.process_tick();
         let _ = tick.focus_search;
 
@@ -427,7 +590,14 @@ impl eframe::App for RustSearchEguiApp {
         }
         if let Some(request) = tick.renderer_mode_request {
             self.renderer = Renderer::from_mode(request);
+            // Rebuilding the soft terminal can leave the window without
+            // keyboard focus, and typing goes straight to the query.
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        }
+        if let Some(text) = tick.clipboard_text {
+            ctx.copy_text(text);
         }
+        self.track_user_resize(ctx);
         self.sync_window_slide(ctx);
         if tick.should_quit {
             ctx.send_viewport_cmd(egui::ViewportCommand::Close);
@@ -441,7 +611,14 @@ impl eframe::App for RustSearchEguiApp {
         }
         self.apply_query_text_input(ctx);
 
-        egui::CentralPanel::default()
+        // Scales both renderers: the soft terminal sizes its grid to the
+        // points it is given, so zooming in also enlarges its glyphs.
+        let font_scale = self.runtime.settings.font_scale();
+        if (ctx.zoom_factor() - font_scale).abs() > f32::EPSILON {
+            ctx.set_zoom_factor(font_scale);
+        }
+
+        let click = egui::CentralPanel::default()
             .frame(
                 egui::Frame::default()
                     .inner_margin(egui::Margin::same(0))
@@ -452,8 +629,22 @@ impl eframe::App for RustSearchEguiApp {
                     frame_time_ms: self.frame_time_ema_ms,
                     repaint_after,
                 };
-                self.renderer.draw(ctx, ui, &self.runtime, hud);
+                self.renderer.draw(ctx, ui, &self.runtime, hud)
             });
+        if let Some(click) = click.inner {
+            match click {
+                gpu_ui::UiClick::Result(click) => match click.action {
+                    Some(action) => {
+                        if let Some(text) = self.runtime.on_row_action(click.row, action) {
+                            ctx.copy_text(text);
+                        }
+                    }
+                    None => self.runtime.on_result_click(click.row, click.double_click),
+                },
+                gpu_ui::UiClick::DateChip(filter) => self.runtime.toggle_date_filter(filter),
+            }
+            ctx.request_repaint();
+        }
     }
 }
 
@@ -464,21 +655,22 @@ struct RenderHud {
 }
 
 enum Renderer {
-    SoftTui(Box<Terminal<RataguiBackend<EmbeddedGraphics>>>),
+    /// The terminal and the first result row it last showed.
+    SoftTui(Box<Terminal<RataguiBackend<EmbeddedGraphics>>>, usize),
     GpuEgui,
 }
 
 impl Renderer {
-    fn from_env() -> Self {
-        let mode = env::var("RUSTSEARCH_RENDERER")
-            .unwrap_or_else(|_| "gpu".to_string())
-            .to_ascii_lowercase();
-
-        if mode == "soft" || mode == "ratatui" {
-            Self::from_mode(RendererModeRequest::Soft)
-        } else {
-            Self::from_mode(RendererModeRequest::Gpu)
-        }
+    /// `RUSTSEARCH_RENDERER` wins over the saved choice when it is set.
+    fn initial(saved: RendererModeRequest) -> Self {
+        let mode = match env::var("RUSTSEARCH_RENDERER") {
+            Ok(value) => match value.to_ascii_lowercase().as_str() {
+                "soft" | "ratatui" => RendererModeRequest::Soft,
+                _ => RendererModeRequest::Gpu,
+            },
+            Err(_) => saved,
+        };
+        Self::from_mode(mode)
     }
 
     fn from_mode(mode: RendererModeRequest) -> Self {
@@ -497,20 +689,27 @@ impl Renderer {
                 );
                 let backend = RataguiBackend::new("rustsearch", soft_backend);
                 let terminal = Terminal::new(backend).expect("terminal init failed");
-                Self::SoftTui(Box::new(terminal))
+                Self::SoftTui(Box::new(terminal), 0)
             }
         }
     }
 
-    fn draw(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, app: &AppState, hud: RenderHud) {
+    fn draw(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        app: &AppState,
+        hud: RenderHud,
+    ) -> Option<gpu_ui::UiClick> {
         match self {
-            Self::SoftTui(terminal) => {
+            Self::SoftTui(terminal, results_top) => {
                 if let Err(err) = terminal.draw(|frame| {
-                    tui_view::draw(frame, app);
+                    tui_view::draw(frame, app, results_top);
                 }) {
                     debug_log(&format!("Soft renderer draw failed: {}", err));
                 }
                 ui.add(terminal.backend_mut());
+                None
             }
             Self::GpuEgui => gpu_ui::draw(ctx, ui, app, hud.frame_time_ms, hud.repaint_after),
         }
@@ -550,11 +749,26 @@ fn window_height_for_rows(rows: usize) -> f32 {
     (rows as f32 * RESULT_ROW_HEIGHT + WINDOW_NON_RESULT_HEIGHT).max(WINDOW_HEIGHT)
 }
 
+/// Keeps a persisted width from overflowing a smaller monitor than the one it
+/// was saved on.
+fn clamp_window_width(window_width: f32) -> f32 {
+    #[cfg(target_os = "windows")]
+    {
+        let screen_w = unsafe { GetSystemMetrics(SM_CXSCREEN) };
+        if screen_w > 0 {
+            return window_width.min(screen_w as f32);
+        }
+    }
+
+    window_width
+}
+
 fn centered_window_x(window_width: f32) -> f32 {
     #[cfg(target_os = "windows")]
     {
         let screen_w = unsafe { GetSystemMetrics(SM_CXSCREEN) };
         if screen_w > 0 {
+            let window_width = clamp_window_width(window_width);
             return ((screen_w as f32) - window_width).max(0.0) / 2.0;
         }
     }
@@ -563,51 +777,54 @@ fn centered_window_x(window_width: f32) -> f32 {
 }
 
 fn startup_scope_override_from_args() -> Option<SearchScope> {
-    for arg in env::args() {
-        let Some(value) = arg.strip_prefix("--scope=") else {
-            continue;
-        };
+    env::args()
+        .filter_map(|arg| arg.strip_prefix("--scope=").map(str::to_string))
+        .find_map(|value| parse_scope_arg(&value))
+}
 
-        let lower = value.trim().to_ascii_lowercase();
-        if lower == "current-folder" {
-            return Some(SearchScope::CurrentFolder);
-        }
-        if lower == "entire-current-drive" {
-            return Some(SearchScope::EntireCurrentDrive);
-        }
-        if lower == "all-local-drives" {
-            return Some(SearchScope::AllLocalDrives);
-        }
+pub(crate) fn parse_scope_arg(value: &str) -> Option<SearchScope> {
+    let lower = value.trim().to_ascii_lowercase();
+    if lower == "current-folder" {
+        return Some(SearchScope::CurrentFolder);
+    }
+    if lower == "entire-current-drive" {
+        return Some(SearchScope::EntireCurrentDrive);
+    }
+    if lower == "all-local-drives" {
+        return Some(SearchScope::AllLocalDrives);
+    }
 
-        let bytes = lower.as_bytes();
-        if bytes.len() == 2 && bytes[1] == b':' && bytes[0].is_ascii_alphabetic() {
-            return Some(SearchScope::Drive((bytes[0] as char).to_ascii_uppercase()));
-        }
+    let bytes = lower.as_bytes();
+    if bytes.len() == 2 && bytes[1] == b':' && bytes[0].is_ascii_alphabetic() {
+        return Some(SearchScope::Drive((bytes[0] as char).to_ascii_uppercase()));
     }
 
     None
 }
 
-fn debug_log_path_localappdata() -> std::path::PathBuf {
+fn debug_log_path_localappdata(file_name: &str) -> std::path::PathBuf {
     let base = env::var("LOCALAPPDATA").unwrap_or_else(|_| ".".to_string());
     std::path::PathBuf::from(base)
         .join("WizMini")
-        .join("rustsearch-debug.log")
+        .join(file_name)
 }
 
-fn debug_log_path_exe_dir() -> std::path::PathBuf {
+fn debug_log_path_exe_dir(file_name: &str) -> std::path::PathBuf {
     let exe_dir = env::current_exe()
         .ok()
         .and_then(|p| p.parent().map(|v| v.to_path_buf()))
         .unwrap_or_else(|| std::path::PathBuf::from("."));
-    exe_dir.join("rustsearch-debug.log")
+    exe_dir.join(file_name)
 }
 
-fn init_debug_log_file() -> Result<(), String> {
+fn open_debug_files(file_name: &str) -> (Vec<std::fs::File>, Vec<String>) {
     let mut files = Vec::new();
     let mut opened_paths = Vec::new();
 
-    for path in [debug_log_path_localappdata(), debug_log_path_exe_dir()] {
+    for path in [
+        debug_log_path_localappdata(file_name),
+        debug_log_path_exe_dir(file_name),
+    ] {
         if let Some(parent) = path.parent() {
             let _ = std::fs::create_dir_all(parent);
         }
@@ -623,6 +840,11 @@ fn init_debug_log_file() -> Result<(), String> {
         }
     }
 
+    (files, opened_paths)
+}
+
+fn init_debug_log_file() -> Result<(), String> {
+    let (files, opened_paths) = open_debug_files("rustsearch-debug.log");
     if files.is_empty() {
         return Err("failed to open any debug log file".to_string());
     }
@@ -654,11 +876,98 @@ pub(crate) fn debug_log(message: &str) {
     eprintln!("{}", line.trim_end());
 }
 
+fn init_debug_event_file() -> Result<(), String> {
+    let (files, opened_paths) = open_debug_files("wizmini-events.jsonl");
+    if files.is_empty() {
+        return Err("failed to open any debug event file".to_string());
+    }
+
+    let _ = DEBUG_EVENT_FILES.set(std::sync::Mutex::new(files));
+    debug_log(&format!(
+        "event files initialized at {}",
+        opened_paths.join(" | ")
+    ));
+    Ok(())
+}
+
+/// What a `debug_event` record is about.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum DebugCategory {
+    /// Index jobs: scope roots, volumes, snapshots, and results.
+    Index,
+    /// Change journal polling and recovery on live NTFS indexes.
+    Usn,
+}
+
+/// With `WIZMINI_DEBUG=2`, appends one JSON line to `wizmini-events.jsonl`
+/// alongside the text log, so a whole session can be attached to a bug report.
+pub(crate) fn debug_event(category: DebugCategory, job_id: u64, fields: serde_json::Value) {
+    let Some(files_mutex) = DEBUG_EVENT_FILES.get() else {
+        return;
+    };
+
+    let ts_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let mut line = serde_json::json!({
+        "ts_ms": ts_ms,
+        "category": category,
+        "job_id": job_id,
+        "fields": fields,
+    })
+    .to_string();
+    line.push('\n');
+
+    if let Ok(mut files) = files_mutex.lock() {
+        for file in files.iter_mut() {
+            let _ = file.write_all(line.as_bytes());
+            let _ = file.flush();
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct SearchItem {
-    pub(crate) path: Box<str>,
+    pub(crate) folder: Arc<str>,
+    pub(crate) name: Box<str>,
     pub(crate) modified_unix_secs: i64,
+    pub(crate) size: u64,
     pub(crate) kind: SearchItemKind,
+    /// Windows file attribute bits, or 0 when they were not read.
+    pub(crate) attrs: u32,
+    /// NTFS file reference number, shared by every hard link to the file, or
+    /// 0 when the item did not come from the MFT.
+    pub(crate) file_id: u64,
+}
+
+impl SearchItem {
+    pub(crate) fn new(
+        path: &str,
+        modified_unix_secs: i64,
+        size: u64,
+        kind: SearchItemKind,
+    ) -> Self {
+        path_table::PathTable::default().item(path, modified_unix_secs, size, kind)
+    }
+
+    pub(crate) fn with_attrs(mut self, attrs: u32) -> Self {
+        self.attrs = attrs;
+        self
+    }
+
+    pub(crate) fn with_file_id(mut self, file_id: u64) -> Self {
+        self.file_id = file_id;
+        self
+    }
+
+    pub(crate) fn full_path(&self) -> String {
+        let mut path = String::with_capacity(self.folder.len() + self.name.len());
+        path.push_str(&self.folder);
+        path.push_str(&self.name);
+        path
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -676,8 +985,19 @@ pub(crate) enum IndexEvent {
         job_id: u64,
         current: usize,
         total: usize,
+        /// Items handled so far, which `current` only matches when the total is exact.
+        scanned: usize,
+        /// `total` is a rough USN-range estimate rather than an item count.
+        total_is_estimate: bool,
         phase: &'static str,
     },
+    /// One volume of a multi-volume scope finished its initial scan, named by
+    /// its mount prefix. The corpus is built up from these until `Done` arrives.
+    VolumeLoaded {
+        job_id: u64,
+        volume: String,
+        items: Vec<SearchItem>,
+    },
     Done {
         job_id: u64,
         items: Vec<SearchItem>,
@@ -687,6 +1007,22 @@ pub(crate) enum IndexEvent {
         job_id: u64,
         upserts: Vec<SearchItem>,
         deleted_paths: Vec<String>,
+        /// How upserted paths changed, where the journal says so. Paths
+        /// missing here count as created when new and modified otherwise.
+        kinds: HashMap<String, ChangeKind>,
+    },
+    /// A volume in the scope could not be indexed, with a message saying why
+    /// and what to do about it. The job carries on with the other roots.
+    Error {
+        job_id: u64,
+        drive: String,
+        message: String,
+    },
+    /// The USN journal of a live volume could not be read again after
+    /// repeated attempts, so the live job stopped and a reindex is needed.
+    LiveUpdatesLost {
+        job_id: u64,
+        volume: String,
     },
 }
 
@@ -697,10 +1033,13 @@ pub(crate) enum WindowModeRequest {
     SetResultRows(usize),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub(crate) enum RendererModeRequest {
-    Gpu,
     Soft,
+    #[default]
+    #[serde(other)]
+    Gpu,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -736,21 +1075,21 @@ impl IndexBackend {
     }
 }
 
-pub(crate) fn backend_status_color(backend: IndexBackend) -> Color {
-    match backend {
-        IndexBackend::NtfsUsnLive => Color::Rgb(117, 227, 140),
-        IndexBackend::NtfsMft => Color::Rgb(130, 210, 255),
-        IndexBackend::Mixed => Color::Rgb(255, 198, 92),
-        IndexBackend::WalkDir => Color::Rgb(184, 184, 184),
-        IndexBackend::Detecting => Color::Rgb(170, 170, 170),
-    }
+pub(crate) fn backend_status_color(palette: &Palette, backend: IndexBackend) -> Color {
+    let rgb = match backend {
+        IndexBackend::NtfsUsnLive => palette.ok,
+        IndexBackend::NtfsMft => palette.info,
+        IndexBackend::Mixed => palette.warn,
+        IndexBackend::WalkDir | IndexBackend::Detecting => palette.neutral,
+    };
+    rgb.into()
 }
 
-pub(crate) fn state_status_color(indexing_in_progress: bool) -> Color {
+pub(crate) fn state_status_color(palette: &Palette, indexing_in_progress: bool) -> Color {
     if indexing_in_progress {
-        Color::Rgb(255, 184, 76)
+        palette.warn.into()
     } else {
-        Color::Rgb(117, 227, 140)
+        palette.ok.into()
     }
 }
 
@@ -763,17 +1102,50 @@ impl SearchScope {
             Self::Drive(letter) => format!("{}:", letter.to_ascii_uppercase()),
         }
     }
+
+    /// The scope Ctrl+Tab moves to: current folder, current drive, all
+    /// drives, and around again. A single drive continues at the current folder.
+    pub(crate) fn next(&self) -> SearchScope {
+        match self {
+            Self::CurrentFolder => Self::EntireCurrentDrive,
+            Self::EntireCurrentDrive => Self::AllLocalDrives,
+            Self::AllLocalDrives | Self::Drive(_) => Self::CurrentFolder,
+        }
+    }
 }
 
 pub(crate) fn estimate_index_memory_bytes(items: &[SearchItem]) -> usize {
     let mut total = std::mem::size_of_val(items);
+    let mut folders = std::collections::HashSet::new();
     for item in items {
-        total += std::mem::size_of::<SearchItem>();
-        total += item.path.len();
+        total += item.name.len();
+        if folders.insert(Arc::as_ptr(&item.folder)) {
+            total += item.folder.len();
+        }
     }
     total
 }
 
+pub(crate) fn format_item_size(size: u64) -> String {
+    if size == UNKNOWN_SIZE {
+        String::new()
+    } else {
+        format_bytes(size as usize)
+    }
+}
+
+pub(crate) fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (idx, ch) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out
+}
+
 pub(crate) fn format_bytes(bytes: usize) -> String {
     const KB: f64 = 1024.0;
     const MB: f64 = KB * 1024.0;
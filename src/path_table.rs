@@ -0,0 +1,146 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::search::file_name_from_path;
+use crate::{SearchItem, SearchItemKind};
+
+/// Interns parent folders so items in the same directory share one allocation.
+#[derive(Default)]
+pub(crate) struct PathTable {
+    folders: HashSet<Arc<str>>,
+}
+
+impl PathTable {
+    pub(crate) fn intern(&mut self, folder: &str) -> Arc<str> {
+        if let Some(existing) = self.folders.get(folder) {
+            return existing.clone();
+        }
+
+        let folder: Arc<str> = Arc::from(folder);
+        self.folders.insert(folder.clone());
+        folder
+    }
+
+    pub(crate) fn item(
+        &mut self,
+        path: &str,
+        modified_unix_secs: i64,
+        size: u64,
+        kind: SearchItemKind,
+    ) -> SearchItem {
+        let (folder, name) = split_path(path);
+        SearchItem {
+            folder: self.intern(folder),
+            name: name.into(),
+            modified_unix_secs,
+            size,
+            kind,
+            attrs: 0,
+            file_id: 0,
+        }
+    }
+}
+
+/// Position of every item in a corpus, by folder and then name.
+pub(crate) type PathIndex = HashMap<Arc<str>, HashMap<Box<str>, usize>>;
+
+/// Replaces the item at `item`'s path, or appends it when the path is new, so
+/// a path is never listed twice. Returns its position and whether it was added.
+pub(crate) fn upsert_item(
+    items: &mut Vec<SearchItem>,
+    index: &mut PathIndex,
+    mut item: SearchItem,
+) -> (usize, bool) {
+    // Share the folder allocation with items already indexed there.
+    if let Some((folder, _)) = index.get_key_value(item.folder.as_ref()) {
+        item.folder = folder.clone();
+    }
+    let siblings = index.entry(item.folder.clone()).or_default();
+    if let Some(&slot) = siblings.get(item.name.as_ref()) {
+        items[slot] = item;
+        return (slot, false);
+    }
+    siblings.insert(item.name.clone(), items.len());
+    items.push(item);
+    (items.len() - 1, true)
+}
+
+/// Removes the item at `path` by moving the last item into its slot, as
+/// `Vec::swap_remove` does. Returns the removed item and its former slot.
+pub(crate) fn remove_item(
+    items: &mut Vec<SearchItem>,
+    index: &mut PathIndex,
+    path: &str,
+) -> Option<(SearchItem, usize)> {
+    let (folder, name) = split_path(path);
+    let siblings = index.get_mut(folder)?;
+    let slot = siblings.remove(name)?;
+    if siblings.is_empty() {
+        index.remove(folder);
+    }
+    let removed = items.swap_remove(slot);
+    if let Some(moved) = items.get(slot) {
+        if let Some(position) = index
+            .get_mut(moved.folder.as_ref())
+            .and_then(|siblings| siblings.get_mut(moved.name.as_ref()))
+        {
+            *position = slot;
+        }
+    }
+    Some((removed, slot))
+}
+
+/// Splits a path into its folder (with trailing separator) and file name.
+pub(crate) fn split_path(path: &str) -> (&str, &str) {
+    let name = file_name_from_path(path);
+    path.split_at(path.len() - name.len())
+}
+
+/// Whether items in `folder` lie under `root`, a path ending in a separator.
+pub(crate) fn folder_is_under(folder: &str, root: &str) -> bool {
+    folder
+        .get(..root.len())
+        .is_some_and(|head| head.eq_ignore_ascii_case(root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upserting_a_path_twice_keeps_one_item() {
+        let mut items = Vec::new();
+        let mut index = PathIndex::new();
+        let first = SearchItem::new("C:\\docs\\a.txt", 1, 10, SearchItemKind::File);
+        let again = SearchItem::new("C:\\docs\\a.txt", 2, 20, SearchItemKind::File);
+        let sibling = SearchItem::new("C:\\docs\\b.txt", 1, 10, SearchItemKind::File);
+
+        assert_eq!(upsert_item(&mut items, &mut index, first), (0, true));
+        assert_eq!(upsert_item(&mut items, &mut index, sibling), (1, true));
+        assert_eq!(upsert_item(&mut items, &mut index, again), (0, false));
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].modified_unix_secs, 2);
+        assert!(Arc::ptr_eq(&items[0].folder, &items[1].folder));
+
+        let removed = remove_item(&mut items, &mut index, "C:\\docs\\a.txt").unwrap();
+        assert_eq!(
+            (removed.0.full_path(), removed.1),
+            ("C:\\docs\\a.txt".into(), 0)
+        );
+        assert!(remove_item(&mut items, &mut index, "C:\\docs\\a.txt").is_none());
+        assert_eq!(index["C:\\docs\\"]["b.txt"], 0);
+        assert_eq!(items[0].full_path(), "C:\\docs\\b.txt");
+    }
+
+    #[test]
+    fn folders_fall_under_their_roots() {
+        assert!(folder_is_under("C:\\", "C:\\"));
+        assert!(folder_is_under(
+            "c:\\mounts\\data\\docs\\",
+            "C:\\Mounts\\Data\\"
+        ));
+        assert!(!folder_is_under("C:\\Mounts\\", "C:\\Mounts\\Data\\"));
+        assert!(!folder_is_under("D:\\", "C:\\"));
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+use serde::Deserialize;
+
+use crate::cli::CliItem;
+use crate::indexing::IndexOptions;
+use crate::path_table::split_path;
+use crate::search::SearchQuery;
+use crate::settings::Settings;
+use crate::{debug_log, indexing, parse_scope_arg, IndexEvent, SearchItem, SearchScope};
+
+pub(crate) const PIPE_NAME: &str = r"\\.\pipe\wizmini";
+
+#[derive(Debug, Deserialize)]
+struct PipeRequest {
+    query: String,
+    #[serde(default)]
+    scope: Option<String>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+struct ScopeCorpus {
+    items: Vec<SearchItem>,
+    ready: bool,
+    /// The live job gave up on the journal and stopped; a new one is needed.
+    live_lost: bool,
+    rx: mpsc::Receiver<IndexEvent>,
+}
+
+/// Keeps one live index per requested scope and answers queries against it.
+struct QueryServer {
+    default_scope: SearchScope,
+    corpora: HashMap<String, ScopeCorpus>,
+    job_counter: u64,
+}
+
+impl QueryServer {
+    fn new(default_scope: SearchScope) -> Self {
+        Self {
+            default_scope,
+            corpora: HashMap::new(),
+            job_counter: 0,
+        }
+    }
+
+    fn handle_line(&mut self, line: &str) -> String {
+        let request = match serde_json::from_str::<PipeRequest>(line) {
+            Ok(request) => request,
+            Err(err) => return error_reply(&format!("invalid request: {}", err)),
+        };
+
+        let scope = match request.scope.as_deref() {
+            Some(value) => match parse_scope_arg(value) {
+                Some(scope) => scope,
+                None => return error_reply(&format!("unknown scope: {}", value)),
+            },
+            None => self.default_scope.clone(),
+        };
+
+        let query = SearchQuery::parse(&request.query.to_lowercase());
+        let limit = request.limit.unwrap_or(usize::MAX);
+        let corpus = self.corpus_for(scope);
+        let items: Vec<CliItem> = corpus
+            .items
+            .iter()
+            .filter(|item| query.matches_item(item))
+            .take(limit)
+            .map(CliItem::from)
+            .collect();
+        serde_json::to_string(&items).unwrap_or_else(|err| error_reply(&err.to_string()))
+    }
+
+    fn corpus_for(&mut self, scope: SearchScope) -> &ScopeCorpus {
+        let key = scope.label();
+        if !self.corpora.contains_key(&key) {
+            let rx = self.spawn_index_job(scope.clone());
+            self.corpora.insert(
+                key.clone(),
+                ScopeCorpus {
+                    items: Vec::new(),
+                    ready: false,
+                    live_lost: false,
+                    rx,
+                },
+            );
+        }
+
+        let corpus = self.corpora.get_mut(&key).expect("corpus inserted above");
+        corpus.drain_events();
+        if corpus.live_lost {
+            // Keep answering from the stale items until the new job replaces them.
+            debug_log(&format!(
+                "pipe server reindexing {} after live updates were lost",
+                key
+            ));
+            let rx = self.spawn_index_job(scope);
+            let corpus = self.corpora.get_mut(&key).expect("corpus inserted above");
+            corpus.rx = rx;
+            corpus.live_lost = false;
+        }
+        &self.corpora[&key]
+    }
+
+    fn spawn_index_job(&mut self, scope: SearchScope) -> mpsc::Receiver<IndexEvent> {
+        self.job_counter += 1;
+        let job_id = self.job_counter;
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let options = IndexOptions::from_settings(&Settings::load_or_default(), true);
+        thread::spawn(move || indexing::run_index_job(scope, job_id, tx, cancel, options));
+        rx
+    }
+}
+
+impl ScopeCorpus {
+    fn drain_events(&mut self) {
+        let mut upserts = Vec::new();
+        let mut deleted_paths = Vec::new();
+
+        loop {
+            let event = if self.ready {
+                match self.rx.try_recv() {
+                    Ok(event) => event,
+                    Err(_) => break,
+                }
+            } else {
+                match self.rx.recv() {
+                    Ok(event) => event,
+                    Err(_) => {
+                        self.ready = true;
+                        break;
+                    }
+                }
+            };
+
+            match event {
+                IndexEvent::SnapshotLoaded { items, .. } | IndexEvent::Done { items, .. } => {
+                    self.items = items;
+                    self.ready = true;
+                    upserts.clear();
+                    deleted_paths.clear();
+                }
+                IndexEvent::Delta {
+                    upserts: batch_upserts,
+                    deleted_paths: batch_deleted,
+                    ..
+                } => {
+                    upserts.extend(batch_upserts);
+                    deleted_paths.extend(batch_deleted);
+                }
+                IndexEvent::LiveUpdatesLost { .. } => self.live_lost = true,
+                IndexEvent::Progress { .. }
+                | IndexEvent::VolumeLoaded { .. }
+                | IndexEvent::Error { .. } => {}
+            }
+        }
+
+        apply_delta(&mut self.items, upserts, deleted_paths);
+    }
+}
+
+fn apply_delta(items: &mut Vec<SearchItem>, upserts: Vec<SearchItem>, deleted_paths: Vec<String>) {
+    if upserts.is_empty() && deleted_paths.is_empty() {
+        return;
+    }
+
+    let mut replaced: HashSet<(&str, &str)> =
+        deleted_paths.iter().map(|path| split_path(path)).collect();
+    replaced.extend(
+        upserts
+            .iter()
+            .map(|item| (item.folder.as_ref(), item.name.as_ref())),
+    );
+    items.retain(|item| !replaced.contains(&(item.folder.as_ref(), item.name.as_ref())));
+
+    let mut seen = HashSet::new();
+    for upsert in upserts.into_iter().rev() {
+        if seen.insert(upsert.full_path()) {
+            items.push(upsert);
+        }
+    }
+}
+
+fn error_reply(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn run_pipe_server(scope: SearchScope) -> i32 {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::FromRawHandle;
+
+    use windows_sys::Win32::Foundation::{
+        GetLastError, ERROR_PIPE_CONNECTED, INVALID_HANDLE_VALUE,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{FlushFileBuffers, PIPE_ACCESS_DUPLEX};
+    use windows_sys::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, PIPE_READMODE_BYTE,
+        PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+
+    crate::cli::attach_parent_console();
+
+    let name: Vec<u16> = std::ffi::OsStr::new(PIPE_NAME)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut server = QueryServer::new(scope);
+    // Start indexing the default scope before the first client connects.
+    let _ = server.corpus_for(server.default_scope.clone());
+
+    loop {
+        let handle = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                PIPE_ACCESS_DUPLEX,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                PIPE_UNLIMITED_INSTANCES,
+                64 * 1024,
+                64 * 1024,
+                0,
+                std::ptr::null(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            debug_log(&format!("CreateNamedPipeW failed: {}", unsafe {
+                GetLastError()
+            }));
+            return 1;
+        }
+
+        let connected = unsafe { ConnectNamedPipe(handle, std::ptr::null_mut()) } != 0
+            || unsafe { GetLastError() } == ERROR_PIPE_CONNECTED;
+        // The file owns the handle from here, so every path closes it.
+        let pipe = unsafe { std::fs::File::from_raw_handle(handle) };
+        if !connected {
+            continue;
+        }
+
+        let mut writer = &pipe;
+        for line in BufReader::new(&pipe).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let reply = server.handle_line(&line);
+            if writeln!(writer, "{}", reply).is_err() {
+                break;
+            }
+        }
+
+        unsafe {
+            FlushFileBuffers(handle);
+            DisconnectNamedPipe(handle);
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn run_pipe_server(_scope: SearchScope) -> i32 {
+    debug_log("--serve is only supported on Windows");
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SearchItemKind;
+
+    fn item(path: &str) -> SearchItem {
+        SearchItem::new(path, 0, 0, SearchItemKind::File)
+    }
+
+    fn server_with(items: Vec<SearchItem>) -> (QueryServer, mpsc::Sender<IndexEvent>) {
+        let (tx, rx) = mpsc::channel();
+        let mut server = QueryServer::new(SearchScope::CurrentFolder);
+        server.corpora.insert(
+            SearchScope::CurrentFolder.label(),
+            ScopeCorpus {
+                items,
+                ready: true,
+                live_lost: false,
+                rx,
+            },
+        );
+        (server, tx)
+    }
+
+    #[test]
+    fn answers_queries_with_limit_and_live_deltas() {
+        let (mut server, tx) = server_with(vec![
+            item("C:\\docs\\a.txt"),
+            item("C:\\docs\\b.txt"),
+            item("C:\\docs\\c.pdf"),
+        ]);
+
+        assert_eq!(
+            server.handle_line(r#"{"query":"txt","limit":1}"#),
+            r#"[{"path":"C:\\docs\\a.txt","modified_unix_secs":0,"size":0}]"#
+        );
+
+        tx.send(IndexEvent::Delta {
+            job_id: 1,
+            upserts: vec![item("C:\\docs\\d.txt")],
+            deleted_paths: vec!["C:\\docs\\a.txt".to_string()],
+            kinds: HashMap::new(),
+        })
+        .unwrap();
+        let reply = server.handle_line(r#"{"query":"txt"}"#);
+        assert!(!reply.contains("a.txt"));
+        assert!(reply.contains("b.txt"));
+        assert!(reply.contains("d.txt"));
+    }
+
+    #[test]
+    fn reports_malformed_requests() {
+        let (mut server, _tx) = server_with(Vec::new());
+        assert!(server.handle_line("not json").starts_with(r#"{"error":"#));
+        assert!(server
+            .handle_line(r#"{"query":"a","scope":"nowhere"}"#)
+            .contains("unknown scope"));
+    }
+}
@@ -1,4 +1,5 @@
-use std::{env, process::Command};
+#[cfg(target_os = "windows")]
+use std::env;
 
 #[cfg(target_os = "windows")]
 use std::os::windows::ffi::OsStrExt;
@@ -53,22 +54,597 @@ pub(crate) fn request_self_elevation(_scope: &SearchScope) -> Result<(), String>
     Err("Elevation is only supported on Windows".to_string())
 }
 
+/// Legacy path limit in UTF-16 units, counting the terminating NUL.
+const MAX_PATH: usize = 260;
+
+/// Adds the `\\?\` extended-length prefix to absolute paths too long for
+/// the legacy limit, such as files deep under `node_modules`, for the raw
+/// Win32 calls that need it; `std::fs` adds it on its own. Shorter, relative
+/// and already prefixed paths are returned as they are.
+#[cfg(any(target_os = "windows", test))]
+fn to_extended_path(path: &str) -> String {
+    if path.encode_utf16().count() < MAX_PATH || path.starts_with(r"\\?\") {
+        return path.to_string();
+    }
+    // Extended-length paths skip normalization, so only backslashes work.
+    let path = path.replace('/', "\\");
+    if let Some(unc) = path.strip_prefix(r"\\") {
+        return format!(r"\\?\UNC\{}", unc);
+    }
+    let bytes = path.as_bytes();
+    if bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\' {
+        return format!(r"\\?\{}", path);
+    }
+    path
+}
+
+/// Drops the `\\?\` or `\\?\UNC\` prefix again, for APIs that only take
+/// ordinary paths.
+#[cfg(any(target_os = "windows", test))]
+fn strip_extended_prefix(path: &str) -> String {
+    if let Some(unc) = path.strip_prefix(r"\\?\UNC\") {
+        return format!(r"\\{}", unc);
+    }
+    path.strip_prefix(r"\\?\").unwrap_or(path).to_string()
+}
+
+/// A form of `path` the shell accepts. `ShellExecuteW` and the shell file
+/// operations reject `\\?\` paths, so one past the legacy limit is swapped
+/// for its 8.3 short name where the volume keeps them.
+#[cfg(target_os = "windows")]
+fn shell_path(path: &str) -> String {
+    use windows_sys::Win32::Storage::FileSystem::GetShortPathNameW;
+
+    if path.encode_utf16().count() < MAX_PATH {
+        return path.to_string();
+    }
+    let long = to_wide(&to_extended_path(path));
+    // A short name is never longer than the long one.
+    let mut short = vec![0u16; long.len()];
+    let len = unsafe { GetShortPathNameW(long.as_ptr(), short.as_mut_ptr(), short.len() as u32) }
+        as usize;
+    if len == 0 || len >= short.len() {
+        return path.to_string();
+    }
+    strip_extended_prefix(&String::from_utf16_lossy(&short[..len]))
+}
+
+/// The Windows attribute bits of a file, such as hidden or read-only.
+#[cfg(target_os = "windows")]
+pub(crate) fn file_attributes(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::windows::fs::MetadataExt;
+    metadata.file_attributes()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn file_attributes(_metadata: &std::fs::Metadata) -> u32 {
+    0
+}
+
+/// Opens `path` with its associated application through `ShellExecuteW`, so
+/// characters like `&` and `^` are passed through untouched.
+#[cfg(target_os = "windows")]
 pub(crate) fn open_path(path: &str) -> Result<(), String> {
-    Command::new("cmd")
-        .args(["/C", "start", "", path])
-        .spawn()
-        .map_err(|e| e.to_string())?;
-    Ok(())
+    shell_open(&shell_path(path), None)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn open_path(_path: &str) -> Result<(), String> {
+    Err("Opening files is only supported on Windows".to_string())
 }
 
+/// Opens the folder that contains `path` without selecting the item.
+#[cfg(target_os = "windows")]
+pub(crate) fn open_containing_folder(path: &str) -> Result<(), String> {
+    let folder = std::path::Path::new(path)
+        .parent()
+        .filter(|folder| !folder.as_os_str().is_empty())
+        .ok_or_else(|| format!("No containing folder for {}", path))?;
+    shell_open(&shell_path(&folder.to_string_lossy()), None)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn open_containing_folder(_path: &str) -> Result<(), String> {
+    Err("Opening folders is only supported on Windows".to_string())
+}
+
+/// Launches `program` with `path` as its only argument. `program` may be a
+/// full path or a name the shell can resolve, such as `code` or `notepad`.
+#[cfg(target_os = "windows")]
+pub(crate) fn open_with(path: &str, program: &str) -> Result<(), String> {
+    shell_open(program, Some(&quote_command_arg(&shell_path(path))))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn open_with(_path: &str, _program: &str) -> Result<(), String> {
+    Err("Opening files is only supported on Windows".to_string())
+}
+
+/// Opens a shell in `folder`: Windows Terminal when `wt.exe` can be found,
+/// otherwise `cmd.exe`. Returns the name of the terminal that was launched.
+#[cfg(target_os = "windows")]
+pub(crate) fn open_terminal_at(folder: &str) -> Result<&'static str, String> {
+    let wt_args = format!("-d {}", quote_command_arg(folder));
+    if shell_open("wt.exe", Some(&wt_args)).is_ok() {
+        return Ok("Windows Terminal");
+    }
+    shell_open("cmd.exe", Some(&cmd_cd_arg(folder)))?;
+    Ok("Command Prompt")
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn open_terminal_at(_folder: &str) -> Result<&'static str, String> {
+    Err("Opening a terminal is only supported on Windows".to_string())
+}
+
+/// Opens Explorer on the parent folder with `path` selected, through the
+/// shell's item ids rather than an `explorer /select,` command line, which
+/// splits on commas and rejects long paths.
+#[cfg(target_os = "windows")]
 pub(crate) fn reveal_path(path: &str) -> Result<(), String> {
-    Command::new("explorer")
-        .arg(format!("/select,{}", path))
-        .spawn()
-        .map_err(|e| e.to_string())?;
+    use windows_sys::Win32::System::Com::{CoInitializeEx, COINIT_APARTMENTTHREADED};
+    use windows_sys::Win32::UI::Shell::{ILFree, SHOpenFolderAndSelectItems, SHParseDisplayName};
+
+    // A thread that already set up COM reports so, and COM stays usable.
+    unsafe { CoInitializeEx(std::ptr::null(), COINIT_APARTMENTTHREADED as u32) };
+    let name = to_wide(&shell_path(path));
+    let mut item = std::ptr::null_mut();
+    let result = unsafe {
+        SHParseDisplayName(
+            name.as_ptr(),
+            std::ptr::null_mut(),
+            &mut item,
+            0,
+            std::ptr::null_mut(),
+        )
+    };
+    if result < 0 {
+        return Err(format!("{} not found (0x{:08X})", path, result as u32));
+    }
+    let result = unsafe { SHOpenFolderAndSelectItems(item, 0, std::ptr::null(), 0) };
+    unsafe { ILFree(item) };
+    if result < 0 {
+        return Err(format!(
+            "Explorer could not select {} (0x{:08X})",
+            path, result as u32
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn reveal_path(_path: &str) -> Result<(), String> {
+    Err("Revealing files is only supported on Windows".to_string())
+}
+
+/// Renames `old` to `new` with `MoveFileExW`, failing instead of replacing an
+/// existing target.
+#[cfg(target_os = "windows")]
+pub(crate) fn rename_path(old: &str, new: &str) -> Result<(), String> {
+    use windows_sys::Win32::Storage::FileSystem::MoveFileExW;
+
+    let from = to_wide(&to_extended_path(old));
+    let to = to_wide(&to_extended_path(new));
+    if unsafe { MoveFileExW(from.as_ptr(), to.as_ptr(), 0) } == 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn rename_path(_old: &str, _new: &str) -> Result<(), String> {
+    Err("Renaming files is only supported on Windows".to_string())
+}
+
+/// Moves `paths` to the Recycle Bin in one shell operation. Explorer asks for
+/// confirmation, and declining it is reported as an error.
+#[cfg(target_os = "windows")]
+pub(crate) fn recycle_paths(paths: &[String]) -> Result<(), String> {
+    use windows_sys::Win32::UI::Shell::{
+        SHFileOperationW, FOF_ALLOWUNDO, FO_DELETE, SHFILEOPSTRUCTW,
+    };
+
+    // pFrom is a list of NUL-terminated paths ending in an extra NUL.
+    let mut from: Vec<u16> = Vec::new();
+    for path in paths {
+        from.extend(std::ffi::OsStr::new(&shell_path(path)).encode_wide());
+        from.push(0);
+    }
+    from.push(0);
+
+    let mut op = SHFILEOPSTRUCTW {
+        wFunc: FO_DELETE,
+        pFrom: from.as_ptr(),
+        fFlags: FOF_ALLOWUNDO as u16,
+        ..Default::default()
+    };
+    let result = unsafe { SHFileOperationW(&mut op) };
+    if result != 0 {
+        return Err(format!("SHFileOperation failed (code {})", result));
+    }
+    if op.fAnyOperationsAborted != 0 {
+        return Err("cancelled".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn recycle_paths(_paths: &[String]) -> Result<(), String> {
+    Err("Recycling files is only supported on Windows".to_string())
+}
+
+/// Moves the most recently recycled copy of `path` back into place. Each
+/// item in `<drive>\$Recycle.Bin\<user SID>` is a `$R` file or folder with a
+/// matching `$I` file that records where it was deleted from.
+#[cfg(target_os = "windows")]
+pub(crate) fn restore_recycled(path: &str) -> Result<(), String> {
+    let target = std::path::Path::new(path);
+    if target.exists() {
+        return Err("a file with that name exists again".to_string());
+    }
+    let Some(root) = target.ancestors().last() else {
+        return Err("not an absolute path".to_string());
+    };
+    let bin = std::fs::read_dir(root.join("$Recycle.Bin")).map_err(|err| err.to_string())?;
+
+    let mut newest: Option<(i64, std::path::PathBuf)> = None;
+    // Other users' folders refuse to list, which leaves only our own.
+    for user_dir in bin.flatten() {
+        let Ok(entries) = std::fs::read_dir(user_dir.path()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !entry.file_name().to_string_lossy().starts_with("$I") {
+                continue;
+            }
+            let Some((deleted_at, original)) = std::fs::read(entry.path())
+                .ok()
+                .and_then(|bytes| parse_recycle_info(&bytes))
+            else {
+                continue;
+            };
+            if original.eq_ignore_ascii_case(path)
+                && newest
+                    .as_ref()
+                    .is_none_or(|(newest, _)| deleted_at > *newest)
+            {
+                newest = Some((deleted_at, entry.path()));
+            }
+        }
+    }
+
+    let Some((_, info_path)) = newest else {
+        return Err("not found in the Recycle Bin".to_string());
+    };
+    let info_name = info_path.file_name().unwrap_or_default().to_string_lossy();
+    let data_path = info_path.with_file_name(format!("$R{}", &info_name[2..]));
+    std::fs::rename(&data_path, target).map_err(|err| err.to_string())?;
+    let _ = std::fs::remove_file(&info_path);
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn restore_recycled(_path: &str) -> Result<(), String> {
+    Err("Restoring files is only supported on Windows".to_string())
+}
+
+/// Reads a `$I` file into its deletion time (a FILETIME) and original path.
+/// Version 1 stores the path in a fixed `MAX_PATH` field, version 2 after its
+/// length.
+#[cfg(any(target_os = "windows", test))]
+fn parse_recycle_info(bytes: &[u8]) -> Option<(i64, String)> {
+    let read_u64 = |at: usize| Some(u64::from_le_bytes(bytes.get(at..at + 8)?.try_into().ok()?));
+    let deleted_at = read_u64(16)? as i64;
+    let units = match read_u64(0)? {
+        1 => bytes.get(24..24 + 260 * 2)?,
+        2 => {
+            let len = u32::from_le_bytes(bytes.get(24..28)?.try_into().ok()?) as usize;
+            bytes.get(28..28 + len * 2)?
+        }
+        _ => return None,
+    };
+    let wide: Vec<u16> = units
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    String::from_utf16(&wide)
+        .ok()
+        .filter(|path| !path.is_empty())
+        .map(|path| (deleted_at, path))
+}
+
+/// Checks a replacement file name against the rules Windows enforces, so the
+/// user sees why a rename was refused before anything touches the disk.
+pub(crate) fn validate_new_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("name is empty".to_string());
+    }
+    if name.contains(['\\', '/']) {
+        return Err("name cannot contain path separators".to_string());
+    }
+    if let Some(ch) = name
+        .chars()
+        .find(|ch| matches!(ch, '<' | '>' | ':' | '"' | '|' | '?' | '*') || ch.is_control())
+    {
+        return Err(format!("name cannot contain {:?}", ch));
+    }
+    if name == "." || name == ".." || name.ends_with(['.', ' ']) {
+        return Err("name cannot end with a dot or space".to_string());
+    }
     Ok(())
 }
 
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+#[cfg(target_os = "windows")]
+const RUN_VALUE: &str = "WizMini";
+
+/// Adds or removes the per-user Run entry that starts the app hidden at logon.
+#[cfg(target_os = "windows")]
+pub(crate) fn set_launch_at_startup(enabled: bool) -> Result<(), String> {
+    use windows_sys::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS};
+    use windows_sys::Win32::System::Registry::{
+        RegCloseKey, RegDeleteValueW, RegOpenKeyExW, RegSetValueExW, HKEY, HKEY_CURRENT_USER,
+        KEY_SET_VALUE, REG_SZ,
+    };
+
+    let command = if enabled {
+        let exe_path = env::current_exe().map_err(|e| e.to_string())?;
+        Some(to_wide(&startup_command(&exe_path.to_string_lossy())))
+    } else {
+        None
+    };
+
+    let key_name = to_wide(RUN_KEY);
+    let value_name = to_wide(RUN_VALUE);
+    let mut key: HKEY = std::ptr::null_mut();
+    let status = unsafe {
+        RegOpenKeyExW(
+            HKEY_CURRENT_USER,
+            key_name.as_ptr(),
+            0,
+            KEY_SET_VALUE,
+            &mut key,
+        )
+    };
+    if status != ERROR_SUCCESS {
+        return Err(format!("Could not open Run key (code {})", status));
+    }
+
+    let status = match command {
+        Some(command) => unsafe {
+            RegSetValueExW(
+                key,
+                value_name.as_ptr(),
+                0,
+                REG_SZ,
+                command.as_ptr().cast(),
+                (command.len() * std::mem::size_of::<u16>()) as u32,
+            )
+        },
+        None => match unsafe { RegDeleteValueW(key, value_name.as_ptr()) } {
+            ERROR_FILE_NOT_FOUND => ERROR_SUCCESS,
+            status => status,
+        },
+    };
+    unsafe { RegCloseKey(key) };
+
+    if status == ERROR_SUCCESS {
+        Ok(())
+    } else {
+        Err(format!("Could not update Run entry (code {})", status))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn set_launch_at_startup(_enabled: bool) -> Result<(), String> {
+    Err("Launch at startup is only supported on Windows".to_string())
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn launch_at_startup_enabled() -> Result<bool, String> {
+    use windows_sys::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS};
+    use windows_sys::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ};
+
+    let key_name = to_wide(RUN_KEY);
+    let value_name = to_wide(RUN_VALUE);
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key_name.as_ptr(),
+            value_name.as_ptr(),
+            RRF_RT_REG_SZ,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    match status {
+        ERROR_SUCCESS => Ok(true),
+        ERROR_FILE_NOT_FOUND => Ok(false),
+        status => Err(format!("Could not read Run entry (code {})", status)),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn launch_at_startup_enabled() -> Result<bool, String> {
+    Err("Launch at startup is only supported on Windows".to_string())
+}
+
+/// How Windows classifies a volume, as reported by `GetDriveTypeW`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DriveKind {
+    Fixed,
+    Removable,
+    Network,
+    Optical,
+    RamDisk,
+    Unknown,
+}
+
+impl DriveKind {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Fixed => "fixed",
+            Self::Removable => "removable",
+            Self::Network => "network",
+            Self::Optical => "optical",
+            Self::RamDisk => "ram disk",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// Classifies a drive root such as `D:\` or a `\\?\Volume{GUID}\` name
+/// without touching the file system on it.
+#[cfg(target_os = "windows")]
+pub(crate) fn drive_kind(root: &str) -> DriveKind {
+    use windows_sys::Win32::Storage::FileSystem::GetDriveTypeW;
+    use windows_sys::Win32::System::WindowsProgramming::{
+        DRIVE_CDROM, DRIVE_FIXED, DRIVE_RAMDISK, DRIVE_REMOTE, DRIVE_REMOVABLE,
+    };
+
+    let mut root = root.to_string();
+    if !root.ends_with('\\') {
+        root.push('\\');
+    }
+    let wide = to_wide(&root);
+    match unsafe { GetDriveTypeW(wide.as_ptr()) } {
+        DRIVE_FIXED => DriveKind::Fixed,
+        DRIVE_REMOVABLE => DriveKind::Removable,
+        DRIVE_REMOTE => DriveKind::Network,
+        DRIVE_CDROM => DriveKind::Optical,
+        DRIVE_RAMDISK => DriveKind::RamDisk,
+        _ => DriveKind::Unknown,
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn drive_kind(_root: &str) -> DriveKind {
+    DriveKind::Fixed
+}
+
+/// The file system name of a volume, such as `NTFS` or `exFAT`, or `None`
+/// when the volume cannot be queried.
+#[cfg(target_os = "windows")]
+pub(crate) fn volume_file_system(root: &str) -> Option<String> {
+    use windows_sys::Win32::Storage::FileSystem::GetVolumeInformationW;
+
+    let mut root = root.to_string();
+    if !root.ends_with('\\') {
+        root.push('\\');
+    }
+    let wide = to_wide(&root);
+    let mut name = [0u16; 32];
+    let ok = unsafe {
+        GetVolumeInformationW(
+            wide.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            name.as_mut_ptr(),
+            name.len() as u32,
+        )
+    };
+    if ok == 0 {
+        return None;
+    }
+    let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+    Some(String::from_utf16_lossy(&name[..len]))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn volume_file_system(_root: &str) -> Option<String> {
+    None
+}
+
+/// Lists `X:\` roots from the logical drive mask, which unlike probing each
+/// letter does not wait on disconnected network shares.
+#[cfg(target_os = "windows")]
+pub(crate) fn logical_drive_roots() -> Vec<String> {
+    let mask = unsafe { windows_sys::Win32::Storage::FileSystem::GetLogicalDrives() };
+    ('A'..='Z')
+        .enumerate()
+        .filter(|(bit, _)| mask & (1 << bit) != 0)
+        .map(|(_, letter)| format!("{}:\\", letter))
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn logical_drive_roots() -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn startup_command(exe_path: &str) -> String {
+    format!("\"{}\" --hidden", exe_path)
+}
+
+/// `cmd.exe` does not unescape backslashes, so the folder is quoted as is.
+#[cfg(any(target_os = "windows", test))]
+fn cmd_cd_arg(folder: &str) -> String {
+    format!("/K cd /d \"{}\"", folder)
+}
+
+/// Quotes one argument so `CommandLineToArgvW` reads it back unchanged; a
+/// folder path's trailing backslash must not escape the closing quote.
+#[cfg(any(target_os = "windows", test))]
+fn quote_command_arg(arg: &str) -> String {
+    let mut out = String::from('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => {
+                backslashes += 1;
+                continue;
+            }
+            '"' => out.push_str(&"\\".repeat(backslashes * 2 + 1)),
+            _ => out.push_str(&"\\".repeat(backslashes)),
+        }
+        backslashes = 0;
+        out.push(c);
+    }
+    out.push_str(&"\\".repeat(backslashes * 2));
+    out.push('"');
+    out
+}
+
+#[cfg(target_os = "windows")]
+fn shell_open(target: &str, parameters: Option<&str>) -> Result<(), String> {
+    use windows_sys::Win32::UI::Shell::{SE_ERR_FNF, SE_ERR_PNF};
+
+    let verb = to_wide("open");
+    let file = to_wide(target);
+    let parameters = parameters.map(to_wide);
+
+    let result = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            verb.as_ptr(),
+            file.as_ptr(),
+            parameters
+                .as_ref()
+                .map_or(std::ptr::null(), |parameters| parameters.as_ptr()),
+            std::ptr::null(),
+            SW_SHOWDEFAULT,
+        )
+    } as isize;
+
+    if result == SE_ERR_FNF as isize || result == SE_ERR_PNF as isize {
+        Err(format!("{} not found", target))
+    } else if result <= 32 {
+        Err(format!(
+            "ShellExecute failed for {} (code {})",
+            target, result
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 #[cfg(target_os = "windows")]
 fn to_wide(value: &str) -> Vec<u16> {
     std::ffi::OsStr::new(value)
@@ -76,3 +652,120 @@ fn to_wide(value: &str) -> Vec<u16> {
         .chain(std::iter::once(0))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cmd_cd_arg, parse_recycle_info, quote_command_arg, startup_command, strip_extended_prefix,
+        to_extended_path, validate_new_name,
+    };
+
+    #[test]
+    fn prefixes_only_paths_past_the_legacy_limit() {
+        let short = r"C:\src\app\main.rs";
+        assert_eq!(to_extended_path(short), short);
+
+        let deep = format!(r"C:\src{}\index.js", r"\node_modules\pkg".repeat(15));
+        assert!(deep.len() >= 260);
+        assert_eq!(to_extended_path(&deep), format!(r"\\?\{}", deep));
+        assert_eq!(
+            to_extended_path(&to_extended_path(&deep)),
+            to_extended_path(&deep)
+        );
+        assert_eq!(
+            to_extended_path(&deep.replace('\\', "/")),
+            to_extended_path(&deep)
+        );
+
+        let share = format!(r"\\server\share{}", r"\deep".repeat(50));
+        assert_eq!(
+            to_extended_path(&share),
+            format!(r"\\?\UNC\server\share{}", r"\deep".repeat(50))
+        );
+
+        let relative = r"deep\".repeat(60);
+        assert_eq!(to_extended_path(&relative), relative);
+    }
+
+    #[test]
+    fn strips_extended_prefixes_back_to_plain_paths() {
+        let deep = format!(r"C:\src{}\index.js", r"\node_modules\pkg".repeat(15));
+        assert_eq!(strip_extended_prefix(&to_extended_path(&deep)), deep);
+        let share = format!(r"\\server\share{}", r"\deep".repeat(50));
+        assert_eq!(strip_extended_prefix(&to_extended_path(&share)), share);
+        assert_eq!(strip_extended_prefix(r"C:\a.txt"), r"C:\a.txt");
+    }
+
+    #[test]
+    fn quotes_command_arguments() {
+        assert_eq!(
+            quote_command_arg(r"C:\My Files\a&b.txt"),
+            r#""C:\My Files\a&b.txt""#
+        );
+        assert_eq!(quote_command_arg(r"D:\src\"), r#""D:\src\\""#);
+        assert_eq!(quote_command_arg(r#"say "hi\""#), r#""say \"hi\\\"""#);
+        assert_eq!(cmd_cd_arg(r"D:\My src\"), r#"/K cd /d "D:\My src\""#);
+    }
+
+    #[test]
+    fn reads_both_recycle_info_versions() {
+        let wide = |path: &str| -> Vec<u8> {
+            path.encode_utf16()
+                .chain(std::iter::once(0))
+                .flat_map(u16::to_le_bytes)
+                .collect()
+        };
+        let header = |version: u64| -> Vec<u8> {
+            [version, 1234, 133_000_000_000_000_000]
+                .into_iter()
+                .flat_map(u64::to_le_bytes)
+                .collect()
+        };
+
+        let mut v2 = header(2);
+        v2.extend(u32::to_le_bytes(12));
+        v2.extend(wide(r"C:\tmp\Ä.txt"));
+        assert_eq!(
+            parse_recycle_info(&v2),
+            Some((133_000_000_000_000_000, r"C:\tmp\Ä.txt".to_string()))
+        );
+
+        let mut v1 = header(1);
+        v1.extend(wide(r"D:\old"));
+        v1.resize(24 + 520, 0);
+        assert_eq!(
+            parse_recycle_info(&v1),
+            Some((133_000_000_000_000_000, r"D:\old".to_string()))
+        );
+
+        assert_eq!(parse_recycle_info(&v1[..100]), None);
+        assert_eq!(parse_recycle_info(&header(3)), None);
+    }
+
+    #[test]
+    fn rejects_invalid_file_names() {
+        assert!(validate_new_name("report (final).txt").is_ok());
+        assert!(validate_new_name(".gitignore").is_ok());
+        for name in [
+            "",
+            "  ",
+            "a\\b.txt",
+            "a/b",
+            "what?.txt",
+            "c:",
+            "trailing.",
+            "..",
+            "x\u{7}",
+        ] {
+            assert!(validate_new_name(name).is_err(), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn startup_command_quotes_exe_and_starts_hidden() {
+        assert_eq!(
+            startup_command("C:\\Program Files\\WizMini\\rustsearch.exe"),
+            "\"C:\\Program Files\\WizMini\\rustsearch.exe\" --hidden"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use std::fs::File;
+use std::io::Read;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::{UNKNOWN_SIZE, UNKNOWN_TS};
+
+pub(crate) const PREVIEW_MAX_BYTES: usize = 64 * 1024;
+const HEX_DUMP_BYTES: usize = 512;
+
+pub(crate) struct PreviewResult {
+    pub(crate) path: String,
+    pub(crate) size: u64,
+    pub(crate) modified_unix_secs: i64,
+    pub(crate) content: PreviewContent,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum PreviewContent {
+    /// The file as text; `truncated` when only the first `PREVIEW_MAX_BYTES` were read.
+    Text {
+        text: String,
+        truncated: bool,
+    },
+    Binary {
+        hex: String,
+    },
+    Folder,
+    Error(String),
+}
+
+/// Reads previews off the UI thread. Requests that queue up while a file is
+/// being read are collapsed to the newest one.
+pub(crate) fn spawn_preview_worker() -> (mpsc::Sender<String>, mpsc::Receiver<PreviewResult>) {
+    let (request_tx, request_rx) = mpsc::channel::<String>();
+    let (result_tx, result_rx) = mpsc::channel::<PreviewResult>();
+
+    thread::spawn(move || {
+        while let Ok(mut path) = request_rx.recv() {
+            while let Ok(newer) = request_rx.try_recv() {
+                path = newer;
+            }
+            if result_tx.send(read_preview(path)).is_err() {
+                break;
+            }
+        }
+    });
+
+    (request_tx, result_rx)
+}
+
+fn read_preview(path: String) -> PreviewResult {
+    let mut result = PreviewResult {
+        path,
+        size: UNKNOWN_SIZE,
+        modified_unix_secs: UNKNOWN_TS,
+        content: PreviewContent::Folder,
+    };
+
+    let metadata = match std::fs::metadata(&result.path) {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            result.content = PreviewContent::Error(err.to_string());
+            return result;
+        }
+    };
+    result.modified_unix_secs = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(UNKNOWN_TS);
+    if metadata.is_dir() {
+        return result;
+    }
+    result.size = metadata.len();
+
+    let mut bytes = Vec::with_capacity(PREVIEW_MAX_BYTES.min(metadata.len() as usize));
+    let read = File::open(&result.path).and_then(|file| {
+        file.take(PREVIEW_MAX_BYTES as u64 + 1)
+            .read_to_end(&mut bytes)
+    });
+    result.content = match read {
+        Ok(_) => {
+            let truncated = bytes.len() > PREVIEW_MAX_BYTES;
+            bytes.truncate(PREVIEW_MAX_BYTES);
+            classify(&bytes, truncated)
+        }
+        Err(err) => PreviewContent::Error(err.to_string()),
+    };
+    result
+}
+
+fn classify(bytes: &[u8], truncated: bool) -> PreviewContent {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => Some(text),
+        // A multi-byte character cut off by the read limit is still text.
+        Err(err) if truncated && err.error_len().is_none() => {
+            std::str::from_utf8(&bytes[..err.valid_up_to()]).ok()
+        }
+        Err(_) => None,
+    };
+
+    match text {
+        Some(text) if !text.contains('\0') => PreviewContent::Text {
+            text: text.to_string(),
+            truncated,
+        },
+        _ => PreviewContent::Binary {
+            hex: hex_dump(&bytes[..bytes.len().min(HEX_DUMP_BYTES)]),
+        },
+    }
+}
+
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        out.push_str(&format!("{:08x} ", line * 16));
+        for byte in chunk {
+            out.push_str(&format!(" {:02x}", byte));
+        }
+        out.push_str(&"   ".repeat(16 - chunk.len()));
+        out.push_str("  ");
+        out.extend(chunk.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        }));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tells_text_from_binary() {
+        assert_eq!(
+            classify(b"\xEF\xBB\xBFhello\r\nworld", false),
+            PreviewContent::Text {
+                text: "hello\r\nworld".to_string(),
+                truncated: false,
+            }
+        );
+        // "é" split by the read limit.
+        assert_eq!(
+            classify(b"caf\xC3", true),
+            PreviewContent::Text {
+                text: "caf".to_string(),
+                truncated: true,
+            }
+        );
+        assert!(matches!(
+            classify(b"caf\xC3", false),
+            PreviewContent::Binary { .. }
+        ));
+        assert_eq!(
+            classify(b"MZ\x90\0", false),
+            PreviewContent::Binary {
+                hex: format!("00000000  4d 5a 90 00{}  MZ..\n", "   ".repeat(12)),
+            }
+        );
+    }
+}
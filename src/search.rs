@@ -1,14 +1,137 @@
-use ratatui::style::Color;
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::Arc;
 
-use crate::SearchItem;
+use crate::commands::parse_latest_window_token;
+use crate::{SearchItem, SearchItemKind, UNKNOWN_SIZE, UNKNOWN_TS};
 
 pub(crate) struct SearchQuery {
     expr: SearchExpr,
+    full_path: bool,
+    fold: bool,
 }
 
 enum SearchExpr {
-    Single(String),
-    Or(Vec<Vec<String>>),
+    Single(QueryClause),
+    Or(Vec<Vec<QueryClause>>),
+}
+
+struct QueryClause {
+    terms: Vec<String>,
+    excluded_terms: Vec<String>,
+    size_filters: Vec<(CompareOp, u64)>,
+    modified_filters: Vec<ModifiedFilter>,
+    ext_filters: Vec<Vec<String>>,
+    path_filters: Vec<String>,
+    /// Attribute masks from `attr:` tokens; an item needs any bit of each.
+    attr_filters: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CompareOp {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl CompareOp {
+    fn matches<T: Ord>(self, value: T, bound: T) -> bool {
+        match self {
+            Self::Lt => value < bound,
+            Self::Le => value <= bound,
+            Self::Eq => value == bound,
+            Self::Ge => value >= bound,
+            Self::Gt => value > bound,
+        }
+    }
+}
+
+/// A `modified:` token resolved to the span of time it names: one UTC day for
+/// dates and `today`, or a start point for relative windows such as `7d`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ModifiedFilter {
+    op: CompareOp,
+    start: i64,
+    end: i64,
+}
+
+impl ModifiedFilter {
+    fn matches(self, modified_unix_secs: i64) -> bool {
+        if modified_unix_secs == UNKNOWN_TS {
+            return false;
+        }
+
+        match self.op {
+            CompareOp::Lt => modified_unix_secs < self.start,
+            CompareOp::Le => modified_unix_secs < self.end,
+            CompareOp::Eq => modified_unix_secs >= self.start && modified_unix_secs < self.end,
+            CompareOp::Ge => modified_unix_secs >= self.start,
+            CompareOp::Gt => modified_unix_secs >= self.end,
+        }
+    }
+}
+
+/// The quick date chips above the results. Each keeps items modified since
+/// its cutoff, as `modified:` would, and composes with the typed query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DateFilter {
+    Today,
+    Week,
+    Month,
+    Year,
+}
+
+impl DateFilter {
+    pub(crate) const ALL: [DateFilter; 4] = [Self::Today, Self::Week, Self::Month, Self::Year];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Today => "Today",
+            Self::Week => "7 days",
+            Self::Month => "30 days",
+            Self::Year => "This year",
+        }
+    }
+
+    /// The earliest modification time kept. Days and years start at UTC
+    /// midnight, like `modified:today`.
+    pub(crate) fn cutoff(self, now: i64) -> i64 {
+        match self {
+            Self::Today => now - now.rem_euclid(86_400),
+            Self::Week => now - 7 * 86_400,
+            Self::Month => now - 30 * 86_400,
+            Self::Year => {
+                let (year, _, _) = civil_from_days(now.div_euclid(86_400));
+                days_from_civil(year, 1, 1) * 86_400
+            }
+        }
+    }
+
+    /// The chip Ctrl+D moves to: each in turn, then none.
+    pub(crate) fn next(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Self::ALL[0]),
+            Some(filter) => Self::ALL
+                .iter()
+                .position(|chip| *chip == filter)
+                .and_then(|index| Self::ALL.get(index + 1))
+                .copied(),
+        }
+    }
+}
+
+/// True when `modified_unix_secs` is known and not before `cutoff`.
+pub(crate) fn modified_since(modified_unix_secs: i64, cutoff: i64) -> bool {
+    ModifiedFilter {
+        op: CompareOp::Ge,
+        start: cutoff,
+        end: cutoff,
+    }
+    .matches(modified_unix_secs)
 }
 
 #[derive(Clone, Copy)]
@@ -22,30 +145,302 @@ impl SearchQuery {
         let query = query.trim();
         if let Some(groups) = parse_boolean_query(query) {
             Self {
-                expr: SearchExpr::Or(groups),
+                expr: SearchExpr::Or(
+                    groups
+                        .iter()
+                        .map(|terms| terms.iter().map(|term| QueryClause::parse(term)).collect())
+                        .collect(),
+                ),
+                full_path: false,
+                fold: false,
             }
         } else {
             Self {
-                expr: SearchExpr::Single(query.to_string()),
+                expr: SearchExpr::Single(QueryClause::parse(query)),
+                full_path: false,
+                fold: false,
+            }
+        }
+    }
+
+    /// Ignores diacritics on both sides, so `cafe` matches `Café.txt`.
+    pub(crate) fn folding_diacritics(mut self, fold: bool) -> Self {
+        if fold && !self.fold {
+            match &mut self.expr {
+                SearchExpr::Single(clause) => clause.fold_terms(),
+                SearchExpr::Or(groups) => groups
+                    .iter_mut()
+                    .flatten()
+                    .for_each(QueryClause::fold_terms),
             }
         }
+        self.fold = fold;
+        self
+    }
+
+    /// Matches terms against each item's whole path as well as its name,
+    /// even alongside `path:` filters.
+    pub(crate) fn matching_full_paths(mut self, full_path: bool) -> Self {
+        self.full_path = full_path;
+        self
     }
 
+    /// Files whose size was never read fail `size:` filters; see
+    /// `matches_once_sized`.
     pub(crate) fn matches_item(&self, item: &SearchItem) -> bool {
+        self.matches(item, false)
+    }
+
+    /// Whether a file without a known size matches every part of the query
+    /// except its `size:` filters, so reading its size could make it a hit.
+    pub(crate) fn matches_once_sized(&self, item: &SearchItem) -> bool {
+        item.kind == SearchItemKind::File
+            && item.size == UNKNOWN_SIZE
+            && self.has_size_filters()
+            && self.matches(item, true)
+    }
+
+    fn has_size_filters(&self) -> bool {
         match &self.expr {
-            SearchExpr::Single(query) => query_matches_item(query, item),
+            SearchExpr::Single(clause) => !clause.size_filters.is_empty(),
             SearchExpr::Or(groups) => groups
                 .iter()
-                .any(|terms| terms.iter().all(|term| query_matches_item(term, item))),
+                .flatten()
+                .any(|clause| !clause.size_filters.is_empty()),
+        }
+    }
+
+    fn matches(&self, item: &SearchItem, unknown_size_matches: bool) -> bool {
+        if self.fold {
+            if let Some(folded) = folded_item(item) {
+                return self.matches_folded_item(&folded, unknown_size_matches);
+            }
+        }
+        self.matches_folded_item(item, unknown_size_matches)
+    }
+
+    fn matches_folded_item(&self, item: &SearchItem, unknown_size_matches: bool) -> bool {
+        let full_path = self.full_path.then(|| item.full_path());
+        let full_path = full_path.as_deref();
+        match &self.expr {
+            SearchExpr::Single(clause) => {
+                clause.matches_item(item, full_path, unknown_size_matches)
+            }
+            SearchExpr::Or(groups) => groups.iter().any(|clauses| {
+                clauses
+                    .iter()
+                    .all(|clause| clause.matches_item(item, full_path, unknown_size_matches))
+            }),
+        }
+    }
+
+    /// The single plain term of a query without filters, which is what
+    /// relevance ranking scores against file names.
+    pub(crate) fn fuzzy_term(&self) -> Option<&str> {
+        let SearchExpr::Single(clause) = &self.expr else {
+            return None;
+        };
+        if clause.terms.len() != 1
+            || !clause.excluded_terms.is_empty()
+            || !clause.size_filters.is_empty()
+            || !clause.modified_filters.is_empty()
+            || !clause.ext_filters.is_empty()
+            || !clause.path_filters.is_empty()
+            || !clause.attr_filters.is_empty()
+        {
+            return None;
         }
+
+        let term = clause.terms[0].as_str();
+        (!term.contains(['*', '?', '\\', '/'])).then_some(term)
     }
 
-    pub(crate) fn boolean_groups(&self) -> Option<&[Vec<String>]> {
+    pub(crate) fn boolean_groups(&self) -> Option<Vec<Vec<&str>>> {
         match &self.expr {
             SearchExpr::Single(_) => None,
-            SearchExpr::Or(groups) => Some(groups),
+            SearchExpr::Or(groups) => Some(
+                groups
+                    .iter()
+                    .map(|clauses| {
+                        clauses
+                            .iter()
+                            .flat_map(|clause| clause.terms.iter().map(String::as_str))
+                            .collect()
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum QueryTerm {
+    Include(String),
+    Exclude(String),
+    Phrase(String),
+}
+
+impl QueryClause {
+    fn parse(clause: &str) -> Self {
+        let now = unix_now();
+        let mut size_filters = Vec::new();
+        let mut modified_filters = Vec::new();
+        let mut terms = Vec::new();
+        let mut excluded_terms = Vec::new();
+        let mut ext_filters = Vec::new();
+        let mut path_filters = Vec::new();
+        let mut attr_filters = Vec::new();
+        for term in tokenize_query(clause) {
+            match term {
+                QueryTerm::Include(word) => {
+                    if let Some(filter) = parse_size_filter(&word) {
+                        size_filters.push(filter);
+                    } else if let Some(filter) = parse_modified_filter(&word, now) {
+                        modified_filters.push(filter);
+                    } else if let Some(extensions) = parse_ext_filter(&word) {
+                        ext_filters.push(extensions);
+                    } else if let Some(folder) = parse_path_filter(&word) {
+                        path_filters.push(folder);
+                    } else if let Some(mask) = parse_attr_filter(&word) {
+                        attr_filters.push(mask);
+                    } else {
+                        terms.push(word);
+                    }
+                }
+                QueryTerm::Phrase(phrase) => terms.push(phrase),
+                QueryTerm::Exclude(excluded) => excluded_terms.push(excluded),
+            }
+        }
+
+        Self {
+            terms,
+            excluded_terms,
+            size_filters,
+            modified_filters,
+            ext_filters,
+            path_filters,
+            attr_filters,
+        }
+    }
+
+    fn fold_terms(&mut self) {
+        for term in self
+            .terms
+            .iter_mut()
+            .chain(&mut self.excluded_terms)
+            .chain(&mut self.path_filters)
+        {
+            if let Cow::Owned(folded) = fold_ascii(term) {
+                *term = folded;
+            }
+        }
+    }
+
+    fn matches_item(
+        &self,
+        item: &SearchItem,
+        full_path: Option<&str>,
+        unknown_size_matches: bool,
+    ) -> bool {
+        let term_matches = |term: &String| {
+            if let Some(full_path) = full_path {
+                term_matches_text(term, &item.name) || term_matches_text(term, full_path)
+            } else if self.path_filters.is_empty() {
+                query_matches_item(term, item)
+            } else {
+                term_matches_text(term, &item.name)
+            }
+        };
+        if !self.terms.iter().all(term_matches) || self.excluded_terms.iter().any(term_matches) {
+            return false;
+        }
+
+        if !self
+            .path_filters
+            .iter()
+            .all(|wanted| contains_case_insensitive(&item.folder, wanted))
+        {
+            return false;
+        }
+
+        if !self.ext_filters.is_empty() {
+            let ext = file_extension(&item.name);
+            if !self.ext_filters.iter().all(|extensions| {
+                extensions
+                    .iter()
+                    .any(|wanted| wanted.eq_ignore_ascii_case(ext) || *wanted == ext.to_lowercase())
+            }) {
+                return false;
+            }
+        }
+
+        if !self.attr_filters.iter().all(|mask| item.attrs & mask != 0) {
+            return false;
+        }
+
+        if !self
+            .modified_filters
+            .iter()
+            .all(|filter| filter.matches(item.modified_unix_secs))
+        {
+            return false;
+        }
+
+        if self.size_filters.is_empty() {
+            return true;
+        }
+
+        if item.kind != SearchItemKind::File {
+            return false;
         }
+        if item.size == UNKNOWN_SIZE {
+            return unknown_size_matches;
+        }
+        self.size_filters
+            .iter()
+            .all(|(op, bound)| op.matches(item.size, *bound))
+    }
+}
+
+pub(crate) fn tokenize_query(query: &str) -> Vec<QueryTerm> {
+    let mut terms = Vec::new();
+    let mut rest = query.trim_start();
+
+    while !rest.is_empty() {
+        let quoted = if let Some(body) = rest.strip_prefix("-\"") {
+            Some((true, body))
+        } else {
+            rest.strip_prefix('"').map(|body| (false, body))
+        };
+
+        if let Some((excluded, body)) = quoted {
+            let end = body.find('"').unwrap_or(body.len());
+            let phrase = body[..end].to_string();
+            rest = body[end..].strip_prefix('"').unwrap_or(&body[end..]);
+            if !phrase.is_empty() {
+                terms.push(if excluded {
+                    QueryTerm::Exclude(phrase)
+                } else {
+                    QueryTerm::Phrase(phrase)
+                });
+            }
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let word = &rest[..end];
+            rest = &rest[end..];
+            terms.push(if let Some(escaped) = word.strip_prefix("\\-") {
+                QueryTerm::Include(format!("-{}", escaped))
+            } else if let Some(excluded) = word.strip_prefix('-').filter(|w| !w.is_empty()) {
+                QueryTerm::Exclude(excluded.to_string())
+            } else {
+                QueryTerm::Include(word.to_string())
+            });
+        }
+
+        rest = rest.trim_start();
     }
+
+    terms
 }
 
 pub(crate) fn query_uses_boolean_logic(query: &str) -> bool {
@@ -61,9 +456,12 @@ pub(crate) fn query_has_incomplete_boolean_logic(query: &str) -> bool {
     let mut saw_operator = false;
     let mut expecting_term = false;
     let mut saw_term = false;
+    let mut in_quote = false;
 
     for word in query.split_whitespace() {
-        if parse_query_operator(word).is_some() {
+        let quoted = in_quote;
+        in_quote ^= word.matches('"').count() % 2 == 1;
+        if !quoted && parse_query_operator(word).is_some() {
             saw_operator = true;
             if !saw_term || expecting_term {
                 return true;
@@ -79,14 +477,224 @@ pub(crate) fn query_has_incomplete_boolean_logic(query: &str) -> bool {
 }
 
 pub(crate) fn query_matches_item(query: &str, item: &SearchItem) -> bool {
-    let name = file_name_from_path(item.path.as_ref());
-    if query.contains('*') || query.contains('?') {
-        wildcard_match_ascii_insensitive(query, name)
-            || wildcard_match_ascii_insensitive(query, item.path.as_ref())
+    if term_matches_text(query, &item.name) {
+        return true;
+    }
+
+    // A plain term can only straddle the folder/name boundary through a separator.
+    if query.contains(['*', '?', '\\', '/']) {
+        term_matches_text(query, &item.full_path())
+    } else {
+        contains_case_insensitive(&item.folder, query)
+    }
+}
+
+fn term_matches_text(term: &str, text: &str) -> bool {
+    if term.contains('*') || term.contains('?') {
+        glob_matches(term, text)
+    } else {
+        contains_case_insensitive(text, term)
+    }
+}
+
+fn parse_path_filter(token: &str) -> Option<String> {
+    let prefix = token.get(..5)?;
+    if !prefix.eq_ignore_ascii_case("path:") || token.len() == 5 {
+        return None;
+    }
+
+    Some(token[5..].replace('/', "\\").to_lowercase())
+}
+
+fn parse_size_filter(token: &str) -> Option<(CompareOp, u64)> {
+    let prefix = token.get(..5)?;
+    if !prefix.eq_ignore_ascii_case("size:") {
+        return None;
+    }
+
+    let (op, value) = parse_compare_prefix(&token[5..]);
+    Some((op, parse_size_value(value)?))
+}
+
+fn parse_modified_filter(token: &str, now: i64) -> Option<ModifiedFilter> {
+    let prefix = token.get(..9)?;
+    if !prefix.eq_ignore_ascii_case("modified:") {
+        return None;
+    }
+
+    let (op, value) = parse_compare_prefix(&token[9..]);
+    let value = value.trim().to_ascii_lowercase();
+    if value == "today" {
+        let start = now - now.rem_euclid(86_400);
+        return Some(ModifiedFilter {
+            op,
+            start,
+            end: start + 86_400,
+        });
+    }
+    if let Some(day) = parse_date(&value) {
+        return Some(ModifiedFilter {
+            op,
+            start: day,
+            end: day + 86_400,
+        });
+    }
+
+    // A bare window such as `7d` means "within the last 7 days".
+    let start = now - parse_latest_window_token(&value)?;
+    Some(ModifiedFilter {
+        op: if op == CompareOp::Eq {
+            CompareOp::Ge
+        } else {
+            op
+        },
+        start,
+        end: start,
+    })
+}
+
+/// Parses `YYYY-MM-DD` into the Unix time of that day's UTC midnight.
+fn parse_date(value: &str) -> Option<i64> {
+    let mut parts = value.splitn(3, '-');
+    let year = parts.next()?.parse::<i64>().ok()?;
+    let month = parts.next()?.parse::<u32>().ok()?;
+    let day = parts.next()?.parse::<u32>().ok()?;
+    let days = days_from_civil(year, month, day);
+    (civil_from_days(days) == (year, month, day)).then_some(days * 86_400)
+}
+
+pub(crate) fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+// Proleptic Gregorian day arithmetic, after Howard Hinnant's date algorithms.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let (month, day) = (month as i64, day as i64);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Formats a modification time as `YYYY-MM-DD HH:MM` in UTC.
+pub(crate) fn format_modified(modified_unix_secs: i64) -> String {
+    if modified_unix_secs == UNKNOWN_TS {
+        return "unknown".to_string();
+    }
+
+    let (year, month, day) = civil_from_days(modified_unix_secs.div_euclid(86_400));
+    let secs_of_day = modified_unix_secs.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60
+    )
+}
+
+fn parse_ext_filter(token: &str) -> Option<Vec<String>> {
+    let prefix = token.get(..4)?;
+    if !prefix.eq_ignore_ascii_case("ext:") {
+        return None;
+    }
+
+    Some(
+        token[4..]
+            .split(',')
+            .map(|ext| ext.trim_start_matches('.').to_lowercase())
+            .collect(),
+    )
+}
+
+/// Names accepted by `attr:`, with their Windows file attribute bits.
+const ATTRIBUTE_NAMES: [(&str, u32); 6] = [
+    ("readonly", 0x1),
+    ("hidden", 0x2),
+    ("system", 0x4),
+    ("archive", 0x20),
+    ("compressed", 0x800),
+    ("encrypted", 0x4000),
+];
+
+/// Parses `attr:hidden` or `attr:hidden,system` into a mask of the named
+/// bits. Unknown names leave the token as a plain search term.
+fn parse_attr_filter(token: &str) -> Option<u32> {
+    let prefix = token.get(..5)?;
+    if !prefix.eq_ignore_ascii_case("attr:") {
+        return None;
+    }
+
+    token[5..].split(',').try_fold(0, |mask, name| {
+        ATTRIBUTE_NAMES
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name))
+            .map(|(_, bit)| mask | bit)
+    })
+}
+
+fn file_extension(name: &str) -> &str {
+    match name.rfind('.') {
+        Some(dot) if dot > 0 => &name[dot + 1..],
+        _ => "",
+    }
+}
+
+fn parse_compare_prefix(value: &str) -> (CompareOp, &str) {
+    if let Some(rest) = value.strip_prefix(">=") {
+        (CompareOp::Ge, rest)
+    } else if let Some(rest) = value.strip_prefix("<=") {
+        (CompareOp::Le, rest)
+    } else if let Some(rest) = value.strip_prefix('>') {
+        (CompareOp::Gt, rest)
+    } else if let Some(rest) = value.strip_prefix('<') {
+        (CompareOp::Lt, rest)
+    } else if let Some(rest) = value.strip_prefix('=') {
+        (CompareOp::Eq, rest)
     } else {
-        contains_ascii_case_insensitive(name, query)
-            || contains_ascii_case_insensitive(item.path.as_ref(), query)
+        (CompareOp::Eq, value)
+    }
+}
+
+fn parse_size_value(value: &str) -> Option<u64> {
+    let value = value.trim().to_ascii_lowercase();
+    let split_at = value
+        .find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+        .unwrap_or(value.len());
+    if split_at == 0 {
+        return None;
     }
+
+    let amount = value[..split_at].parse::<f64>().ok()?;
+    let factor = match &value[split_at..] {
+        "" | "b" => 1u64,
+        "k" | "kb" => 1024,
+        "m" | "mb" => 1024 * 1024,
+        "g" | "gb" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+
+    Some((amount * factor as f64) as u64)
 }
 
 fn parse_boolean_query(query: &str) -> Option<Vec<Vec<String>>> {
@@ -97,9 +705,12 @@ fn parse_boolean_query(query: &str) -> Option<Vec<Vec<String>>> {
     let mut terms = Vec::new();
     let mut operators = Vec::new();
     let mut term_words = Vec::new();
+    let mut in_quote = false;
 
     for word in query.split_whitespace() {
-        if let Some(op) = parse_query_operator(word) {
+        let quoted = in_quote;
+        in_quote ^= word.matches('"').count() % 2 == 1;
+        if let Some(op) = parse_query_operator(word).filter(|_| !quoted) {
             if term_words.is_empty() {
                 return None;
             }
@@ -140,6 +751,211 @@ fn parse_query_operator(word: &str) -> Option<QueryOp> {
     }
 }
 
+pub(crate) fn contains_case_insensitive(haystack: &str, needle_lower: &str) -> bool {
+    if needle_lower.is_ascii() {
+        contains_ascii_case_insensitive(haystack, needle_lower)
+    } else {
+        haystack.to_lowercase().contains(needle_lower)
+    }
+}
+
+/// Strips diacritics from Latin letters: `Crème Brûlée` becomes `Creme Brulee`.
+/// Combining marks (U+0300..U+036F) are dropped and the accented letters of
+/// Latin-1 and Latin Extended-A map to their base letter. This is a table
+/// rather than NFD on purpose: it also folds letters NFD keeps whole, such as
+/// `Đ`, `Ħ`, `ı`, `Ŀ`, `Ł`, `Ø` and `Ŧ`, and it leaves other scripts alone, where
+/// NFD would fold Cyrillic `й` into `и`. Ligatures like `Æ`, `Œ` and `ß` stay.
+pub(crate) fn fold_ascii(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if ('\u{300}'..='\u{36f}').contains(&ch) {
+            continue;
+        }
+        match fold_char(ch) {
+            Some(base) => out.push_str(base),
+            None => out.push(ch),
+        }
+    }
+    Cow::Owned(out)
+}
+
+fn fold_char(ch: char) -> Option<&'static str> {
+    const LATIN_1: [&str; 64] = [
+        "A", "A", "A", "A", "A", "A", "", "C", "E", "E", "E", "E", "I", "I", "I", "I", //
+        "", "N", "O", "O", "O", "O", "O", "", "O", "U", "U", "U", "U", "Y", "", "", //
+        "a", "a", "a", "a", "a", "a", "", "c", "e", "e", "e", "e", "i", "i", "i", "i", //
+        "", "n", "o", "o", "o", "o", "o", "", "o", "u", "u", "u", "u", "y", "", "y",
+    ];
+    // U+0100..U+017F, one base letter per code point.
+    const LATIN_EXTENDED_A: &str = "AaAaAaCcCcCcCcDdDdEeEeEeEeEeGgGgGgGgHhHhIiIiIiIiIi\
+        \0\0JjKk\0LlLlLlLlLlNnNnNn\0\0\0OoOoOo\0\0RrRrRrSsSsSsSsTtTtTtUuUuUuUuUuUuWwYyYZzZzZz\0";
+
+    let code = ch as u32;
+    let base = match code {
+        0xc0..=0xff => LATIN_1[(code - 0xc0) as usize],
+        0x100..=0x17f => {
+            let offset = (code - 0x100) as usize;
+            LATIN_EXTENDED_A.get(offset..offset + 1).unwrap_or("\0")
+        }
+        _ => return None,
+    };
+    (!base.is_empty() && base != "\0").then_some(base)
+}
+
+pub(crate) fn fold_if(text: &str, fold: bool) -> Cow<'_, str> {
+    if fold {
+        fold_ascii(text)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// The key a file name is filed under in the filename index.
+pub(crate) fn filename_key(name: &str, fold: bool) -> String {
+    let name_lower = name.to_lowercase();
+    fold_if(&name_lower, fold).into_owned()
+}
+
+/// A copy of `item` with its name and folder folded, or `None` when folding
+/// would change nothing.
+fn folded_item(item: &SearchItem) -> Option<SearchItem> {
+    if item.name.is_ascii() && item.folder.is_ascii() {
+        return None;
+    }
+    Some(SearchItem {
+        folder: fold_ascii(&item.folder).into(),
+        name: fold_ascii(&item.name).into(),
+        ..item.clone()
+    })
+}
+
+pub(crate) fn filename_prefix_keys(name_lower: &str) -> Vec<String> {
+    let mut keys = Vec::with_capacity(3);
+    let mut prefix = String::new();
+    for ch in name_lower.chars().take(3) {
+        prefix.push(ch);
+        keys.push(prefix.clone());
+    }
+    keys
+}
+
+/// Files `index` under the exact and prefix keys for `name`, folded with
+/// `fold_ascii` when `fold` is set.
+pub(crate) fn index_filename(
+    exact: &mut HashMap<String, Vec<usize>>,
+    prefixes: &mut HashMap<String, Vec<usize>>,
+    name: &str,
+    index: usize,
+    fold: bool,
+) {
+    let name_lower = filename_key(name, fold);
+    for prefix in filename_prefix_keys(&name_lower) {
+        prefixes.entry(prefix).or_default().push(index);
+    }
+    exact.entry(name_lower).or_default().push(index);
+}
+
+/// Undoes `index_filename`, dropping keys that no longer hold any index.
+pub(crate) fn unindex_filename(
+    exact: &mut HashMap<String, Vec<usize>>,
+    prefixes: &mut HashMap<String, Vec<usize>>,
+    name: &str,
+    index: usize,
+    fold: bool,
+) {
+    let name_lower = filename_key(name, fold);
+    for prefix in filename_prefix_keys(&name_lower) {
+        remove_index(prefixes, &prefix, index);
+    }
+    remove_index(exact, &name_lower, index);
+}
+
+/// Updates the filename index after `remove_item` took the item named
+/// `removed` out of `slot`, refiling the item that moved there from the end.
+pub(crate) fn unindex_swap_removed(
+    exact: &mut HashMap<String, Vec<usize>>,
+    prefixes: &mut HashMap<String, Vec<usize>>,
+    items: &[SearchItem],
+    removed: &str,
+    slot: usize,
+    fold: bool,
+) {
+    unindex_filename(exact, prefixes, removed, slot, fold);
+    if let Some(moved) = items.get(slot) {
+        unindex_filename(exact, prefixes, &moved.name, items.len(), fold);
+        index_filename(exact, prefixes, &moved.name, slot, fold);
+    }
+}
+
+fn remove_index(map: &mut HashMap<String, Vec<usize>>, key: &str, index: usize) {
+    let Some(indices) = map.get_mut(key) else {
+        return;
+    };
+    if let Some(position) = indices.iter().position(|&candidate| candidate == index) {
+        indices.swap_remove(position);
+    }
+    if indices.is_empty() {
+        map.remove(key);
+    }
+}
+
+/// Whether the filename index alone can answer `query_lower`: a single plain
+/// term, under a sort other than relevance. The index only finds substrings,
+/// so relevance, which also ranks subsequence and initials matches such as
+/// `rsm` for `RustSearchMain.rs`, is left to the search worker.
+pub(crate) fn filename_index_answers(query_lower: &str, sort_mode: SortMode) -> bool {
+    sort_mode != SortMode::Relevance
+        && !query_lower.is_empty()
+        && !query_lower.contains(['*', '?', '\\', '/', ':', '"'])
+        && !query_lower.contains(char::is_whitespace)
+        && !query_lower.starts_with('-')
+        && !query_uses_boolean_logic(query_lower)
+}
+
+pub(crate) fn query_prefix_key(query_lower: &str) -> String {
+    query_lower.chars().take(3).collect()
+}
+
+/// Positions of the items whose name contains `query_lower`, read from the
+/// filename index: exact names first, then prefix candidates, each listed
+/// once. `fold` must match how the index was built.
+pub(crate) fn filename_index_lookup(
+    exact: &HashMap<String, Vec<usize>>,
+    prefixes: &HashMap<String, Vec<usize>>,
+    items: &[SearchItem],
+    query_lower: &str,
+    fold: bool,
+) -> Vec<usize> {
+    let mut out = Vec::new();
+    let mut seen: HashSet<usize> = HashSet::new();
+
+    if let Some(exact) = exact.get(query_lower) {
+        for &idx in exact {
+            if seen.insert(idx) {
+                out.push(idx);
+            }
+        }
+    }
+
+    if let Some(candidates) = prefixes.get(&query_prefix_key(query_lower)) {
+        for &idx in candidates {
+            if seen.contains(&idx) {
+                continue;
+            }
+            if contains_case_insensitive(&fold_if(&items[idx].name, fold), query_lower) {
+                seen.insert(idx);
+                out.push(idx);
+            }
+        }
+    }
+
+    out
+}
+
 pub(crate) fn contains_ascii_case_insensitive(haystack: &str, needle_lower_ascii: &str) -> bool {
     if needle_lower_ascii.is_empty() {
         return true;
@@ -177,19 +993,34 @@ pub(crate) fn contains_ascii_case_insensitive(haystack: &str, needle_lower_ascii
     false
 }
 
-fn wildcard_match_ascii_insensitive(pattern_lower_ascii: &str, text: &str) -> bool {
-    let p = pattern_lower_ascii.as_bytes();
-    let t = text.as_bytes();
+pub(crate) fn glob_matches(pattern: &str, name: &str) -> bool {
+    if pattern.is_ascii() && name.is_ascii() {
+        return glob_match_units(pattern.as_bytes(), name.as_bytes(), b'*', b'?', |ch| {
+            ch.to_ascii_lowercase()
+        });
+    }
+
+    let p: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    let t: Vec<char> = name.chars().flat_map(char::to_lowercase).collect();
+    glob_match_units(&p, &t, '*', '?', |ch| ch)
+}
 
+fn glob_match_units<T: Copy + PartialEq>(
+    p: &[T],
+    t: &[T],
+    any_run: T,
+    any_one: T,
+    fold: impl Fn(T) -> T,
+) -> bool {
     let (mut pi, mut ti) = (0usize, 0usize);
     let mut star_pi: Option<usize> = None;
     let mut star_ti = 0usize;
 
     while ti < t.len() {
-        if pi < p.len() && (p[pi] == t[ti].to_ascii_lowercase() || p[pi] == b'?') {
+        if pi < p.len() && (p[pi] == any_one || fold(p[pi]) == fold(t[ti])) {
             pi += 1;
             ti += 1;
-        } else if pi < p.len() && p[pi] == b'*' {
+        } else if pi < p.len() && p[pi] == any_run {
             star_pi = Some(pi);
             pi += 1;
             star_ti = ti;
@@ -202,13 +1033,343 @@ fn wildcard_match_ascii_insensitive(pattern_lower_ascii: &str, text: &str) -> bo
         }
     }
 
-    while pi < p.len() && p[pi] == b'*' {
+    while pi < p.len() && p[pi] == any_run {
         pi += 1;
     }
 
     pi == p.len()
 }
 
+const FUZZY_MATCH: i64 = 16;
+const FUZZY_CONSECUTIVE_BONUS: i64 = 8;
+const FUZZY_WORD_START_BONUS: i64 = 10;
+const FUZZY_PREFIX_BONUS: i64 = 12;
+const FUZZY_GAP_PENALTY: i64 = 2;
+const ACRONYM_BONUS: i64 = 8;
+
+pub(crate) struct RankedHit {
+    pub(crate) item: SearchItem,
+    pub(crate) score: i64,
+}
+
+/// Orders hits best-first, keeping encounter order between equal scores.
+/// With `per_folder`, only that many of the best hits from any one folder
+/// are kept.
+pub(crate) fn rank_hits(hits: &mut Vec<RankedHit>, limit: usize, per_folder: Option<usize>) {
+    hits.sort_by_key(|hit| std::cmp::Reverse(hit.score));
+    if let Some(per_folder) = per_folder {
+        let mut kept: HashMap<Arc<str>, usize> = HashMap::new();
+        hits.retain(|hit| {
+            let count = kept.entry(hit.item.folder.clone()).or_insert(0);
+            *count += 1;
+            *count <= per_folder
+        });
+    }
+    hits.truncate(limit);
+}
+
+/// Scores `name` as an fzf-style subsequence match of `query_lower`, or
+/// returns `None` when the query characters do not all appear in order.
+/// Contiguous runs, matches at word starts, a match at the very start, and
+/// shorter names all score higher.
+pub(crate) fn fuzzy_score(query_lower: &str, name: &str) -> Option<i64> {
+    let query: Vec<char> = query_lower.chars().collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let original: Vec<char> = name.chars().collect();
+    let text: Vec<char> = original
+        .iter()
+        .map(|ch| ch.to_lowercase().next().unwrap_or(*ch))
+        .collect();
+
+    // The forward pass is the cheap rejection; only real matches get scored.
+    let mut qi = 0usize;
+    let mut end = 0usize;
+    for (ti, ch) in text.iter().enumerate() {
+        if *ch == query[qi] {
+            qi += 1;
+            if qi == query.len() {
+                end = ti;
+                break;
+            }
+        }
+    }
+    if qi < query.len() {
+        return None;
+    }
+
+    // Walking back from the earliest end finds the tightest window.
+    let mut positions = vec![0usize; query.len()];
+    let mut qi = query.len();
+    let mut ti = end + 1;
+    while qi > 0 {
+        ti -= 1;
+        if text[ti] == query[qi - 1] {
+            qi -= 1;
+            positions[qi] = ti;
+        }
+    }
+
+    let mut score = 0i64;
+    for (index, &pos) in positions.iter().enumerate() {
+        score += FUZZY_MATCH;
+        if pos == 0 {
+            score += FUZZY_PREFIX_BONUS;
+        }
+        if is_word_start(&original, pos) {
+            score += FUZZY_WORD_START_BONUS;
+        }
+        if index > 0 {
+            let gap = pos - positions[index - 1] - 1;
+            if gap == 0 {
+                score += FUZZY_CONSECUTIVE_BONUS;
+            } else {
+                score -= FUZZY_GAP_PENALTY * gap as i64;
+            }
+        }
+    }
+
+    Some(score - text.len() as i64 / 4)
+}
+
+/// Matches the query against word initials only, so `rsm` finds
+/// `RustSearchMain.rs` and `ghp` finds `get_http_parser.rs`. Words start
+/// after a separator, at a camelCase hump, or where digits begin.
+pub(crate) fn acronym_matches(query_lower: &str, name: &str) -> Option<i64> {
+    let mut query = query_lower.chars().peekable();
+    if query_lower.chars().count() < 2 {
+        return None;
+    }
+
+    let chars: Vec<char> = name.chars().collect();
+    let mut score = 0i64;
+    for pos in 0..chars.len() {
+        let Some(&wanted) = query.peek() else {
+            break;
+        };
+        let ch = chars[pos].to_lowercase().next().unwrap_or(chars[pos]);
+        if ch != wanted || !is_word_start(&chars, pos) {
+            continue;
+        }
+
+        query.next();
+        score += FUZZY_MATCH + FUZZY_WORD_START_BONUS + ACRONYM_BONUS;
+        if pos == 0 {
+            score += FUZZY_PREFIX_BONUS;
+        }
+    }
+    if query.peek().is_some() {
+        return None;
+    }
+
+    Some(score - chars.len() as i64 / 4)
+}
+
+/// The relevance score for a name: the better of the fuzzy and acronym paths.
+pub(crate) fn relevance_score(query_lower: &str, name: &str) -> Option<i64> {
+    if !is_subsequence(query_lower, name) {
+        return None;
+    }
+    fuzzy_score(query_lower, name).max(acronym_matches(query_lower, name))
+}
+
+/// Allocation-free check that both scorers need to pass, so most of a large
+/// corpus is rejected before any scoring work.
+fn is_subsequence(query_lower: &str, name: &str) -> bool {
+    let mut query = query_lower.chars().peekable();
+    for ch in name.chars().flat_map(char::to_lowercase) {
+        match query.peek() {
+            Some(&wanted) if wanted == ch => {
+                query.next();
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+    query.peek().is_none()
+}
+
+fn is_word_start(chars: &[char], pos: usize) -> bool {
+    let Some(&prev) = pos.checked_sub(1).and_then(|prev| chars.get(prev)) else {
+        return true;
+    };
+    let ch = chars[pos];
+    !prev.is_alphanumeric()
+        || (prev.is_lowercase() && ch.is_uppercase())
+        || (!prev.is_numeric() && ch.is_numeric())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SortMode {
+    Relevance,
+    Name,
+    Path,
+    Date,
+    Size,
+}
+
+impl SortMode {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Relevance => "relevance",
+            Self::Name => "name",
+            Self::Path => "path",
+            Self::Date => "date",
+            Self::Size => "size",
+        }
+    }
+
+    pub(crate) fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "relevance" => Some(Self::Relevance),
+            "name" => Some(Self::Name),
+            "path" => Some(Self::Path),
+            "date" => Some(Self::Date),
+            "size" => Some(Self::Size),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Self::Relevance => Self::Name,
+            Self::Name => Self::Path,
+            Self::Path => Self::Date,
+            Self::Date => Self::Size,
+            Self::Size => Self::Relevance,
+        }
+    }
+
+    pub(crate) fn default_descending(self) -> bool {
+        matches!(self, Self::Date | Self::Size)
+    }
+}
+
+/// Orders two items by a non-relevance sort mode, breaking ties by path.
+/// Unknown sizes sort last either way.
+pub(crate) fn cmp_items(
+    a: &SearchItem,
+    b: &SearchItem,
+    mode: SortMode,
+    descending: bool,
+) -> std::cmp::Ordering {
+    let directed = |ordering: std::cmp::Ordering| {
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    };
+
+    match mode {
+        SortMode::Relevance => std::cmp::Ordering::Equal,
+        SortMode::Name => {
+            directed(cmp_ascii_case_insensitive(&a.name, &b.name)).then_with(|| cmp_paths(a, b))
+        }
+        SortMode::Path => directed(cmp_paths(a, b)),
+        SortMode::Date => {
+            directed(a.modified_unix_secs.cmp(&b.modified_unix_secs)).then_with(|| cmp_paths(a, b))
+        }
+        SortMode::Size => (a.size == UNKNOWN_SIZE)
+            .cmp(&(b.size == UNKNOWN_SIZE))
+            .then_with(|| directed(a.size.cmp(&b.size)))
+            .then_with(|| cmp_paths(a, b)),
+    }
+}
+
+pub(crate) fn sort_items(items: &mut [SearchItem], mode: SortMode, descending: bool) {
+    if mode != SortMode::Relevance {
+        items.sort_by(|a, b| cmp_items(a, b, mode, descending));
+    }
+}
+
+/// Keeps the `limit` entries that sort first, unordered, so a sorted view
+/// shows the real top rows rather than whichever were found first. Under
+/// relevance the first `limit` are kept as found.
+pub(crate) fn select_first_sorted<'a, T>(
+    matches: &mut Vec<T>,
+    limit: usize,
+    mode: SortMode,
+    descending: bool,
+    item: impl Fn(&T) -> &'a SearchItem,
+) {
+    if mode != SortMode::Relevance && limit > 0 && matches.len() > limit {
+        matches.select_nth_unstable_by(limit - 1, |a, b| {
+            cmp_items(item(a), item(b), mode, descending)
+        });
+    }
+    matches.truncate(limit);
+}
+
+/// The path the live journal last reported for an NTFS file, keyed by
+/// volume and file reference number.
+pub(crate) type LatestLinks = HashMap<(Box<str>, u64), Box<str>>;
+
+/// The volume part of a folder: `C:` or `\\?\Volume{GUID}`.
+pub(crate) fn volume_of(folder: &str) -> &str {
+    let start = if folder.starts_with("\\\\?\\") { 4 } else { 0 };
+    let end = folder[start..]
+        .find('\\')
+        .map_or(folder.len(), |offset| start + offset);
+    &folder[..end]
+}
+
+/// Drops repeats of a path already listed. With `latest_links`, hard links
+/// to one NTFS file collapse into a single row. The MFT scan names each file
+/// once, so extra links only come from the live journal, and the row shows
+/// the name it reported last, else the first one listed.
+pub(crate) fn dedup_items(items: &mut Vec<SearchItem>, latest_links: Option<&LatestLinks>) {
+    let mut paths = HashSet::with_capacity(items.len());
+    let mut files: HashMap<(&str, u64), usize> = HashMap::new();
+    // The item each kept row shows, by position in `items`.
+    let mut shown: Vec<usize> = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        if !paths.insert((item.folder.as_ref(), item.name.as_ref())) {
+            continue;
+        }
+        if let Some(latest_links) = latest_links.filter(|_| item.file_id != 0) {
+            // File reference numbers are only unique within a volume.
+            let volume = volume_of(&item.folder);
+            match files.entry((volume, item.file_id)) {
+                Entry::Occupied(row) => {
+                    let latest = latest_links.get(&(Box::from(volume), item.file_id));
+                    if latest.is_some_and(|path| **path == item.full_path()) {
+                        shown[*row.get()] = index;
+                    }
+                    continue;
+                }
+                Entry::Vacant(row) => {
+                    row.insert(shown.len());
+                }
+            }
+        }
+        shown.push(index);
+    }
+    if shown.len() == items.len() {
+        return;
+    }
+
+    let mut taken: Vec<Option<SearchItem>> = items.drain(..).map(Some).collect();
+    items.extend(shown.into_iter().filter_map(|index| taken[index].take()));
+}
+
+fn cmp_paths(a: &SearchItem, b: &SearchItem) -> std::cmp::Ordering {
+    let a_bytes = || a.folder.bytes().chain(a.name.bytes());
+    let b_bytes = || b.folder.bytes().chain(b.name.bytes());
+    a_bytes()
+        .map(|ch| ch.to_ascii_lowercase())
+        .cmp(b_bytes().map(|ch| ch.to_ascii_lowercase()))
+        .then_with(|| a_bytes().cmp(b_bytes()))
+}
+
+fn cmp_ascii_case_insensitive(a: &str, b: &str) -> std::cmp::Ordering {
+    a.bytes()
+        .map(|ch| ch.to_ascii_lowercase())
+        .cmp(b.bytes().map(|ch| ch.to_ascii_lowercase()))
+}
+
 pub(crate) fn truncate_middle(input: &str, max_chars: usize) -> String {
     let chars: Vec<char> = input.chars().collect();
     if chars.len() <= max_chars {
@@ -228,31 +1389,90 @@ pub(crate) fn truncate_middle(input: &str, max_chars: usize) -> String {
     format!("{}...{}", start, end)
 }
 
-pub(crate) fn file_type_color(name: &str) -> Color {
-    let lower = name.to_ascii_lowercase();
-    if lower.ends_with(".rs") {
-        Color::Rgb(255, 153, 85)
-    } else if lower.ends_with(".ts") || lower.ends_with(".tsx") {
-        Color::Rgb(99, 179, 237)
-    } else if lower.ends_with(".js") || lower.ends_with(".jsx") {
-        Color::Rgb(246, 224, 94)
-    } else if lower.ends_with(".json") {
-        Color::Rgb(104, 211, 145)
-    } else if lower.ends_with(".md") {
-        Color::Rgb(180, 178, 255)
-    } else {
-        Color::Rgb(220, 220, 220)
+pub(crate) fn file_name_from_path(path: &str) -> &str {
+    path.rsplit(['\\', '/']).next().unwrap_or(path)
+}
+
+/// Literal terms from every OR group; wildcard and filter terms are skipped.
+pub(crate) fn highlight_terms(query: &str) -> Vec<String> {
+    let clauses: Vec<QueryClause> = match SearchQuery::parse(&query.to_lowercase()).expr {
+        SearchExpr::Single(clause) => vec![clause],
+        SearchExpr::Or(groups) => groups.into_iter().flatten().collect(),
+    };
+
+    clauses
+        .into_iter()
+        .flat_map(|clause| clause.terms)
+        .filter(|term| !term.contains(['*', '?']))
+        .collect()
+}
+
+/// Byte ranges of `text` matched by any of `terms`, sorted and merged.
+pub(crate) fn highlight_ranges(text: &str, terms: &[String]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    for term in terms.iter().filter(|term| !term.is_empty()) {
+        let mut start = 0;
+        while start < text.len() {
+            match match_len_at(&text[start..], term) {
+                Some(len) => {
+                    ranges.push(start..start + len);
+                    start += len;
+                }
+                None => start += text[start..].chars().next().map_or(1, char::len_utf8),
+            }
+        }
+    }
+
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
     }
+    merged
 }
 
-pub(crate) fn file_name_from_path(path: &str) -> &str {
-    path.rsplit(['\\', '/']).next().unwrap_or(path)
+/// Splits `text` into `(segment, highlighted)` pieces that cover it in order.
+pub(crate) fn highlight_segments<'a>(
+    text: &'a str,
+    ranges: &[Range<usize>],
+) -> Vec<(&'a str, bool)> {
+    let mut segments = Vec::with_capacity(ranges.len() * 2 + 1);
+    let mut cursor = 0;
+    for range in ranges {
+        if range.start > cursor {
+            segments.push((&text[cursor..range.start], false));
+        }
+        segments.push((&text[range.clone()], true));
+        cursor = range.end;
+    }
+    if cursor < text.len() {
+        segments.push((&text[cursor..], false));
+    }
+    segments
+}
+
+fn match_len_at(text: &str, needle_lower: &str) -> Option<usize> {
+    let mut needle = needle_lower.chars().peekable();
+    for (offset, ch) in text.char_indices() {
+        if needle.peek().is_none() {
+            return Some(offset);
+        }
+        for lower in ch.to_lowercase() {
+            if needle.next() != Some(lower) {
+                return None;
+            }
+        }
+    }
+    needle.peek().is_none().then_some(text.len())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::SearchItemKind;
+    use crate::path_table::{remove_item, upsert_item, PathIndex};
 
     #[test]
     fn contains_ascii_case_insensitive_works() {
@@ -260,24 +1480,180 @@ mod tests {
         assert!(!contains_ascii_case_insensitive("HelloWorld", "xyz"));
     }
 
+    #[test]
+    fn unicode_names_share_prefix_buckets_with_queries() {
+        for (name, query) in [
+            ("Übung.txt", "ÜBUNG"),
+            ("Отчёт за март.docx", "ОТЧЁТ"),
+            ("Éclair recette.md", "écl"),
+        ] {
+            let name_lower = name.to_lowercase();
+            let query_lower = query.to_lowercase();
+            assert!(filename_prefix_keys(&name_lower).contains(&query_prefix_key(&query_lower)));
+            assert!(contains_case_insensitive(name, &query_lower));
+        }
+
+        assert!(contains_case_insensitive("C:\\Daten\\ÜBUNG.txt", "übung"));
+        assert!(!contains_case_insensitive("Отчёт.docx", "март"));
+    }
+
     #[test]
     fn wildcard_match_works() {
-        let item = SearchItem {
-            path: "C:\\tmp\\notes.txt".into(),
-            modified_unix_secs: 0,
-            kind: SearchItemKind::File,
-        };
+        let item = SearchItem::new("C:\\tmp\\notes.txt", 0, 0, SearchItemKind::File);
         assert!(query_matches_item("n*.txt", &item));
         assert!(query_matches_item("*tmp*", &item));
     }
 
     #[test]
-    fn boolean_and_requires_all_terms() {
-        let item = SearchItem {
-            path: "C:\\tmp\\project notes.txt".into(),
-            modified_unix_secs: 0,
-            kind: SearchItemKind::File,
+    fn glob_matches_whole_name() {
+        assert!(glob_matches("*.rs", "main.rs"));
+        assert!(glob_matches("*.RS", "Main.rs"));
+        assert!(!glob_matches("*.rs", "main.rsx"));
+        assert!(glob_matches("foo?.txt", "foo1.txt"));
+        assert!(!glob_matches("foo?.txt", "foo.txt"));
+        assert!(!glob_matches("foo?.txt", "foo12.txt"));
+        assert!(glob_matches("a*b*c", "axxbyyc"));
+        assert!(glob_matches("a*b*c", "abc"));
+        assert!(!glob_matches("a*b*c", "abcd"));
+        assert!(glob_matches("?bung.txt", "Übung.txt"));
+    }
+
+    #[test]
+    fn fuzzy_scores_prefer_tight_early_matches() {
+        let contiguous = fuzzy_score("abc", "abc.txt").unwrap();
+        let scattered = fuzzy_score("abc", "axbxc.txt").unwrap();
+        assert!(contiguous > scattered);
+
+        assert!(fuzzy_score("abc", "abc.txt") > fuzzy_score("abc", "abc_backup_copy.txt"));
+        assert!(fuzzy_score("rd", "README.md") > fuzzy_score("rd", "card.txt"));
+        assert!(fuzzy_score("mr", "main_rs.txt") > fuzzy_score("mr", "summary.txt"));
+        assert_eq!(fuzzy_score("abc", "acb.txt"), None);
+        assert!(fuzzy_score("übu", "Übung.txt").is_some());
+    }
+
+    #[test]
+    fn acronyms_match_word_initials_in_order() {
+        assert!(acronym_matches("rsm", "RustSearchMain.rs").is_some());
+        assert!(acronym_matches("ghp", "get_http_parser.rs").is_some());
+        assert!(acronym_matches("mtf", "my-test file.txt").is_some());
+        assert!(acronym_matches("v2n", "v2_notes.md").is_some());
+
+        assert_eq!(acronym_matches("msr", "RustSearchMain"), None);
+        assert_eq!(acronym_matches("phg", "get_http_parser.rs"), None);
+        assert_eq!(acronym_matches("rsm", "rustsearchmain.rs"), None);
+        assert_eq!(acronym_matches("r", "RustSearchMain.rs"), None);
+
+        let acronym = relevance_score("rsm", "RustSearchMain.rs").unwrap();
+        assert!(acronym > fuzzy_score("rsm", "RustSearchMain.rs").unwrap());
+        assert!(acronym > relevance_score("rsm", "rsmisc_notes_backup.txt").unwrap());
+    }
+
+    #[test]
+    fn fuzzy_term_only_covers_plain_queries() {
+        assert_eq!(SearchQuery::parse("abc").fuzzy_term(), Some("abc"));
+        assert_eq!(SearchQuery::parse("abc def").fuzzy_term(), None);
+        assert_eq!(SearchQuery::parse("abc size:>1mb").fuzzy_term(), None);
+        assert_eq!(SearchQuery::parse("*.rs").fuzzy_term(), None);
+        assert_eq!(SearchQuery::parse("abc or def").fuzzy_term(), None);
+    }
+
+    #[test]
+    fn filename_index_selection_keeps_the_true_first_rows_in_any_order() {
+        let paths = [
+            "C:\\b\\notes.txt",
+            "C:\\a\\notes",
+            "C:\\a\\my notes.txt",
+            "C:\\z\\notes",
+            "C:\\a\\notes.txt",
+            "C:\\a\\todo.txt",
+        ];
+        let select = |paths: &[&str], mode: SortMode| -> Vec<String> {
+            let items: Vec<SearchItem> = paths
+                .iter()
+                .map(|path| SearchItem::new(path, 0, 0, SearchItemKind::File))
+                .collect();
+            let (mut exact, mut prefixes) = (HashMap::new(), HashMap::new());
+            for (index, item) in items.iter().enumerate() {
+                index_filename(&mut exact, &mut prefixes, &item.name, index, false);
+            }
+            let mut matches = filename_index_lookup(&exact, &prefixes, &items, "notes", false);
+            select_first_sorted(&mut matches, 3, mode, false, |&index| &items[index]);
+            let mut kept: Vec<SearchItem> = matches.iter().map(|&i| items[i].clone()).collect();
+            sort_items(&mut kept, mode, false);
+            kept.iter().map(SearchItem::full_path).collect()
+        };
+
+        let mut reversed = paths;
+        reversed.reverse();
+        for mode in [SortMode::Name, SortMode::Path] {
+            assert_eq!(select(&paths, mode), select(&reversed, mode), "{:?}", mode);
+        }
+        assert_eq!(
+            select(&paths, SortMode::Name),
+            ["C:\\a\\notes", "C:\\z\\notes", "C:\\a\\notes.txt"]
+        );
+        assert_eq!(
+            select(&paths, SortMode::Path),
+            ["C:\\a\\notes", "C:\\a\\notes.txt", "C:\\b\\notes.txt"]
+        );
+        assert_eq!(select(&paths, SortMode::Relevance).len(), 3);
+    }
+
+    #[test]
+    fn ranked_hits_keep_order_for_ties() {
+        let hit = |path: &str, score| RankedHit {
+            item: SearchItem::new(path, 0, 0, SearchItemKind::File),
+            score,
+        };
+        let mut hits = vec![
+            hit("C:\\a", 1),
+            hit("C:\\b", 5),
+            hit("C:\\c", 1),
+            hit("C:\\d", 3),
+        ];
+        rank_hits(&mut hits, 3, None);
+        let names: Vec<&str> = hits.iter().map(|hit| hit.item.name.as_ref()).collect();
+        assert_eq!(names, ["b", "d", "a"]);
+    }
+
+    #[test]
+    fn per_folder_cap_keeps_the_best_hits_of_each_folder() {
+        let mut folders = crate::path_table::PathTable::default();
+        let mut hit = |path: &str, score| RankedHit {
+            item: folders.item(path, 0, 0, SearchItemKind::File),
+            score,
         };
+        let mut hits = vec![
+            hit("C:\\cache\\a", 9),
+            hit("C:\\cache\\b", 8),
+            hit("C:\\cache\\c", 7),
+            hit("C:\\docs\\d", 1),
+            hit("C:\\cache\\e", 6),
+            hit("D:\\f", 2),
+        ];
+        rank_hits(&mut hits, 10, Some(2));
+        let names: Vec<&str> = hits.iter().map(|hit| hit.item.name.as_ref()).collect();
+        assert_eq!(names, ["a", "b", "f", "d"]);
+    }
+
+    #[test]
+    fn terms_match_across_folder_and_name() {
+        let mut folders = crate::path_table::PathTable::default();
+        let report = folders.item("C:\\work\\Reports\\q1.pdf", 0, 0, SearchItemKind::File);
+        let summary = folders.item("C:\\work\\Reports\\q2.pdf", 0, 0, SearchItemKind::File);
+        assert!(std::sync::Arc::ptr_eq(&report.folder, &summary.folder));
+        assert_eq!(report.full_path(), "C:\\work\\Reports\\q1.pdf");
+
+        assert!(query_matches_item("reports", &report));
+        assert!(query_matches_item("q1", &report));
+        assert!(query_matches_item("reports\\q1", &report));
+        assert!(query_matches_item("c:\\work\\*.pdf", &report));
+        assert!(!query_matches_item("reportsq1", &report));
+    }
+
+    #[test]
+    fn boolean_and_requires_all_terms() {
+        let item = SearchItem::new("C:\\tmp\\project notes.txt", 0, 0, SearchItemKind::File);
 
         assert!(SearchQuery::parse("project AND notes").matches_item(&item));
         assert!(!SearchQuery::parse("project AND report").matches_item(&item));
@@ -285,11 +1661,7 @@ mod tests {
 
     #[test]
     fn boolean_or_allows_any_group() {
-        let item = SearchItem {
-            path: "C:\\tmp\\budget.xlsx".into(),
-            modified_unix_secs: 0,
-            kind: SearchItemKind::File,
-        };
+        let item = SearchItem::new("C:\\tmp\\budget.xlsx", 0, 0, SearchItemKind::File);
 
         assert!(SearchQuery::parse("notes OR budget").matches_item(&item));
         assert!(SearchQuery::parse("notes OR bud*").matches_item(&item));
@@ -298,11 +1670,7 @@ mod tests {
 
     #[test]
     fn boolean_and_binds_inside_or_groups() {
-        let item = SearchItem {
-            path: "C:\\tmp\\client invoice.pdf".into(),
-            modified_unix_secs: 0,
-            kind: SearchItemKind::File,
-        };
+        let item = SearchItem::new("C:\\tmp\\client invoice.pdf", 0, 0, SearchItemKind::File);
 
         assert!(SearchQuery::parse("notes AND draft OR client AND invoice").matches_item(&item));
         assert!(!SearchQuery::parse("notes OR client AND draft").matches_item(&item));
@@ -310,17 +1678,399 @@ mod tests {
 
     #[test]
     fn boolean_operators_must_be_standalone_words() {
-        let item = SearchItem {
-            path: "C:\\tmp\\candy orange.txt".into(),
-            modified_unix_secs: 0,
-            kind: SearchItemKind::File,
-        };
+        let item = SearchItem::new("C:\\tmp\\candy orange.txt", 0, 0, SearchItemKind::File);
 
         assert!(!query_uses_boolean_logic("candy"));
         assert!(!query_uses_boolean_logic("orange"));
         assert!(SearchQuery::parse("candy orange").matches_item(&item));
     }
 
+    #[test]
+    fn space_separated_terms_must_all_match() {
+        let item = |path: &str| SearchItem::new(path, 0, 0, SearchItemKind::File);
+
+        let query = SearchQuery::parse("foo bar");
+        assert!(query.matches_item(&item("C:\\tmp\\barfoo.txt")));
+        assert!(query.matches_item(&item("C:\\tmp\\afoobar")));
+        assert!(!query.matches_item(&item("C:\\tmp\\foo.txt")));
+        assert!(SearchQuery::parse("rust main").matches_item(&item("C:\\rust\\src\\main.rs")));
+    }
+
+    #[test]
+    fn sort_modes_order_items_with_path_tie_break() {
+        let item = |path: &str, modified_unix_secs: i64, size: u64| {
+            SearchItem::new(path, modified_unix_secs, size, SearchItemKind::File)
+        };
+        let mut items = vec![
+            item("C:\\b\\Notes.txt", 10, UNKNOWN_SIZE),
+            item("C:\\a\\zeta.txt", 30, 512),
+            item("C:\\a\\notes.txt", 10, 2048),
+        ];
+
+        sort_items(&mut items, SortMode::Name, false);
+        let paths: Vec<String> = items.iter().map(SearchItem::full_path).collect();
+        assert_eq!(
+            paths,
+            ["C:\\a\\notes.txt", "C:\\b\\Notes.txt", "C:\\a\\zeta.txt"]
+        );
+
+        sort_items(&mut items, SortMode::Date, true);
+        let paths: Vec<String> = items.iter().map(SearchItem::full_path).collect();
+        assert_eq!(
+            paths,
+            ["C:\\a\\zeta.txt", "C:\\a\\notes.txt", "C:\\b\\Notes.txt"]
+        );
+
+        sort_items(&mut items, SortMode::Date, false);
+        let paths: Vec<String> = items.iter().map(SearchItem::full_path).collect();
+        assert_eq!(
+            paths,
+            ["C:\\a\\notes.txt", "C:\\b\\Notes.txt", "C:\\a\\zeta.txt"]
+        );
+
+        sort_items(&mut items, SortMode::Size, true);
+        let paths: Vec<String> = items.iter().map(SearchItem::full_path).collect();
+        assert_eq!(
+            paths,
+            ["C:\\a\\notes.txt", "C:\\a\\zeta.txt", "C:\\b\\Notes.txt"]
+        );
+
+        sort_items(&mut items, SortMode::Size, false);
+        let paths: Vec<String> = items.iter().map(SearchItem::full_path).collect();
+        assert_eq!(
+            paths,
+            ["C:\\a\\zeta.txt", "C:\\a\\notes.txt", "C:\\b\\Notes.txt"]
+        );
+    }
+
+    #[test]
+    fn parses_size_filters() {
+        assert_eq!(
+            parse_size_filter("size:>=1mb"),
+            Some((CompareOp::Ge, 1024 * 1024))
+        );
+        assert_eq!(
+            parse_size_filter("size:<=10kb"),
+            Some((CompareOp::Le, 10 * 1024))
+        );
+        assert_eq!(
+            parse_size_filter("size:>2gb"),
+            Some((CompareOp::Gt, 2 * 1024 * 1024 * 1024))
+        );
+        assert_eq!(parse_size_filter("size:<500b"), Some((CompareOp::Lt, 500)));
+        assert_eq!(parse_size_filter("size:=42"), Some((CompareOp::Eq, 42)));
+        assert_eq!(parse_size_filter("size:1.5kb"), Some((CompareOp::Eq, 1536)));
+        assert_eq!(parse_size_filter("size:abc"), None);
+        assert_eq!(parse_size_filter("size:>"), None);
+        assert_eq!(parse_size_filter("report"), None);
+    }
+
+    #[test]
+    fn parses_absolute_modified_dates() {
+        let jan_first = 1_704_067_200;
+        assert_eq!(parse_date("2024-01-01"), Some(jan_first));
+        assert_eq!(parse_date("2024-02-29"), Some(jan_first + 59 * 86_400));
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("2024-13-01"), None);
+        assert_eq!(
+            format_modified(jan_first + 13 * 3600 + 5 * 60),
+            "2024-01-01 13:05"
+        );
+        assert_eq!(format_modified(UNKNOWN_TS), "unknown");
+
+        let query = SearchQuery::parse("report modified:>=2024-01-01");
+        let file = |ts| SearchItem::new("C:\\docs\\report.txt", ts, 10, SearchItemKind::File);
+        assert!(query.matches_item(&file(jan_first)));
+        assert!(!query.matches_item(&file(jan_first - 1)));
+
+        let query = SearchQuery::parse("modified:2024-01-01");
+        assert!(query.matches_item(&file(jan_first + 86_399)));
+        assert!(!query.matches_item(&file(jan_first + 86_400)));
+
+        let query = SearchQuery::parse("modified:>2024-01-01");
+        assert!(!query.matches_item(&file(jan_first + 3600)));
+        assert!(query.matches_item(&file(jan_first + 86_400)));
+    }
+
+    #[test]
+    fn parses_relative_modified_windows() {
+        let now = 1_704_067_200 + 10 * 3600;
+        let week = parse_modified_filter("modified:7d", now).unwrap();
+        assert!(week.matches(now - 6 * 86_400));
+        assert!(!week.matches(now - 8 * 86_400));
+
+        let older = parse_modified_filter("modified:<7d", now).unwrap();
+        assert!(older.matches(now - 8 * 86_400));
+        assert!(!older.matches(now - 60));
+
+        let today = parse_modified_filter("modified:today", now).unwrap();
+        assert!(today.matches(1_704_067_200));
+        assert!(!today.matches(1_704_067_199));
+        assert_eq!(parse_modified_filter("modified:soon", now), None);
+
+        let recent = SearchItem::new("C:\\a.txt", unix_now() - 60, 1, SearchItemKind::File);
+        assert!(SearchQuery::parse("modified:1h").matches_item(&recent));
+        assert!(!SearchQuery::parse("modified:<1h").matches_item(&recent));
+    }
+
+    #[test]
+    fn date_filters_cut_off_and_cycle() {
+        // 2024-03-10 10:00 UTC.
+        let now = 1_710_064_800;
+        assert_eq!(DateFilter::Today.cutoff(now), 1_710_028_800);
+        assert_eq!(DateFilter::Week.cutoff(now), now - 7 * 86_400);
+        assert_eq!(DateFilter::Year.cutoff(now), 1_704_067_200);
+        assert!(modified_since(now, DateFilter::Month.cutoff(now)));
+        assert!(!modified_since(1_704_067_199, DateFilter::Year.cutoff(now)));
+        assert!(!modified_since(UNKNOWN_TS, i64::MIN));
+
+        let mut chip = None;
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            chip = DateFilter::next(chip);
+            seen.push(chip.map(DateFilter::label));
+        }
+        assert_eq!(
+            seen,
+            [
+                Some("Today"),
+                Some("7 days"),
+                Some("30 days"),
+                Some("This year"),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_modified_time_never_matches() {
+        let item = SearchItem::new("C:\\a.txt", UNKNOWN_TS, 1, SearchItemKind::File);
+        for query in [
+            "modified:>2000-01-01",
+            "modified:<2999-01-01",
+            "modified:7d",
+        ] {
+            assert!(!SearchQuery::parse(query).matches_item(&item), "{}", query);
+        }
+    }
+
+    #[test]
+    fn size_filters_are_anded_with_text_terms() {
+        let item = SearchItem::new(
+            "C:\\tmp\\report.pdf",
+            0,
+            2 * 1024 * 1024,
+            SearchItemKind::File,
+        );
+
+        assert!(SearchQuery::parse("report size:>1mb").matches_item(&item));
+        assert!(SearchQuery::parse("size:>=2mb").matches_item(&item));
+        assert!(SearchQuery::parse("size:<=2mb report").matches_item(&item));
+        assert!(SearchQuery::parse("size:=2mb").matches_item(&item));
+        assert!(!SearchQuery::parse("report size:<500kb").matches_item(&item));
+        assert!(!SearchQuery::parse("notes size:>1mb").matches_item(&item));
+        assert!(!SearchQuery::parse("size:>2mb").matches_item(&item));
+
+        let sizeless =
+            SearchItem::new("C:\\tmp\\report.pdf", 0, UNKNOWN_SIZE, SearchItemKind::File);
+        assert!(!SearchQuery::parse("report size:>1mb").matches_item(&sizeless));
+        assert!(SearchQuery::parse("report size:>1mb").matches_once_sized(&sizeless));
+        assert!(!SearchQuery::parse("notes size:>1mb").matches_once_sized(&sizeless));
+        assert!(!SearchQuery::parse("report").matches_once_sized(&sizeless));
+
+        let named = SearchItem::new("C:\\tmp\\size:abc.txt", 0, 10, SearchItemKind::File);
+        assert!(SearchQuery::parse("size:abc").matches_item(&named));
+        assert!(!SearchQuery::parse("size:abc").matches_item(&item));
+    }
+
+    #[test]
+    fn excluded_terms_filter_matches() {
+        let item = |path: &str| SearchItem::new(path, 0, 0, SearchItemKind::File);
+        let final_invoice = item("C:\\docs\\invoice final.pdf");
+        let draft_invoice = item("C:\\docs\\invoice draft.pdf");
+        let dashed = item("C:\\docs\\-foo.txt");
+
+        let query = SearchQuery::parse("invoice -draft");
+        assert!(query.matches_item(&final_invoice));
+        assert!(!query.matches_item(&draft_invoice));
+
+        let query = SearchQuery::parse("-draft");
+        assert!(query.matches_item(&final_invoice));
+        assert!(query.matches_item(&dashed));
+        assert!(!query.matches_item(&draft_invoice));
+
+        let query = SearchQuery::parse("invoice -draft -final");
+        assert!(!query.matches_item(&final_invoice));
+        assert!(!query.matches_item(&draft_invoice));
+
+        let query = SearchQuery::parse("\\-foo");
+        assert!(query.matches_item(&dashed));
+        assert!(!query.matches_item(&final_invoice));
+    }
+
+    #[test]
+    fn tokenizes_phrases_and_exclusions() {
+        assert_eq!(
+            tokenize_query("\"foo bar\" baz"),
+            [
+                QueryTerm::Phrase("foo bar".to_string()),
+                QueryTerm::Include("baz".to_string())
+            ]
+        );
+        assert_eq!(
+            tokenize_query("report -\"old copy\" -tmp \\-dash"),
+            [
+                QueryTerm::Include("report".to_string()),
+                QueryTerm::Exclude("old copy".to_string()),
+                QueryTerm::Exclude("tmp".to_string()),
+                QueryTerm::Include("-dash".to_string())
+            ]
+        );
+        assert_eq!(
+            tokenize_query("notes \"my report"),
+            [
+                QueryTerm::Include("notes".to_string()),
+                QueryTerm::Phrase("my report".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn quoted_phrases_match_literal_spaces() {
+        let item = |path: &str| SearchItem::new(path, 0, 0, SearchItemKind::File);
+
+        let query = SearchQuery::parse("\"my report\"");
+        assert!(query.matches_item(&item("C:\\docs\\my report.pdf")));
+        assert!(!query.matches_item(&item("C:\\docs\\report my.pdf")));
+        assert!(!query_uses_boolean_logic("\"cats and dogs\""));
+        assert!(
+            SearchQuery::parse("\"cats and dogs\"").matches_item(&item("C:\\cats and dogs.txt"))
+        );
+    }
+
+    #[test]
+    fn ext_filters_match_final_extension() {
+        let item = |path: &str| SearchItem::new(path, 0, 0, SearchItemKind::File);
+        let budget = item("C:\\docs\\Budget.XLSX");
+        let photo = item("C:\\docs\\budget.tar.jpg");
+        let readme = item("C:\\docs\\README");
+        let dotfile = item("C:\\repo\\.gitignore");
+
+        assert!(SearchQuery::parse("budget ext:xlsx").matches_item(&budget));
+        assert!(!SearchQuery::parse("budget ext:xlsx").matches_item(&photo));
+        assert!(SearchQuery::parse("ext:png,jpg").matches_item(&photo));
+        assert!(!SearchQuery::parse("ext:tar").matches_item(&photo));
+        assert!(SearchQuery::parse("ext:").matches_item(&readme));
+        assert!(SearchQuery::parse("ext:").matches_item(&dotfile));
+        assert!(!SearchQuery::parse("ext:gitignore").matches_item(&dotfile));
+        assert!(!SearchQuery::parse("ext:").matches_item(&budget));
+    }
+
+    #[test]
+    fn path_filters_match_parent_folder() {
+        let item = |path: &str| SearchItem::new(path, 0, 0, SearchItemKind::File);
+        let nested = item("C:\\app\\node_modules\\pkg\\config.json");
+        let root = item("C:\\app\\config.json");
+        let named = item("C:\\app\\node_modules.txt");
+
+        assert!(SearchQuery::parse("path:node_modules").matches_item(&nested));
+        assert!(!SearchQuery::parse("path:node_modules").matches_item(&root));
+        assert!(!SearchQuery::parse("path:node_modules").matches_item(&named));
+        assert!(SearchQuery::parse("config path:node_modules").matches_item(&nested));
+        assert!(!SearchQuery::parse("config path:node_modules").matches_item(&root));
+        assert!(!SearchQuery::parse("pkg path:node_modules").matches_item(&nested));
+        assert!(SearchQuery::parse("config path:app/node_modules").matches_item(&nested));
+    }
+
+    #[test]
+    fn attr_filters_match_attribute_bits() {
+        let item = |attrs| {
+            SearchItem::new("C:\\tmp\\desktop.ini", 0, 0, SearchItemKind::File).with_attrs(attrs)
+        };
+        let hidden = item(0x2 | 0x4);
+        let readonly = item(0x1);
+
+        assert!(SearchQuery::parse("attr:hidden").matches_item(&hidden));
+        assert!(!SearchQuery::parse("attr:HIDDEN").matches_item(&readonly));
+        assert!(SearchQuery::parse("attr:hidden,readonly desktop").matches_item(&readonly));
+        assert!(!SearchQuery::parse("attr:hidden attr:readonly").matches_item(&hidden));
+        assert!(!SearchQuery::parse("attr:system").matches_item(&item(0)));
+        assert_eq!(parse_attr_filter("attr:sparse"), None);
+        assert!(SearchQuery::parse("attr:sparse").fuzzy_term().is_some());
+        assert!(SearchQuery::parse("attr:hidden ini").fuzzy_term().is_none());
+    }
+
+    #[test]
+    fn full_path_matching_keeps_path_filters_separate() {
+        let item = |path: &str| SearchItem::new(path, 0, 0, SearchItemKind::File);
+        let nested = item("C:\\app\\node_modules\\pkg\\config.json");
+        let root = item("C:\\app\\config.json");
+        let full = |query: &str| SearchQuery::parse(query).matching_full_paths(true);
+
+        assert!(full("pkg path:node_modules").matches_item(&nested));
+        assert!(!full("pkg path:node_modules").matches_item(&root));
+        assert!(!full("app path:node_modules").matches_item(&root));
+        assert!(full("modules\\pkg\\con").matches_item(&nested));
+        assert!(full("*.json").matches_item(&nested));
+        assert!(full("c:\\app\\*").matches_item(&root));
+        assert!(!full("-node_modules").matches_item(&nested));
+        assert!(full("-node_modules").matches_item(&root));
+    }
+
+    #[test]
+    fn folds_diacritics_only_when_asked() {
+        assert_eq!(fold_ascii("Crème Brûlée.TXT"), "Creme Brulee.TXT");
+        assert_eq!(fold_ascii("Ångström ŽŁÓW ÿ"), "Angstrom ZLOW y");
+        assert_eq!(fold_ascii("cafe\u{301}"), "cafe");
+        assert_eq!(fold_ascii("straße Отчёт"), "straße Отчёт");
+        assert_eq!(fold_ascii("Đ Ħ ı Ŀ Ł Ø Ŧ"), "D H i L L O T");
+        assert_eq!(fold_ascii("Ørsted søndag.pdf"), "Orsted sondag.pdf");
+        assert_eq!(fold_ascii("Æsir Œuvre йод"), "Æsir Œuvre йод");
+        assert!(matches!(fold_ascii("plain.txt"), Cow::Borrowed(_)));
+
+        let item = |path: &str| SearchItem::new(path, 0, 0, SearchItemKind::File);
+        let cafe = item("C:\\Menüs\\Café Notes.md");
+        let query = |text: &str, fold| SearchQuery::parse(text).folding_diacritics(fold);
+
+        assert!(!query("cafe", false).matches_item(&cafe));
+        assert!(query("cafe", true).matches_item(&cafe));
+        assert!(query("café", true).matches_item(&item("C:\\cafe.md")));
+        assert!(query("café", false).matches_item(&cafe));
+        assert!(query("notes path:menus", true).matches_item(&cafe));
+        assert!(!query("notes path:menus", false).matches_item(&cafe));
+        assert!(!query("-cafe", true).matches_item(&cafe));
+
+        let mut exact = HashMap::new();
+        let mut prefixes = HashMap::new();
+        index_filename(&mut exact, &mut prefixes, "Éclair.txt", 0, true);
+        assert!(exact.contains_key("eclair.txt"));
+        assert!(prefixes.contains_key("ecl"));
+        unindex_filename(&mut exact, &mut prefixes, "Éclair.txt", 0, true);
+        assert!(exact.is_empty() && prefixes.is_empty());
+    }
+
+    #[test]
+    fn highlights_each_term_preserving_original_case() {
+        let terms = highlight_terms("cfg \"my conf\" -draft ext:cfg");
+        assert_eq!(terms, vec!["cfg".to_string(), "my conf".to_string()]);
+
+        let name = "My Config.CFG";
+        let ranges = highlight_ranges(name, &terms);
+        assert_eq!(ranges, vec![0..7, 10..13]);
+        assert_eq!(
+            highlight_segments(name, &ranges),
+            vec![("My Conf", true), ("ig.", false), ("CFG", true)]
+        );
+
+        let terms = highlight_terms("a OR ré*");
+        assert_eq!(terms, vec!["a".to_string()]);
+        assert_eq!(
+            highlight_ranges("ÉtÉ.txt", &["été".to_string()]),
+            vec![0..5]
+        );
+        assert!(highlight_ranges("notes.txt", &[]).is_empty());
+    }
+
     #[test]
     fn incomplete_boolean_queries_are_detected() {
         assert!(query_has_incomplete_boolean_logic("AND"));
@@ -332,4 +2082,143 @@ mod tests {
         assert!(!query_has_incomplete_boolean_logic("project AND notes"));
         assert!(!query_has_incomplete_boolean_logic("project OR notes"));
     }
+
+    #[test]
+    fn incremental_filename_index_matches_rebuild() {
+        type Index = HashMap<String, Vec<usize>>;
+        fn build(items: &[SearchItem]) -> (Index, Index) {
+            let (mut exact, mut prefixes) = (HashMap::new(), HashMap::new());
+            for (index, item) in items.iter().enumerate() {
+                index_filename(&mut exact, &mut prefixes, &item.name, index, true);
+            }
+            (exact, prefixes)
+        }
+        fn sorted((exact, prefixes): (Index, Index)) -> Vec<(bool, String, Vec<usize>)> {
+            let mut out: Vec<_> = exact
+                .into_iter()
+                .map(|(key, indices)| (true, key, indices))
+                .chain(
+                    prefixes
+                        .into_iter()
+                        .map(|(key, indices)| (false, key, indices)),
+                )
+                .map(|(exact, key, mut indices)| {
+                    indices.sort_unstable();
+                    (exact, key, indices)
+                })
+                .collect();
+            out.sort();
+            out
+        }
+        let file = |path: &str| SearchItem::new(path, 0, 0, SearchItemKind::File);
+
+        let mut items = Vec::new();
+        let mut path_index = PathIndex::new();
+        for path in ["C:\\Report.docx", "C:\\notes.txt", "C:\\Übung.md"] {
+            upsert_item(&mut items, &mut path_index, file(path));
+        }
+        let (mut exact, mut prefixes) = build(&items);
+        let mut apply = |items: &mut Vec<SearchItem>, deleted: &[&str], created: &[&str]| {
+            for path in deleted {
+                let (removed, slot) = remove_item(items, &mut path_index, path).unwrap();
+                unindex_swap_removed(&mut exact, &mut prefixes, items, &removed.name, slot, true);
+            }
+            for path in created {
+                let (slot, added) = upsert_item(items, &mut path_index, file(path));
+                assert!(added);
+                index_filename(&mut exact, &mut prefixes, &items[slot].name, slot, true);
+            }
+        };
+
+        apply(&mut items, &[], &["C:\\Notebook.ipynb"]);
+        apply(&mut items, &["C:\\Report.docx"], &[]);
+        // A rename is a delete of the old name and a create of the new one.
+        apply(&mut items, &["C:\\Übung.md"], &["C:\\README.txt"]);
+        apply(&mut items, &["C:\\notes.txt"], &[]);
+
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_ref()).collect();
+        assert_eq!(names, ["Notebook.ipynb", "README.txt"]);
+        assert!(!exact.contains_key("report.docx"));
+        assert!(!exact.contains_key("ubung.md"));
+        assert!(!prefixes.contains_key("rep"));
+        assert_eq!(sorted((exact, prefixes)), sorted(build(&items)));
+    }
+
+    #[test]
+    fn dedup_drops_repeated_paths_and_collapses_hard_links() {
+        let item = |path: &str, file_id| {
+            SearchItem::new(path, 0, 0, SearchItemKind::File).with_file_id(file_id)
+        };
+        let mut items = vec![
+            item("C:\\projects\\app\\notes.txt", 9),
+            item("C:\\tmp\\a.txt", 0),
+            item("C:\\tmp\\a.txt", 0),
+            item("C:\\n.txt", 9),
+            item("D:\\n.txt", 9),
+            item("\\\\?\\Volume{a}\\n.txt", 9),
+            item("\\\\?\\Volume{b}\\n.txt", 9),
+        ];
+        let paths = |items: &[SearchItem]| -> Vec<String> {
+            items.iter().map(SearchItem::full_path).collect()
+        };
+
+        dedup_items(&mut items, None);
+        assert_eq!(
+            paths(&items),
+            [
+                "C:\\projects\\app\\notes.txt",
+                "C:\\tmp\\a.txt",
+                "C:\\n.txt",
+                "D:\\n.txt",
+                "\\\\?\\Volume{a}\\n.txt",
+                "\\\\?\\Volume{b}\\n.txt",
+            ]
+        );
+
+        let mut first_listed = items.clone();
+        dedup_items(&mut first_listed, Some(&LatestLinks::new()));
+        assert_eq!(
+            paths(&first_listed),
+            [
+                "C:\\projects\\app\\notes.txt",
+                "C:\\tmp\\a.txt",
+                "D:\\n.txt",
+                "\\\\?\\Volume{a}\\n.txt",
+                "\\\\?\\Volume{b}\\n.txt",
+            ]
+        );
+
+        let latest = LatestLinks::from([(("C:".into(), 9), "C:\\n.txt".into())]);
+        dedup_items(&mut items, Some(&latest));
+        assert_eq!(
+            paths(&items),
+            [
+                "C:\\n.txt",
+                "C:\\tmp\\a.txt",
+                "D:\\n.txt",
+                "\\\\?\\Volume{a}\\n.txt",
+                "\\\\?\\Volume{b}\\n.txt",
+            ]
+        );
+        assert_eq!(volume_of("\\\\?\\Volume{a}\\docs"), "\\\\?\\Volume{a}");
+        assert_eq!(volume_of("C:\\docs"), "C:");
+    }
+
+    #[test]
+    fn filename_index_leaves_relevance_to_the_worker() {
+        assert!(!filename_index_answers("rsm", SortMode::Relevance));
+        assert!(filename_index_answers("rsm", SortMode::Name));
+        assert!(filename_index_answers("report", SortMode::Date));
+        for query in [
+            "",
+            "rep*",
+            "a b",
+            "-draft",
+            "ext:rs",
+            "\"quoted\"",
+            "a or b",
+        ] {
+            assert!(!filename_index_answers(query, SortMode::Name), "{}", query);
+        }
+    }
 }
@@ -1,9 +1,29 @@
 use std::collections::HashMap;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc;
 use std::thread;
 
-use crate::search::SearchQuery;
-use crate::{SearchItem, SEARCH_BATCH_SIZE, UNKNOWN_TS, VISIBLE_RESULTS_LIMIT};
+use rayon::prelude::*;
+use regex::Regex;
+
+use crate::export::write_export;
+use crate::feed::RecentEvents;
+use crate::search::{
+    cmp_items, fold_ascii, modified_since, rank_hits, relevance_score, sort_items, RankedHit,
+    SearchQuery, SortMode,
+};
+use crate::sizes::{file_size, ResolvedSizes};
+use crate::{SearchItem, SearchItemKind, UNKNOWN_SIZE, UNKNOWN_TS};
+
+const PARALLEL_SCAN_MIN_ITEMS: usize = 200_000;
+const MAX_SCAN_THREADS: usize = 8;
+/// Most files a `size:` search reads sizes for when the index has none.
+const SIZE_PROBE_LIMIT: usize = 2_000;
+/// Most file sizes a search sorted by size reads before it keeps its rows.
+/// Matches past it that have no size sort after every sized one.
+const SIZE_SORT_READ_LIMIT: usize = 100_000;
 
 pub(crate) enum SearchEvent {
     Progress {
@@ -14,29 +34,82 @@ pub(crate) enum SearchEvent {
     Done {
         generation: u64,
         items: Vec<SearchItem>,
+        total_matches: usize,
+        /// Matching files left out of a size sort because their size was
+        /// not read.
+        unsized_matches: usize,
     },
+    /// Sizes read while running a `size:` search or sorting by size, for the
+    /// UI's copy of the index.
+    Sizes(ResolvedSizes),
+    /// An `/export` finished: how many rows were written, or why it failed.
+    Exported {
+        path: PathBuf,
+        result: Result<usize, String>,
+    },
+    /// A `/copyall` finished: the paths to copy, and how many matched in all.
+    CopiedPaths { paths: Vec<String>, total: usize },
 }
 
 pub(crate) enum SearchWorkerMessage {
     SetCorpus {
         items: Vec<SearchItem>,
-        recent_event_by_path: HashMap<Box<str>, i64>,
+        recent_event_by_path: RecentEvents,
     },
-    Run {
-        generation: u64,
-        query: String,
-        latest_only_mode: bool,
-        latest_window_secs: i64,
+    Run(SearchRun),
+    /// Writes every match to `path` without holding up the UI.
+    Export {
+        source: MatchSource,
+        path: PathBuf,
+    },
+    /// Lists the paths of up to `limit` matches for the clipboard.
+    CopyAll {
+        source: MatchSource,
+        limit: usize,
     },
     Cancel,
     Clear,
 }
 
-struct SearchRun {
-    generation: u64,
-    query: String,
-    latest_only_mode: bool,
-    latest_window_secs: i64,
+/// Where `/export` and `/copyall` take their rows from.
+pub(crate) enum MatchSource {
+    /// Every match is already among the results shown.
+    Shown(Vec<SearchItem>),
+    /// The search to run again without the visible-results cap.
+    Rerun(SearchRun),
+}
+
+impl MatchSource {
+    fn into_items(self, corpus: &[SearchItem], recent: &RecentEvents) -> Vec<SearchItem> {
+        match self {
+            Self::Shown(items) => items,
+            Self::Rerun(run) => collect_all_matches(corpus, recent, &run),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct SearchRun {
+    pub(crate) generation: u64,
+    pub(crate) query: String,
+    pub(crate) regex: Option<Regex>,
+    pub(crate) latest_only_mode: bool,
+    pub(crate) latest_window_secs: i64,
+    pub(crate) include_dirs: bool,
+    /// Match the query and regex against whole paths rather than file names.
+    pub(crate) full_path: bool,
+    /// Ignore diacritics in the query and in names.
+    pub(crate) fold_diacritics: bool,
+    /// Most hits ranked and returned.
+    pub(crate) result_limit: usize,
+    /// Items scanned per thread between checks for a newer message.
+    pub(crate) batch_size: usize,
+    pub(crate) sort_mode: SortMode,
+    pub(crate) sort_descending: bool,
+    /// Most hits kept from any one folder, for `/diverse`.
+    pub(crate) per_folder: Option<usize>,
+    /// Cutoff of the active date chip; older and undated items are skipped.
+    pub(crate) modified_since: Option<i64>,
 }
 
 pub(crate) fn spawn_search_worker() -> (
@@ -48,8 +121,9 @@ pub(crate) fn spawn_search_worker() -> (
 
     thread::spawn(move || {
         let mut corpus: Vec<SearchItem> = Vec::new();
-        let mut recent_event_by_path: HashMap<Box<str>, i64> = HashMap::new();
+        let mut recent_event_by_path: RecentEvents = HashMap::new();
         let mut pending_run: Option<SearchRun> = None;
+        let mut narrow_cache: Option<NarrowCache> = None;
 
         loop {
             if let Some(run) = pending_run.take() {
@@ -60,6 +134,7 @@ pub(crate) fn spawn_search_worker() -> (
                     &request_rx,
                     &event_tx,
                     &mut pending_run,
+                    &mut narrow_cache,
                 ) {
                     break;
                 }
@@ -73,24 +148,22 @@ pub(crate) fn spawn_search_worker() -> (
                 }) => {
                     corpus = items;
                     recent_event_by_path = recent;
+                    narrow_cache = None;
                 }
-                Ok(SearchWorkerMessage::Run {
-                    generation,
-                    query,
-                    latest_only_mode,
-                    latest_window_secs,
-                }) => {
-                    pending_run = Some(SearchRun {
-                        generation,
-                        query,
-                        latest_only_mode,
-                        latest_window_secs,
-                    });
+                Ok(SearchWorkerMessage::Run(run)) => {
+                    pending_run = Some(run);
+                }
+                Ok(SearchWorkerMessage::Export { source, path }) => {
+                    export_matches(source, path, &corpus, &recent_event_by_path, &event_tx);
+                }
+                Ok(SearchWorkerMessage::CopyAll { source, limit }) => {
+                    copy_matches(source, limit, &corpus, &recent_event_by_path, &event_tx);
                 }
                 Ok(SearchWorkerMessage::Clear) => {
                     corpus.clear();
                     recent_event_by_path.clear();
                     pending_run = None;
+                    narrow_cache = None;
                 }
                 Ok(SearchWorkerMessage::Cancel) => {
                     pending_run = None;
@@ -103,30 +176,84 @@ pub(crate) fn spawn_search_worker() -> (
     (request_tx, event_rx)
 }
 
+/// Runs `run` to completion the way the worker does, with no UI to stream
+/// partial results to, and returns its total match count.
+pub(crate) fn run_search_to_end(
+    run: SearchRun,
+    corpus: &mut Vec<SearchItem>,
+    recent_event_by_path: &mut RecentEvents,
+) -> usize {
+    let (_request_tx, request_rx) = mpsc::channel();
+    let (event_tx, event_rx) = mpsc::channel();
+    run_search_query(
+        run,
+        corpus,
+        recent_event_by_path,
+        &request_rx,
+        &event_tx,
+        &mut None,
+        &mut None,
+    );
+    event_rx
+        .try_iter()
+        .find_map(|event| match event {
+            SearchEvent::Done { total_matches, .. } => Some(total_matches),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
 fn run_search_query(
     run: SearchRun,
     corpus: &mut Vec<SearchItem>,
-    recent_event_by_path: &mut HashMap<Box<str>, i64>,
+    recent_event_by_path: &mut RecentEvents,
     request_rx: &mpsc::Receiver<SearchWorkerMessage>,
     event_tx: &mpsc::Sender<SearchEvent>,
     pending_run: &mut Option<SearchRun>,
+    narrow_cache: &mut Option<NarrowCache>,
 ) -> bool {
-    let total = corpus.len().max(1);
-    let latest_cutoff = if run.latest_only_mode {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_secs() as i64)
-            .unwrap_or(0);
-        Some(now - run.latest_window_secs)
-    } else {
-        None
-    };
+    let parsed_query = (!run.query.is_empty() && run.regex.is_none()).then(|| {
+        SearchQuery::parse(&run.query)
+            .matching_full_paths(run.full_path)
+            .folding_diacritics(run.fold_diacritics)
+    });
+    let narrow_key = parsed_query
+        .as_ref()
+        .filter(|_| !run.latest_only_mode)
+        .and_then(SearchQuery::fuzzy_term)
+        .map(|term| NarrowKey {
+            term: term.to_string(),
+            include_dirs: run.include_dirs,
+            full_path: run.full_path,
+            fold: run.fold_diacritics,
+            relevance: run.sort_mode == SortMode::Relevance,
+            modified_since: run.modified_since,
+        });
+    let previous = narrow_cache.take();
+    let candidates = previous
+        .as_ref()
+        .zip(narrow_key.as_ref())
+        .filter(|(cache, key)| cache.key.narrows_to(key))
+        .map(|(cache, _)| cache.indices.as_slice());
 
-    let mut out: Vec<SearchItem> = Vec::new();
-    let parsed_query = (!run.query.is_empty()).then(|| SearchQuery::parse(&run.query));
+    let filter = ScanFilter::for_run(
+        &run,
+        parsed_query.as_ref(),
+        recent_event_by_path,
+        narrow_key.is_some(),
+    );
+    let threads = scan_threads(corpus.len());
+    let span = candidates.map_or(corpus.len(), <[usize]>::len);
+    let total = span.max(1);
+    let mut total_matches = 0usize;
+    let mut hits: Vec<RankedHit> = Vec::new();
+    let mut matched_indices: Vec<usize> = Vec::new();
+    let mut sizeless: Vec<usize> = Vec::new();
+    let mut sizes: ResolvedSizes = Vec::new();
+    let mut unsized_matches = 0usize;
 
     let mut start = 0usize;
-    while start < corpus.len() {
+    while start < span {
         if let Ok(message) = request_rx.try_recv() {
             match message {
                 SearchWorkerMessage::SetCorpus {
@@ -137,83 +264,984 @@ fn run_search_query(
                     *recent_event_by_path = recent;
                     return false;
                 }
-                SearchWorkerMessage::Run {
-                    generation,
-                    query,
-                    latest_only_mode,
-                    latest_window_secs,
-                } => {
-                    *pending_run = Some(SearchRun {
-                        generation,
-                        query,
-                        latest_only_mode,
-                        latest_window_secs,
-                    });
+                SearchWorkerMessage::Run(next) => {
+                    *pending_run = Some(next);
+                    *narrow_cache = previous;
                     return false;
                 }
-                SearchWorkerMessage::Clear | SearchWorkerMessage::Cancel => {
+                // The search picks up again once these are answered.
+                SearchWorkerMessage::Export { source, path } => {
+                    export_matches(source, path, corpus, recent_event_by_path, event_tx);
+                }
+                SearchWorkerMessage::CopyAll { source, limit } => {
+                    copy_matches(source, limit, corpus, recent_event_by_path, event_tx);
+                }
+                SearchWorkerMessage::Cancel => {
                     *pending_run = None;
+                    *narrow_cache = previous;
                     return false;
                 }
+                SearchWorkerMessage::Clear => {
+                    *pending_run = None;
+                    return false;
+                }
+            }
+        }
+
+        let limit = if filter.keeps_all_matches() {
+            run.result_limit
+        } else {
+            run.result_limit.saturating_sub(hits.len())
+        };
+        let (end, result) = match candidates {
+            Some(candidates) => {
+                let end = (start + run.batch_size).min(span);
+                let items = candidates[start..end]
+                    .iter()
+                    .map(|&index| (index, &corpus[index]));
+                (end, filter.scan(items, limit))
             }
+            None => {
+                let end = (start + run.batch_size * threads).min(span);
+                (end, scan_items(corpus, start..end, &filter, threads, limit))
+            }
+        };
+        total_matches += result.matches;
+        hits.extend(result.hits);
+        matched_indices.extend(result.indices);
+        sizeless.extend(result.sizeless);
+        sizeless.truncate(SIZE_PROBE_LIMIT);
+        unsized_matches += result.unsized_matches;
+        for (index, size) in result.sized {
+            corpus[index].size = size;
+            sizes.push((corpus[index].full_path(), size));
+        }
+        if hits.len() >= 2 * run.result_limit {
+            filter.prune(&mut hits, run.result_limit);
+        }
+
+        let _ = event_tx.send(SearchEvent::Progress {
+            generation: run.generation,
+            scanned: end.min(total),
+            total,
+        });
+
+        start = end;
+    }
+
+    *narrow_cache = narrow_key.map(|key| NarrowCache {
+        key,
+        indices: matched_indices,
+    });
+
+    if let Some(query) = parsed_query.as_ref() {
+        sizes.extend(probe_sizes(
+            corpus,
+            &sizeless,
+            query,
+            &mut hits,
+            &mut total_matches,
+        ));
+    }
+    if !sizes.is_empty() {
+        let _ = event_tx.send(SearchEvent::Sizes(sizes));
+    }
+
+    filter.prune(&mut hits, run.result_limit);
+    let mut out: Vec<SearchItem> = hits.into_iter().map(|hit| hit.item).collect();
+
+    if run.latest_only_mode {
+        sort_latest_first(&mut out, recent_event_by_path);
+    }
+
+    sort_results(&mut out, run.sort_mode, run.sort_descending);
+
+    let _ = event_tx.send(SearchEvent::Done {
+        generation: run.generation,
+        items: out,
+        total_matches,
+        unsized_matches,
+    });
+    false
+}
+
+struct ScanFilter<'a> {
+    include_dirs: bool,
+    full_path: bool,
+    fold: bool,
+    latest_cutoff: Option<i64>,
+    modified_since: Option<i64>,
+    regex: Option<&'a Regex>,
+    query: Option<&'a SearchQuery>,
+    fuzzy_term: Option<&'a str>,
+    recent_event_by_path: &'a RecentEvents,
+    record_matches: bool,
+    per_folder: Option<usize>,
+    /// The active non-relevance sort, which decides the hits kept.
+    sort: Option<(SortMode, bool)>,
+    /// File sizes a size sort may still read, shared by every scan thread.
+    size_reads_left: AtomicUsize,
+}
+
+#[derive(Default)]
+struct ScanResult {
+    matches: usize,
+    hits: Vec<RankedHit>,
+    indices: Vec<usize>,
+    /// Files that miss only because their size was never read.
+    sizeless: Vec<usize>,
+    /// Sizes read so a size sort can place the hits.
+    sized: Vec<(usize, u64)>,
+    unsized_matches: usize,
+}
+
+/// Identifies a plain single-term search whose full match set can seed the next one.
+#[derive(Debug, PartialEq, Eq)]
+struct NarrowKey {
+    term: String,
+    include_dirs: bool,
+    full_path: bool,
+    fold: bool,
+    relevance: bool,
+    modified_since: Option<i64>,
+}
+
+impl NarrowKey {
+    /// A longer term that starts with this one can only match a subset of
+    /// what this one matched, by substring, subsequence, or initials alike,
+    /// and so can a later date cutoff. Dropping or loosening a cutoff widens
+    /// the matches, so that rescans.
+    fn narrows_to(&self, next: &NarrowKey) -> bool {
+        let longer_term = next.term.len() > self.term.len() && next.term.starts_with(&self.term);
+        let (same_bound, tighter_bound) = match (self.modified_since, next.modified_since) {
+            (None, None) => (true, false),
+            (None, Some(_)) => (false, true),
+            (Some(cutoff), Some(next_cutoff)) => (next_cutoff == cutoff, next_cutoff > cutoff),
+            (Some(_), None) => (false, false),
+        };
+        next.include_dirs == self.include_dirs
+            && next.full_path == self.full_path
+            && next.fold == self.fold
+            && next.relevance == self.relevance
+            && ((longer_term && (same_bound || tighter_bound))
+                || (next.term == self.term && tighter_bound))
+    }
+}
+
+/// Corpus indices of every match of the last completed search. Dropped
+/// whenever the corpus is replaced.
+struct NarrowCache {
+    key: NarrowKey,
+    indices: Vec<usize>,
+}
+
+impl<'a> ScanFilter<'a> {
+    fn for_run(
+        run: &'a SearchRun,
+        query: Option<&'a SearchQuery>,
+        recent_event_by_path: &'a RecentEvents,
+        record_matches: bool,
+    ) -> Self {
+        let latest_cutoff = run.latest_only_mode.then(|| {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            now - run.latest_window_secs
+        });
+
+        Self {
+            include_dirs: run.include_dirs,
+            full_path: run.full_path,
+            fold: run.fold_diacritics,
+            latest_cutoff,
+            modified_since: run.modified_since,
+            regex: run.regex.as_ref(),
+            query,
+            fuzzy_term: query
+                .filter(|_| run.sort_mode == SortMode::Relevance)
+                .and_then(SearchQuery::fuzzy_term),
+            recent_event_by_path,
+            record_matches,
+            per_folder: run.per_folder,
+            sort: (run.sort_mode != SortMode::Relevance)
+                .then_some((run.sort_mode, run.sort_descending)),
+            size_reads_left: AtomicUsize::new(SIZE_SORT_READ_LIMIT),
+        }
+    }
+
+    /// Reads the size of a matching file the index has none for, when
+    /// sorting by size and the read budget lasts, so pruning sees it.
+    fn read_size_for_sort(&self, index: usize, item: &mut SearchItem, result: &mut ScanResult) {
+        if !matches!(self.sort, Some((SortMode::Size, _)))
+            || item.size != UNKNOWN_SIZE
+            || item.kind != SearchItemKind::File
+        {
+            return;
+        }
+        let budget =
+            self.size_reads_left
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                    left.checked_sub(1)
+                });
+        if budget.is_err() {
+            result.unsized_matches += 1;
+            return;
         }
+        item.size = file_size(&item.full_path());
+        result.sized.push((index, item.size));
+    }
 
-        let end = (start + SEARCH_BATCH_SIZE).min(corpus.len());
-        for item in &corpus[start..end] {
-            let matches_latest = latest_cutoff
+    /// Whether every match has to be weighed before the kept hits are known.
+    fn keeps_all_matches(&self) -> bool {
+        self.fuzzy_term.is_some() || self.per_folder.is_some() || self.sort.is_some()
+    }
+
+    /// Cuts `hits` to the `limit` that come first under the active sort or
+    /// ranking.
+    fn prune(&self, hits: &mut Vec<RankedHit>, limit: usize) {
+        if let Some((mode, descending)) = self.sort {
+            hits.sort_by(|a, b| cmp_items(&a.item, &b.item, mode, descending));
+        }
+        rank_hits(hits, limit, self.per_folder);
+    }
+
+    /// Keeps the `limit` best hits when ranking or sorting, otherwise the
+    /// first `limit`.
+    fn scan<'c>(
+        &self,
+        items: impl Iterator<Item = (usize, &'c SearchItem)>,
+        limit: usize,
+    ) -> ScanResult {
+        let mut result = ScanResult::default();
+        // Once `limit` hits are kept, anything not beating the worst of them is dropped.
+        let mut floor: Option<i64> = None;
+        for (index, item) in items {
+            if !self.include_dirs && item.kind == SearchItemKind::Folder {
+                continue;
+            }
+
+            let matches_latest = self
+                .latest_cutoff
                 .map(|cutoff| {
-                    recent_event_by_path
-                        .get(item.path.as_ref())
-                        .copied()
+                    recent_event_time(self.recent_event_by_path, item)
                         .or((item.modified_unix_secs != UNKNOWN_TS)
                             .then_some(item.modified_unix_secs))
                         .map(|ts| ts >= cutoff)
                         .unwrap_or(false)
                 })
                 .unwrap_or(true);
+            if !matches_latest {
+                continue;
+            }
+            if self
+                .modified_since
+                .is_some_and(|cutoff| !modified_since(item.modified_unix_secs, cutoff))
+            {
+                continue;
+            }
 
-            let matches_query = parsed_query
-                .as_ref()
-                .map(|query| query.matches_item(item))
-                .unwrap_or(true);
+            let score = self.fuzzy_term.and_then(|term| {
+                if self.fold {
+                    relevance_score(term, &fold_ascii(&item.name))
+                } else {
+                    relevance_score(term, &item.name)
+                }
+            });
+            let matches_query = if let Some(regex) = self.regex {
+                if self.full_path {
+                    regex.is_match(&item.full_path())
+                } else {
+                    regex.is_match(&item.name)
+                }
+            } else if score.is_some() {
+                true
+            } else {
+                self.query
+                    .map(|query| query.matches_item(item))
+                    .unwrap_or(true)
+            };
+            if !matches_query {
+                if result.sizeless.len() < SIZE_PROBE_LIMIT
+                    && self
+                        .query
+                        .is_some_and(|query| query.matches_once_sized(item))
+                {
+                    result.sizeless.push(index);
+                }
+                continue;
+            }
 
-            if matches_latest && matches_query {
-                out.push(item.clone());
-                if out.len() >= VISIBLE_RESULTS_LIMIT {
-                    break;
+            result.matches += 1;
+            if self.record_matches {
+                result.indices.push(index);
+            }
+            if self.fuzzy_term.is_some() {
+                // Folder-only matches still count but rank below every name match.
+                let score = score.unwrap_or(i64::MIN);
+                if floor.is_some_and(|floor| score <= floor) {
+                    continue;
+                }
+                result.hits.push(RankedHit {
+                    item: item.clone(),
+                    score,
+                });
+                if result.hits.len() >= 2 * limit {
+                    rank_hits(&mut result.hits, limit, self.per_folder);
+                    floor = result
+                        .hits
+                        .last()
+                        .filter(|_| result.hits.len() >= limit)
+                        .map(|hit| hit.score);
+                }
+            } else if self.keeps_all_matches() {
+                // A later match may sort ahead, and hits past a folder's cap
+                // free room, so keep collecting and prune as the ranked path does.
+                let mut item = item.clone();
+                self.read_size_for_sort(index, &mut item, &mut result);
+                result.hits.push(RankedHit { item, score: 0 });
+                if result.hits.len() >= 2 * limit {
+                    self.prune(&mut result.hits, limit);
                 }
+            } else if result.hits.len() < limit {
+                result.hits.push(RankedHit {
+                    item: item.clone(),
+                    score: 0,
+                });
             }
         }
 
-        let scanned = end.min(total);
-        let _ = event_tx.send(SearchEvent::Progress {
-            generation: run.generation,
-            scanned,
-            total,
-        });
+        if self.keeps_all_matches() {
+            self.prune(&mut result.hits, limit);
+        }
+        result
+    }
+}
+
+fn scan_threads(corpus_len: usize) -> usize {
+    if corpus_len < PARALLEL_SCAN_MIN_ITEMS {
+        return 1;
+    }
+    rayon::current_num_threads().min(MAX_SCAN_THREADS)
+}
+
+/// Splits `corpus[range]` into `threads` chunks scanned on the rayon pool and
+/// merges the per-chunk hits in chunk order, so ties keep the same order as
+/// a sequential scan.
+fn scan_items(
+    corpus: &[SearchItem],
+    range: Range<usize>,
+    filter: &ScanFilter,
+    threads: usize,
+    limit: usize,
+) -> ScanResult {
+    let base = range.start;
+    let items = &corpus[range];
+    if threads <= 1 || items.len() < 2 * threads {
+        return filter.scan((base..).zip(items), limit);
+    }
+
+    let chunk_len = items.len().div_ceil(threads);
+    let results: Vec<ScanResult> = items
+        .par_chunks(chunk_len)
+        .enumerate()
+        .map(|(chunk_index, chunk)| {
+            let chunk_base = base + chunk_index * chunk_len;
+            filter.scan((chunk_base..).zip(chunk), limit)
+        })
+        .collect();
+
+    let mut merged = ScanResult::default();
+    for result in results {
+        merged.matches += result.matches;
+        merged.hits.extend(result.hits);
+        merged.indices.extend(result.indices);
+        merged.sizeless.extend(result.sizeless);
+        merged.sized.extend(result.sized);
+        merged.unsized_matches += result.unsized_matches;
+    }
+    merged
+}
 
-        if out.len() >= VISIBLE_RESULTS_LIMIT {
-            break;
+/// Reads the sizes of up to `SIZE_PROBE_LIMIT` files that failed only on
+/// `size:`, keeps them on the corpus so the next search has them, and adds
+/// the ones that now match.
+fn probe_sizes(
+    corpus: &mut [SearchItem],
+    sizeless: &[usize],
+    query: &SearchQuery,
+    hits: &mut Vec<RankedHit>,
+    total_matches: &mut usize,
+) -> ResolvedSizes {
+    let mut sizes = Vec::new();
+    for &index in sizeless.iter().take(SIZE_PROBE_LIMIT) {
+        let item = &mut corpus[index];
+        let path = item.full_path();
+        item.size = file_size(&path);
+        sizes.push((path, item.size));
+        if query.matches_item(item) {
+            *total_matches += 1;
+            hits.push(RankedHit {
+                item: item.clone(),
+                score: 0,
+            });
         }
+    }
+    sizes
+}
 
-        start = end;
+fn export_matches(
+    source: MatchSource,
+    path: PathBuf,
+    corpus: &[SearchItem],
+    recent_event_by_path: &RecentEvents,
+    event_tx: &mpsc::Sender<SearchEvent>,
+) {
+    let items = source.into_items(corpus, recent_event_by_path);
+    let result = write_export(&path, &items);
+    let _ = event_tx.send(SearchEvent::Exported { path, result });
+}
+
+fn copy_matches(
+    source: MatchSource,
+    limit: usize,
+    corpus: &[SearchItem],
+    recent_event_by_path: &RecentEvents,
+    event_tx: &mpsc::Sender<SearchEvent>,
+) {
+    let items = source.into_items(corpus, recent_event_by_path);
+    let paths = items
+        .iter()
+        .take(limit)
+        .map(SearchItem::full_path)
+        .collect();
+    let _ = event_tx.send(SearchEvent::CopiedPaths {
+        paths,
+        total: items.len(),
+    });
+}
+
+/// Every match of `run` in `corpus`, ordered like a finished search but
+/// without the visible-results cap. Unknown sizes are left unresolved, since
+/// statting a full match set would stall the caller, so `size:` skips them.
+fn collect_all_matches(
+    corpus: &[SearchItem],
+    recent_event_by_path: &RecentEvents,
+    run: &SearchRun,
+) -> Vec<SearchItem> {
+    let parsed_query = (!run.query.is_empty() && run.regex.is_none()).then(|| {
+        SearchQuery::parse(&run.query)
+            .matching_full_paths(run.full_path)
+            .folding_diacritics(run.fold_diacritics)
+    });
+    // Exports and copies take every match, however many share a folder, and
+    // are sorted once at the end.
+    let filter = ScanFilter {
+        per_folder: None,
+        sort: None,
+        ..ScanFilter::for_run(run, parsed_query.as_ref(), recent_event_by_path, false)
+    };
+    let limit = corpus.len().max(1);
+    let mut result = scan_items(
+        corpus,
+        0..corpus.len(),
+        &filter,
+        scan_threads(corpus.len()),
+        limit,
+    );
+    if filter.fuzzy_term.is_some() {
+        rank_hits(&mut result.hits, limit, None);
     }
 
+    let mut out: Vec<SearchItem> = result.hits.into_iter().map(|hit| hit.item).collect();
     if run.latest_only_mode {
-        out.sort_by_key(|item| {
-            std::cmp::Reverse(
-                recent_event_by_path
-                    .get(item.path.as_ref())
-                    .copied()
-                    .or((item.modified_unix_secs != UNKNOWN_TS).then_some(item.modified_unix_secs))
-                    .unwrap_or(i64::MIN),
-            )
-        });
+        sort_latest_first(&mut out, recent_event_by_path);
     }
+    sort_items(&mut out, run.sort_mode, run.sort_descending);
+    out
+}
 
-    let _ = event_tx.send(SearchEvent::Done {
-        generation: run.generation,
-        items: out,
+fn sort_latest_first(items: &mut [SearchItem], recent_event_by_path: &RecentEvents) {
+    items.sort_by_key(|item| {
+        std::cmp::Reverse(
+            recent_event_time(recent_event_by_path, item)
+                .or((item.modified_unix_secs != UNKNOWN_TS).then_some(item.modified_unix_secs))
+                .unwrap_or(i64::MIN),
+        )
     });
-    false
+}
+
+/// Applies the user's sort here so the UI thread only receives final results.
+fn sort_results(items: &mut [SearchItem], sort_mode: SortMode, descending: bool) {
+    if sort_mode == SortMode::Size {
+        for item in items.iter_mut() {
+            if item.size == UNKNOWN_SIZE && item.kind == SearchItemKind::File {
+                item.size = file_size(&item.full_path());
+            }
+        }
+    }
+    sort_items(items, sort_mode, descending);
+}
+
+fn recent_event_time(recent_event_by_path: &RecentEvents, item: &SearchItem) -> Option<i64> {
+    if recent_event_by_path.is_empty() {
+        return None;
+    }
+
+    recent_event_by_path
+        .get(item.full_path().as_str())
+        .map(|event| event.at_unix_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_table::PathTable;
+    use crate::settings::{DEFAULT_RESULT_LIMIT, DEFAULT_SEARCH_BATCH_SIZE};
+
+    fn synthetic_corpus(len: usize) -> Vec<SearchItem> {
+        let mut folders = PathTable::default();
+        (0..len)
+            .map(|index| {
+                let kind = if index % 10 == 0 {
+                    SearchItemKind::Folder
+                } else {
+                    SearchItemKind::File
+                };
+                let path = format!(
+                    "C:\\data\\set{}\\{}_{}.{}",
+                    index % 97,
+                    ["RustSearchMain", "report", "abc", "axbxc", "notes"][index % 5],
+                    index,
+                    ["rs", "txt", "md"][index % 3]
+                );
+                folders.item(&path, index as i64, index as u64, kind)
+            })
+            .collect()
+    }
+
+    fn paths(result: &ScanResult) -> Vec<String> {
+        let mut hits: Vec<&RankedHit> = result.hits.iter().collect();
+        hits.truncate(DEFAULT_RESULT_LIMIT);
+        hits.iter().map(|hit| hit.item.full_path()).collect()
+    }
+
+    #[test]
+    fn parallel_scan_matches_sequential_scan_on_a_million_items() {
+        let corpus = synthetic_corpus(1_000_000);
+        let recent = HashMap::new();
+
+        for (query, relevance) in [("abc", true), ("rsm", true), ("report", false)] {
+            let parsed = SearchQuery::parse(query);
+            let filter = ScanFilter {
+                include_dirs: false,
+                full_path: false,
+                fold: false,
+                latest_cutoff: None,
+                modified_since: None,
+                regex: None,
+                query: Some(&parsed),
+                fuzzy_term: relevance.then(|| parsed.fuzzy_term()).flatten(),
+                recent_event_by_path: &recent,
+                record_matches: true,
+                per_folder: None,
+                sort: None,
+                size_reads_left: AtomicUsize::new(0),
+            };
+
+            let all = 0..corpus.len();
+            let mut sequential = scan_items(&corpus, all.clone(), &filter, 1, DEFAULT_RESULT_LIMIT);
+            let mut parallel = scan_items(&corpus, all, &filter, 4, DEFAULT_RESULT_LIMIT);
+            rank_hits(&mut sequential.hits, DEFAULT_RESULT_LIMIT, None);
+            rank_hits(&mut parallel.hits, DEFAULT_RESULT_LIMIT, None);
+
+            assert!(sequential.matches > 0, "{}", query);
+            assert_eq!(sequential.matches, parallel.matches, "{}", query);
+            assert_eq!(paths(&sequential), paths(&parallel), "{}", query);
+            assert_eq!(sequential.indices, parallel.indices, "{}", query);
+        }
+    }
+
+    #[test]
+    fn narrowing_rescans_only_previous_matches() {
+        let corpus = synthetic_corpus(20_000);
+        let recent = HashMap::new();
+        let scan = |query: &str, candidates: Option<&[usize]>| {
+            let parsed = SearchQuery::parse(query);
+            let filter = ScanFilter {
+                include_dirs: true,
+                full_path: false,
+                fold: false,
+                latest_cutoff: None,
+                modified_since: None,
+                regex: None,
+                query: Some(&parsed),
+                fuzzy_term: parsed.fuzzy_term(),
+                recent_event_by_path: &recent,
+                record_matches: true,
+                per_folder: None,
+                sort: None,
+                size_reads_left: AtomicUsize::new(0),
+            };
+            match candidates {
+                Some(candidates) => filter.scan(
+                    candidates.iter().map(|&index| (index, &corpus[index])),
+                    DEFAULT_RESULT_LIMIT,
+                ),
+                None => scan_items(&corpus, 0..corpus.len(), &filter, 1, DEFAULT_RESULT_LIMIT),
+            }
+        };
+
+        let broad = scan("re", None);
+        for query in ["rea", "rep", "repo", "report_1"] {
+            let full = scan(query, None);
+            let narrowed = scan(query, Some(&broad.indices));
+            assert!(narrowed.matches < broad.matches, "{}", query);
+            assert_eq!(narrowed.matches, full.matches, "{}", query);
+            assert_eq!(narrowed.indices, full.indices, "{}", query);
+            assert_eq!(paths(&narrowed), paths(&full), "{}", query);
+        }
+    }
+
+    #[test]
+    fn collects_every_match_past_the_visible_limit() {
+        let corpus = synthetic_corpus(20_000);
+        let recent = HashMap::new();
+        let run = SearchRun {
+            generation: 0,
+            query: "report".to_string(),
+            regex: None,
+            latest_only_mode: false,
+            latest_window_secs: 0,
+            include_dirs: false,
+            full_path: false,
+            fold_diacritics: false,
+            result_limit: DEFAULT_RESULT_LIMIT,
+            batch_size: DEFAULT_SEARCH_BATCH_SIZE,
+            sort_mode: SortMode::Name,
+            sort_descending: false,
+            per_folder: None,
+            modified_since: None,
+        };
+
+        let all = collect_all_matches(&corpus, &recent, &run);
+        assert_eq!(all.len(), 4_000);
+        assert!(all.windows(2).all(|pair| pair[0].name <= pair[1].name));
+    }
+
+    fn run_for(query: &str) -> SearchRun {
+        SearchRun {
+            generation: 1,
+            query: query.to_string(),
+            regex: None,
+            latest_only_mode: false,
+            latest_window_secs: 0,
+            include_dirs: false,
+            full_path: false,
+            fold_diacritics: false,
+            result_limit: DEFAULT_RESULT_LIMIT,
+            batch_size: DEFAULT_SEARCH_BATCH_SIZE,
+            sort_mode: SortMode::Relevance,
+            sort_descending: false,
+            per_folder: None,
+            modified_since: None,
+        }
+    }
+
+    /// Runs `run` through the worker's search and returns every event it sent.
+    fn run_events(corpus: &mut Vec<SearchItem>, run: SearchRun) -> Vec<SearchEvent> {
+        let (_request_tx, request_rx) = mpsc::channel();
+        let (event_tx, event_rx) = mpsc::channel();
+        run_search_query(
+            run,
+            corpus,
+            &mut HashMap::new(),
+            &request_rx,
+            &event_tx,
+            &mut None,
+            &mut None,
+        );
+        event_rx.try_iter().collect()
+    }
+
+    fn done_paths(events: &[SearchEvent]) -> Vec<String> {
+        events
+            .iter()
+            .find_map(|event| match event {
+                SearchEvent::Done { items, .. } => {
+                    Some(items.iter().map(SearchItem::full_path).collect())
+                }
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn exports_every_match_past_the_limit_on_the_worker() {
+        let corpus: Vec<SearchItem> = (0..5)
+            .map(|index| {
+                let path = format!("C:\\logs\\report_{}.txt", index);
+                SearchItem::new(&path, 0, 1, SearchItemKind::File)
+            })
+            .collect();
+        let path = std::env::temp_dir().join(format!(
+            "rustsearch-worker-export-{}.txt",
+            std::process::id()
+        ));
+
+        let (request_tx, event_rx) = spawn_search_worker();
+        request_tx
+            .send(SearchWorkerMessage::SetCorpus {
+                items: corpus,
+                recent_event_by_path: HashMap::new(),
+            })
+            .unwrap();
+        let run = SearchRun {
+            result_limit: 2,
+            ..run_for("report")
+        };
+        request_tx
+            .send(SearchWorkerMessage::Export {
+                source: MatchSource::Rerun(run),
+                path: path.clone(),
+            })
+            .unwrap();
+
+        let event = event_rx
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .unwrap();
+        let written = std::fs::read_to_string(&path).unwrap_or_default();
+        let _ = std::fs::remove_file(&path);
+        let SearchEvent::Exported {
+            path: reported,
+            result,
+        } = event
+        else {
+            panic!("expected an export event");
+        };
+        assert_eq!(reported, path);
+        assert_eq!(result, Ok(5));
+        assert_eq!(written.lines().count(), 5);
+    }
+
+    #[test]
+    fn copies_paths_up_to_the_copy_limit_on_the_worker() {
+        let corpus: Vec<SearchItem> = (0..5)
+            .map(|index| {
+                let path = format!("C:\\logs\\report_{}.txt", index);
+                SearchItem::new(&path, 0, 1, SearchItemKind::File)
+            })
+            .collect();
+
+        let (request_tx, event_rx) = spawn_search_worker();
+        request_tx
+            .send(SearchWorkerMessage::SetCorpus {
+                items: corpus,
+                recent_event_by_path: HashMap::new(),
+            })
+            .unwrap();
+        let run = SearchRun {
+            result_limit: 2,
+            ..run_for("report")
+        };
+        request_tx
+            .send(SearchWorkerMessage::CopyAll {
+                source: MatchSource::Rerun(run),
+                limit: 3,
+            })
+            .unwrap();
+
+        let event = event_rx
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .unwrap();
+        let SearchEvent::CopiedPaths { paths, total } = event else {
+            panic!("expected a copy event");
+        };
+        assert_eq!(paths.len(), 3);
+        assert_eq!(total, 5);
+    }
+
+    #[test]
+    fn sorted_search_keeps_the_newest_matches_past_the_limit() {
+        // Oldest first in the corpus, so encounter order would keep the oldest.
+        let mut corpus: Vec<SearchItem> = (0..50)
+            .map(|age| {
+                let path = format!("C:\\logs\\report_{:02}.txt", age);
+                SearchItem::new(&path, age, 1, SearchItemKind::File)
+            })
+            .collect();
+        let run = SearchRun {
+            result_limit: 3,
+            batch_size: 7,
+            sort_mode: SortMode::Date,
+            sort_descending: true,
+            ..run_for("report")
+        };
+
+        let events = run_events(&mut corpus, run);
+        assert_eq!(
+            done_paths(&events),
+            [
+                "C:\\logs\\report_49.txt",
+                "C:\\logs\\report_48.txt",
+                "C:\\logs\\report_47.txt",
+            ]
+        );
+    }
+
+    #[test]
+    fn size_sort_reads_sizes_before_keeping_rows() {
+        let dir = std::env::temp_dir().join(format!("rustsearch-sizesort-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut corpus: Vec<SearchItem> = (1..=20)
+            .map(|len| {
+                let path = dir.join(format!("report_{:02}.txt", len));
+                std::fs::write(&path, vec![0u8; len]).unwrap();
+                let path = path.to_string_lossy().into_owned();
+                SearchItem::new(&path, 0, UNKNOWN_SIZE, SearchItemKind::File)
+            })
+            .collect();
+        let run = SearchRun {
+            result_limit: 2,
+            batch_size: 3,
+            sort_mode: SortMode::Size,
+            sort_descending: true,
+            ..run_for("report")
+        };
+
+        let events = run_events(&mut corpus, run);
+        let sizes: Vec<u64> = events
+            .iter()
+            .find_map(|event| match event {
+                SearchEvent::Done {
+                    items,
+                    unsized_matches,
+                    ..
+                } => {
+                    assert_eq!(*unsized_matches, 0);
+                    Some(items.iter().map(|item| item.size).collect())
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(sizes, [20, 19]);
+        assert!(corpus.iter().all(|item| item.size != UNKNOWN_SIZE));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, SearchEvent::Sizes(sizes) if sizes.len() == 20)));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn fuzzy_matches_rank_alongside_substring_matches() {
+        let mut corpus: Vec<SearchItem> = [
+            "C:\\src\\abc.txt",
+            "C:\\src\\axbxc.txt",
+            "C:\\src\\rsm.txt",
+            "C:\\src\\RustSearchMain.rs",
+            "C:\\src\\notes.txt",
+        ]
+        .iter()
+        .map(|path| SearchItem::new(path, 0, 1, SearchItemKind::File))
+        .collect();
+
+        let found = done_paths(&run_events(&mut corpus, run_for("abc")));
+        assert_eq!(found, ["C:\\src\\abc.txt", "C:\\src\\axbxc.txt"]);
+
+        let found = done_paths(&run_events(&mut corpus, run_for("rsm")));
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&"C:\\src\\rsm.txt".to_string()));
+        assert!(found.contains(&"C:\\src\\RustSearchMain.rs".to_string()));
+    }
+
+    #[test]
+    fn size_search_reads_sizes_the_index_lacks() {
+        let path =
+            std::env::temp_dir().join(format!("rustsearch-probe-{}.bin", std::process::id()));
+        std::fs::write(&path, [0u8; 64]).unwrap();
+        let path = path.to_string_lossy().into_owned();
+        let mut corpus = vec![SearchItem::new(
+            &path,
+            0,
+            UNKNOWN_SIZE,
+            SearchItemKind::File,
+        )];
+
+        let events = run_events(&mut corpus, run_for("rustsearch-probe size:>=64"));
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(corpus[0].size, 64);
+        assert!(events.iter().any(
+            |event| matches!(event, SearchEvent::Sizes(sizes) if *sizes == [(path.clone(), 64)])
+        ));
+        assert_eq!(done_paths(&events), [path]);
+    }
+
+    #[test]
+    fn only_strict_extensions_narrow() {
+        let key = |term: &str, include_dirs| NarrowKey {
+            term: term.to_string(),
+            include_dirs,
+            full_path: false,
+            fold: false,
+            relevance: true,
+            modified_since: None,
+        };
+        assert!(key("rep", true).narrows_to(&key("repo", true)));
+        assert!(!key("rep", true).narrows_to(&key("rep", true)));
+        assert!(!key("repo", true).narrows_to(&key("rep", true)));
+        assert!(!key("rep", true).narrows_to(&key("pre", true)));
+        assert!(!key("rep", true).narrows_to(&key("repo", false)));
+
+        let since = |term: &str, cutoff| NarrowKey {
+            modified_since: cutoff,
+            ..key(term, true)
+        };
+        assert!(since("rep", None).narrows_to(&since("rep", Some(10))));
+        assert!(since("rep", Some(10)).narrows_to(&since("rep", Some(20))));
+        assert!(since("rep", Some(10)).narrows_to(&since("repo", Some(10))));
+        assert!(!since("rep", Some(10)).narrows_to(&since("rep", Some(10))));
+        assert!(!since("rep", Some(20)).narrows_to(&since("repo", Some(10))));
+        assert!(!since("rep", Some(10)).narrows_to(&since("repo", None)));
+    }
+
+    #[test]
+    fn clearing_a_date_chip_rescans_the_whole_corpus() {
+        let mut corpus: Vec<SearchItem> = [
+            ("C:\\a\\report_old.txt", 100),
+            ("C:\\a\\report_new.txt", 900),
+        ]
+        .iter()
+        .map(|(path, modified)| SearchItem::new(path, *modified, 1, SearchItemKind::File))
+        .collect();
+        let (_request_tx, request_rx) = mpsc::channel();
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut narrow_cache = None;
+        let mut search = |modified_since| {
+            let run = SearchRun {
+                modified_since,
+                ..run_for("report")
+            };
+            run_search_query(
+                run,
+                &mut corpus,
+                &mut HashMap::new(),
+                &request_rx,
+                &event_tx,
+                &mut None,
+                &mut narrow_cache,
+            );
+            let events: Vec<SearchEvent> = event_rx.try_iter().collect();
+            let mut paths = done_paths(&events);
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(search(Some(500)), ["C:\\a\\report_new.txt"]);
+        assert_eq!(
+            search(None),
+            ["C:\\a\\report_new.txt", "C:\\a\\report_old.txt"]
+        );
+    }
 }
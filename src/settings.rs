@@ -0,0 +1,557 @@
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{env, fmt};
+
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::theme::ColorMap;
+use crate::{debug_log, RendererModeRequest};
+
+pub(crate) const DEFAULT_RESULT_LIMIT: usize = 600;
+pub(crate) const DEFAULT_SEARCH_BATCH_SIZE: usize = 12_000;
+pub(crate) const DEFAULT_QUERY_DEBOUNCE_MS: u64 = 70;
+const RESULT_LIMIT_RANGE: RangeInclusive<usize> = 50..=50_000;
+const SEARCH_BATCH_SIZE_RANGE: RangeInclusive<usize> = 1_000..=1_000_000;
+const QUERY_DEBOUNCE_MS_RANGE: RangeInclusive<u64> = 0..=2_000;
+const MIN_QUERY_LEN_RANGE: RangeInclusive<usize> = 1..=3;
+const AUTO_HIDE_SECS_RANGE: RangeInclusive<u64> = 0..=86_400;
+const PER_FOLDER_LIMIT_RANGE: RangeInclusive<usize> = 1..=10_000;
+const MEMORY_LIMIT_MB_RANGE: RangeInclusive<u64> = 0..=1_048_576;
+const SCROLL_MARGIN_ROWS_RANGE: RangeInclusive<usize> = 0..=20;
+pub(crate) const FONT_SCALE_PERCENT_RANGE: RangeInclusive<u32> = 50..=300;
+
+/// How tightly result rows are packed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum RowDensity {
+    Compact,
+    #[default]
+    #[serde(other)]
+    Normal,
+}
+
+impl RowDensity {
+    pub(crate) fn toggled(self) -> Self {
+        match self {
+            Self::Normal => Self::Compact,
+            Self::Compact => Self::Normal,
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Compact => "compact",
+        }
+    }
+
+    /// Row height in points for the GPU renderer, before `font_scale`.
+    pub(crate) fn row_height(self) -> f32 {
+        match self {
+            Self::Normal => 20.0,
+            Self::Compact => 15.0,
+        }
+    }
+
+    pub(crate) fn font_size(self) -> f32 {
+        match self {
+            Self::Normal => 13.0,
+            Self::Compact => 12.0,
+        }
+    }
+
+    /// Marker column text for folders and files.
+    pub(crate) fn kind_marker(self, is_folder: bool) -> &'static str {
+        match (self, is_folder) {
+            (Self::Normal, true) => "[D]",
+            (Self::Normal, false) => "   ",
+            (Self::Compact, true) => "D",
+            (Self::Compact, false) => " ",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Settings {
+    pub(crate) hotkey: String,
+    pub(crate) launch_at_startup: bool,
+    pub(crate) theme: String,
+    pub(crate) include_dirs: bool,
+    /// Match query terms against whole paths instead of just file names.
+    pub(crate) search_full_paths: bool,
+    /// Ignore diacritics when matching, so `cafe` finds `café`.
+    pub(crate) ascii_fold: bool,
+    /// Globs matched against full paths; matching files and folders are not indexed.
+    pub(crate) exclude_paths: Vec<String>,
+    /// Honor `.gitignore` and `.ignore` files when the directory walker indexes.
+    pub(crate) respect_gitignore: bool,
+    /// Index files and folders marked hidden or system, such as `desktop.ini`.
+    pub(crate) index_hidden: bool,
+    /// Create the USN journal on NTFS volumes where it is disabled instead of
+    /// falling back to walking them. Needs an elevated process.
+    pub(crate) create_usn_journal: bool,
+    pub(crate) show_preview: bool,
+    /// Multiplier for text and row sizes; 1.5 suits a 4K display.
+    pub(crate) font_scale: f32,
+    /// `normal` or `compact` result rows.
+    pub(crate) density: RowDensity,
+    /// `gpu` or `soft`; `/gpu` and `/soft` change it, and `RUSTSEARCH_RENDERER`
+    /// overrides it for one run.
+    pub(crate) renderer: RendererModeRequest,
+    /// Most results kept and shown for one search, clamped to 50..=50000.
+    pub(crate) result_limit: usize,
+    /// Items the search worker scans between checks for a newer query.
+    pub(crate) search_batch_size: usize,
+    /// Pause after the last keystroke before a search starts.
+    pub(crate) query_debounce_ms: u64,
+    /// Keep at most `per_folder_limit` results from any one folder. `/diverse`
+    /// toggles it.
+    pub(crate) diverse_results: bool,
+    pub(crate) per_folder_limit: usize,
+    /// Collapse hard links to one NTFS file into a single result. `/dedup`
+    /// toggles it.
+    pub(crate) dedup_results: bool,
+    /// Shorter queries only list exact file-name matches instead of scanning.
+    pub(crate) min_query_len: usize,
+    /// Rows kept visible above and below the selection as it moves.
+    pub(crate) scroll_margin_rows: usize,
+    /// Hide the panel after this many seconds without input; 0 never hides it.
+    pub(crate) auto_hide_secs: u64,
+    /// Flag the index in the status bar once its estimated size passes this
+    /// many MB; 0 turns the warning off. `/memlimit` sets it.
+    pub(crate) memory_limit_mb: u64,
+    /// Most paths `/copyall` puts on the clipboard.
+    pub(crate) copy_all_limit: usize,
+    /// Let `/all` index removable, network and optical drives too; by default
+    /// it only indexes fixed disks.
+    pub(crate) index_non_fixed_drives: bool,
+    /// Put the last search back in the box at startup. Turn off to keep
+    /// searches from being written to disk.
+    pub(crate) restore_last_query: bool,
+    /// Hide the panel after Enter opens a result; Shift+Enter does the
+    /// opposite of whichever this picks.
+    pub(crate) hide_after_open: bool,
+    /// Programs that open files instead of the shell default, keyed by
+    /// lowercase extension without the dot, such as `rs = "code"`.
+    pub(crate) editors: BTreeMap<String, String>,
+    /// File name colors by lowercase extension without the dot, such as
+    /// `log = [255, 140, 0]`. They win over the theme's colors.
+    pub(crate) file_colors: ColorMap,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            hotkey: "`".to_string(),
+            launch_at_startup: false,
+            theme: "default".to_string(),
+            include_dirs: true,
+            search_full_paths: false,
+            ascii_fold: false,
+            exclude_paths: vec![
+                "*\\$Recycle.Bin".to_string(),
+                "*\\System Volume Information".to_string(),
+            ],
+            respect_gitignore: false,
+            index_hidden: false,
+            create_usn_journal: false,
+            show_preview: false,
+            font_scale: 1.0,
+            density: RowDensity::Normal,
+            renderer: RendererModeRequest::Gpu,
+            result_limit: DEFAULT_RESULT_LIMIT,
+            search_batch_size: DEFAULT_SEARCH_BATCH_SIZE,
+            query_debounce_ms: DEFAULT_QUERY_DEBOUNCE_MS,
+            diverse_results: false,
+            per_folder_limit: 5,
+            dedup_results: false,
+            min_query_len: 1,
+            scroll_margin_rows: 3,
+            auto_hide_secs: 0,
+            memory_limit_mb: 2_048,
+            copy_all_limit: 100_000,
+            index_non_fixed_drives: false,
+            restore_last_query: true,
+            hide_after_open: false,
+            editors: BTreeMap::new(),
+            file_colors: ColorMap::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ConfigError {
+    Io(std::io::Error),
+    Parse(String),
+    Serialize(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "settings I/O failed: {}", err),
+            Self::Parse(err) => write!(f, "settings file is malformed: {}", err),
+            Self::Serialize(err) => write!(f, "settings could not be encoded: {}", err),
+        }
+    }
+}
+
+impl Settings {
+    /// The program configured in `editors` for the extension of `path`.
+    pub(crate) fn editor_for(&self, path: &str) -> Option<&str> {
+        let ext = Path::new(path)
+            .extension()?
+            .to_string_lossy()
+            .to_lowercase();
+        self.editors.get(&ext).map(String::as_str)
+    }
+
+    pub(crate) fn result_limit(&self) -> usize {
+        clamp_to(self.result_limit, &RESULT_LIMIT_RANGE)
+    }
+
+    pub(crate) fn search_batch_size(&self) -> usize {
+        clamp_to(self.search_batch_size, &SEARCH_BATCH_SIZE_RANGE)
+    }
+
+    /// `font_scale` within 0.5..=3.0; anything unreadable falls back to 1.0.
+    pub(crate) fn font_scale(&self) -> f32 {
+        if !self.font_scale.is_finite() {
+            return 1.0;
+        }
+        let min = *FONT_SCALE_PERCENT_RANGE.start() as f32 / 100.0;
+        let max = *FONT_SCALE_PERCENT_RANGE.end() as f32 / 100.0;
+        self.font_scale.clamp(min, max)
+    }
+
+    /// Sets `font_scale` from a percentage, clamped, and returns the stored percentage.
+    pub(crate) fn set_font_scale_percent(&mut self, percent: u32) -> u32 {
+        let percent = clamp_to(percent, &FONT_SCALE_PERCENT_RANGE);
+        self.font_scale = percent as f32 / 100.0;
+        percent
+    }
+
+    pub(crate) fn min_query_len(&self) -> usize {
+        clamp_to(self.min_query_len, &MIN_QUERY_LEN_RANGE)
+    }
+
+    pub(crate) fn scroll_margin_rows(&self) -> usize {
+        clamp_to(self.scroll_margin_rows, &SCROLL_MARGIN_ROWS_RANGE)
+    }
+
+    /// `None` unless `/diverse` is on.
+    pub(crate) fn per_folder_cap(&self) -> Option<usize> {
+        self.diverse_results
+            .then(|| clamp_to(self.per_folder_limit, &PER_FOLDER_LIMIT_RANGE))
+    }
+
+    /// `None` when auto-hide is off.
+    pub(crate) fn auto_hide_after(&self) -> Option<Duration> {
+        match clamp_to(self.auto_hide_secs, &AUTO_HIDE_SECS_RANGE) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    /// `None` when the memory warning is off.
+    pub(crate) fn memory_limit_bytes(&self) -> Option<usize> {
+        match clamp_to(self.memory_limit_mb, &MEMORY_LIMIT_MB_RANGE) {
+            0 => None,
+            mb => Some(usize::try_from(mb << 20).unwrap_or(usize::MAX)),
+        }
+    }
+
+    pub(crate) fn query_debounce(&self) -> Duration {
+        Duration::from_millis(clamp_to(self.query_debounce_ms, &QUERY_DEBOUNCE_MS_RANGE))
+    }
+
+    /// Applies `/set <key> <value>`, clamping the value into the key's bounds,
+    /// and returns what was stored.
+    pub(crate) fn set_tunable(&mut self, key: &str, value: u64) -> Result<u64, String> {
+        match key {
+            "limit" => {
+                let value = usize::try_from(value).unwrap_or(usize::MAX);
+                self.result_limit = clamp_to(value, &RESULT_LIMIT_RANGE);
+                Ok(self.result_limit as u64)
+            }
+            "batch" => {
+                let value = usize::try_from(value).unwrap_or(usize::MAX);
+                self.search_batch_size = clamp_to(value, &SEARCH_BATCH_SIZE_RANGE);
+                Ok(self.search_batch_size as u64)
+            }
+            "debounce" => {
+                self.query_debounce_ms = clamp_to(value, &QUERY_DEBOUNCE_MS_RANGE);
+                Ok(self.query_debounce_ms)
+            }
+            "minlen" => {
+                let value = usize::try_from(value).unwrap_or(usize::MAX);
+                self.min_query_len = clamp_to(value, &MIN_QUERY_LEN_RANGE);
+                Ok(self.min_query_len as u64)
+            }
+            "perfolder" => {
+                let value = usize::try_from(value).unwrap_or(usize::MAX);
+                self.per_folder_limit = clamp_to(value, &PER_FOLDER_LIMIT_RANGE);
+                Ok(self.per_folder_limit as u64)
+            }
+            "autohide" => {
+                self.auto_hide_secs = clamp_to(value, &AUTO_HIDE_SECS_RANGE);
+                Ok(self.auto_hide_secs)
+            }
+            "memlimit" => {
+                self.memory_limit_mb = clamp_to(value, &MEMORY_LIMIT_MB_RANGE);
+                Ok(self.memory_limit_mb)
+            }
+            "margin" => {
+                let value = usize::try_from(value).unwrap_or(usize::MAX);
+                self.scroll_margin_rows = clamp_to(value, &SCROLL_MARGIN_ROWS_RANGE);
+                Ok(self.scroll_margin_rows as u64)
+            }
+            _ => Err(format!(
+                "unknown setting {:?} (limit, batch, debounce, minlen, perfolder, autohide, memlimit, margin)",
+                key
+            )),
+        }
+    }
+
+    pub(crate) fn load_from_path(path: &Path) -> Result<Settings, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        toml::from_str(&content).map_err(|err| ConfigError::Parse(err.to_string()))
+    }
+
+    /// Writes the settings to `path`. A file there that does not parse is
+    /// copied to `settings.toml.bak` first, so hand edits are not lost.
+    pub(crate) fn save_to_path(&self, path: &Path) -> Result<(), ConfigError> {
+        let content =
+            toml::to_string_pretty(self).map_err(|err| ConfigError::Serialize(err.to_string()))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(ConfigError::Io)?;
+        }
+        if matches!(Self::load_from_path(path), Err(ConfigError::Parse(_))) {
+            std::fs::copy(path, backup_path(path)).map_err(ConfigError::Io)?;
+        }
+        std::fs::write(path, content).map_err(ConfigError::Io)
+    }
+
+    /// Reads the user's settings. A missing file is created with defaults; a
+    /// malformed one is used as defaults for this run and backed up before
+    /// the first save replaces it.
+    pub(crate) fn load_or_default() -> Settings {
+        let path = settings_path();
+        match Self::load_from_path(&path) {
+            Ok(settings) => settings,
+            Err(ConfigError::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+                let settings = Settings::default();
+                if let Err(err) = settings.save_to_path(&path) {
+                    debug_log(&format!("could not write default settings: {}", err));
+                }
+                settings
+            }
+            Err(err) => {
+                debug_log(&format!(
+                    "warning: using default settings, {} ({})",
+                    err,
+                    path.display()
+                ));
+                Settings::default()
+            }
+        }
+    }
+
+    pub(crate) fn save(&self) -> Result<(), ConfigError> {
+        self.save_to_path(&settings_path())
+    }
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Parses combos such as `` ` ``, `Alt+``, or `Ctrl+Shift+Space`. `Win` is
+/// accepted as an alias for the Super modifier.
+pub(crate) fn parse_hotkey(combo: &str) -> Result<(Modifiers, Code), String> {
+    let normalized: Vec<&str> = combo
+        .split('+')
+        .map(|token| {
+            let token = token.trim();
+            if token.eq_ignore_ascii_case("win") || token.eq_ignore_ascii_case("windows") {
+                "Super"
+            } else {
+                token
+            }
+        })
+        .collect();
+
+    let hotkey: HotKey = normalized
+        .join("+")
+        .parse()
+        .map_err(|err| format!("invalid hotkey {:?}: {}", combo, err))?;
+    Ok((hotkey.mods, hotkey.key))
+}
+
+fn clamp_to<T: Ord + Copy>(value: T, range: &RangeInclusive<T>) -> T {
+    value.clamp(*range.start(), *range.end())
+}
+
+fn settings_path() -> PathBuf {
+    let base = env::var("LOCALAPPDATA").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(base).join("WizMini").join("settings.toml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tolerates_unknown_and_missing_fields() {
+        let settings: Settings =
+            toml::from_str("theme = \"gruvbox\"\nfuture_option = 3\n").unwrap();
+        assert_eq!(settings.theme, "gruvbox");
+        assert_eq!(settings.hotkey, Settings::default().hotkey);
+        assert!(!settings.launch_at_startup);
+        assert!(settings.include_dirs);
+        assert_eq!(settings.exclude_paths, Settings::default().exclude_paths);
+        assert!(!settings.respect_gitignore);
+    }
+
+    #[test]
+    fn clamps_tunables_into_bounds() {
+        let mut settings = Settings {
+            result_limit: 10,
+            ..Settings::default()
+        };
+        assert_eq!(settings.result_limit(), 50);
+        assert_eq!(settings.set_tunable("limit", 2_000), Ok(2_000));
+        assert_eq!(settings.result_limit(), 2_000);
+        assert_eq!(settings.set_tunable("limit", 1_000_000), Ok(50_000));
+        assert_eq!(settings.set_tunable("debounce", 100), Ok(100));
+        assert_eq!(settings.query_debounce(), Duration::from_millis(100));
+        assert_eq!(settings.set_tunable("batch", 1), Ok(1_000));
+        assert_eq!(settings.set_tunable("minlen", 9), Ok(3));
+        assert_eq!(settings.min_query_len(), 3);
+        assert_eq!(settings.auto_hide_after(), None);
+        assert_eq!(settings.set_tunable("perfolder", 0), Ok(1));
+        assert_eq!(settings.per_folder_cap(), None);
+        settings.diverse_results = true;
+        assert_eq!(settings.per_folder_cap(), Some(1));
+        assert_eq!(settings.set_tunable("autohide", 30), Ok(30));
+        assert_eq!(settings.auto_hide_after(), Some(Duration::from_secs(30)));
+        assert_eq!(settings.set_tunable("autohide", 0), Ok(0));
+        assert_eq!(settings.auto_hide_after(), None);
+        assert_eq!(settings.memory_limit_bytes(), Some(2_048 << 20));
+        assert_eq!(settings.set_tunable("memlimit", 0), Ok(0));
+        assert_eq!(settings.memory_limit_bytes(), None);
+        assert_eq!(settings.set_tunable("memlimit", u64::MAX), Ok(1_048_576));
+        assert_eq!(settings.scroll_margin_rows(), 3);
+        assert_eq!(settings.set_tunable("margin", 50), Ok(20));
+        assert!(settings.set_tunable("rows", 5).is_err());
+
+        assert_eq!(settings.set_font_scale_percent(1_000), 300);
+        assert_eq!(settings.font_scale(), 3.0);
+        settings.font_scale = f32::NAN;
+        assert_eq!(settings.font_scale(), 1.0);
+    }
+
+    #[test]
+    fn unknown_density_falls_back_to_normal() {
+        let settings: Settings = toml::from_str("density = \"compact\"\n").unwrap();
+        assert_eq!(settings.density, RowDensity::Compact);
+        let settings: Settings = toml::from_str("density = \"roomy\"\n").unwrap();
+        assert_eq!(settings.density, RowDensity::Normal);
+    }
+
+    #[test]
+    fn unknown_renderer_falls_back_to_gpu() {
+        let settings: Settings = toml::from_str("renderer = \"soft\"\n").unwrap();
+        assert_eq!(settings.renderer, RendererModeRequest::Soft);
+        let settings: Settings = toml::from_str("renderer = \"vulkan\"\n").unwrap();
+        assert_eq!(settings.renderer, RendererModeRequest::Gpu);
+    }
+
+    #[test]
+    fn parses_common_hotkeys() {
+        assert_eq!(parse_hotkey("`"), Ok((Modifiers::empty(), Code::Backquote)));
+        assert_eq!(
+            parse_hotkey("Ctrl+Space"),
+            Ok((Modifiers::CONTROL, Code::Space))
+        );
+        assert_eq!(
+            parse_hotkey("alt + `"),
+            Ok((Modifiers::ALT, Code::Backquote))
+        );
+        assert_eq!(
+            parse_hotkey("Ctrl+Shift+F"),
+            Ok((Modifiers::CONTROL | Modifiers::SHIFT, Code::KeyF))
+        );
+        assert_eq!(parse_hotkey("Win+S"), Ok((Modifiers::SUPER, Code::KeyS)));
+    }
+
+    #[test]
+    fn rejects_invalid_hotkeys() {
+        assert!(parse_hotkey("").is_err());
+        assert!(parse_hotkey("Ctrl+").is_err());
+        assert!(parse_hotkey("Ctrl+Shift").is_err());
+        assert!(parse_hotkey("Ctrl+NotAKey").is_err());
+        assert!(parse_hotkey("A+B").is_err());
+    }
+
+    #[test]
+    fn round_trips_through_a_file_and_rejects_garbage() {
+        let dir = env::temp_dir().join(format!("rustsearch-settings-{}", std::process::id()));
+        let path = dir.join("settings.toml");
+        let settings = Settings {
+            hotkey: "Ctrl+Space".to_string(),
+            launch_at_startup: true,
+            theme: "default".to_string(),
+            include_dirs: false,
+            search_full_paths: true,
+            ascii_fold: true,
+            exclude_paths: vec!["*\\node_modules".to_string()],
+            respect_gitignore: true,
+            index_hidden: true,
+            create_usn_journal: true,
+            show_preview: true,
+            font_scale: 1.25,
+            density: RowDensity::Compact,
+            renderer: RendererModeRequest::Soft,
+            result_limit: 2_000,
+            search_batch_size: 50_000,
+            query_debounce_ms: 120,
+            diverse_results: true,
+            per_folder_limit: 20,
+            dedup_results: true,
+            min_query_len: 2,
+            scroll_margin_rows: 5,
+            auto_hide_secs: 90,
+            memory_limit_mb: 512,
+            copy_all_limit: 5_000,
+            index_non_fixed_drives: true,
+            restore_last_query: false,
+            hide_after_open: true,
+            editors: BTreeMap::from([("rs".to_string(), "code".to_string())]),
+            file_colors: ColorMap::from([("log".to_string(), [255, 140, 0])]),
+        };
+
+        settings.save_to_path(&path).unwrap();
+        assert_eq!(Settings::load_from_path(&path).unwrap(), settings);
+
+        std::fs::write(&path, "hotkey = [").unwrap();
+        assert!(matches!(
+            Settings::load_from_path(&path),
+            Err(ConfigError::Parse(_))
+        ));
+
+        settings.save_to_path(&path).unwrap();
+        let backup = dir.join("settings.toml.bak");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "hotkey = [");
+        std::fs::write(&backup, "kept").unwrap();
+        settings.save_to_path(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "kept");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::UNKNOWN_SIZE;
+
+/// Paths with the size read for each.
+pub(crate) type ResolvedSizes = Vec<(String, u64)>;
+
+/// Reads file sizes off the UI thread, for rows whose index entry has none.
+/// Every request that queued up is answered in one reply.
+pub(crate) fn spawn_size_worker() -> (mpsc::Sender<Vec<String>>, mpsc::Receiver<ResolvedSizes>) {
+    let (request_tx, request_rx) = mpsc::channel::<Vec<String>>();
+    let (result_tx, result_rx) = mpsc::channel::<ResolvedSizes>();
+
+    thread::spawn(move || {
+        while let Ok(mut paths) = request_rx.recv() {
+            while let Ok(more) = request_rx.try_recv() {
+                paths.extend(more);
+            }
+            let mut seen = HashSet::new();
+            let sizes: ResolvedSizes = paths
+                .into_iter()
+                .filter(|path| seen.insert(path.clone()))
+                .map(|path| {
+                    let size = file_size(&path);
+                    (path, size)
+                })
+                .collect();
+            if result_tx.send(sizes).is_err() {
+                break;
+            }
+        }
+    });
+
+    (request_tx, result_rx)
+}
+
+/// Size of the file at `path`, or `UNKNOWN_SIZE` when it cannot be read.
+pub(crate) fn file_size(path: &str) -> u64 {
+    std::fs::metadata(path)
+        .map(|metadata| metadata.len())
+        .unwrap_or(UNKNOWN_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worker_answers_each_path_once() {
+        let path = std::env::temp_dir().join(format!("rustsearch-size-{}", std::process::id()));
+        std::fs::write(&path, b"12345").unwrap();
+        let path = path.to_string_lossy().into_owned();
+
+        let (request_tx, result_rx) = spawn_size_worker();
+        request_tx
+            .send(vec![path.clone(), path.clone(), "Z:\\missing".to_string()])
+            .unwrap();
+        let sizes = result_rx.recv().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            sizes,
+            [(path, 5), ("Z:\\missing".to_string(), UNKNOWN_SIZE)]
+        );
+    }
+}
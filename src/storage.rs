@@ -1,8 +1,20 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 use std::{env, thread};
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
-use crate::{SearchItem, SearchItemKind, SearchScope};
+use crate::path_table::PathTable;
+use crate::{debug_log, SearchItem, SearchItemKind, SearchScope, UNKNOWN_SIZE};
+
+const SCOPE_SNAPSHOT_VERSION: u32 = 7;
+/// Header of checksummed snapshots: magic, zstd payload, then a little-endian
+/// CRC32 of the payload.
+const SNAPSHOT_MAGIC: [u8; 4] = *b"WZSN";
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+// Snapshots are rewritten often, so favor speed over ratio.
+const SNAPSHOT_ZSTD_LEVEL: i32 = 1;
 
 #[derive(Serialize, Deserialize)]
 struct ScopeIndexSnapshot {
@@ -15,10 +27,71 @@ struct ScopeIndexSnapshot {
 struct SnapshotItem {
     path: String,
     modified_unix_secs: i64,
+    #[serde(default = "default_snapshot_size")]
+    size: u64,
     #[serde(default = "default_snapshot_kind")]
     kind: SnapshotItemKind,
 }
 
+#[derive(Serialize, Deserialize)]
+struct ScopeIndexSnapshotV3 {
+    version: u32,
+    scope: String,
+    folders: Vec<String>,
+    items: Vec<SnapshotItemV3>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotItemV3 {
+    folder: u32,
+    name: String,
+    modified_unix_secs: i64,
+    #[serde(default = "default_snapshot_size")]
+    size: u64,
+    #[serde(default = "default_snapshot_kind")]
+    kind: SnapshotItemKind,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScopeIndexSnapshotV6 {
+    version: u32,
+    scope: String,
+    folders: Vec<String>,
+    items: Vec<SnapshotItemV6>,
+}
+
+/// Version 6 adds file attributes; older snapshots load with none set.
+#[derive(Serialize, Deserialize)]
+struct SnapshotItemV6 {
+    folder: u32,
+    name: String,
+    modified_unix_secs: i64,
+    size: u64,
+    kind: SnapshotItemKind,
+    #[serde(default)]
+    attrs: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScopeIndexSnapshotV7 {
+    version: u32,
+    scope: String,
+    folders: Vec<String>,
+    items: Vec<SnapshotItemV7>,
+}
+
+/// Version 7 adds NTFS file reference numbers for `/dedup`.
+#[derive(Serialize, Deserialize)]
+struct SnapshotItemV7 {
+    folder: u32,
+    name: String,
+    modified_unix_secs: i64,
+    size: u64,
+    kind: SnapshotItemKind,
+    attrs: u32,
+    file_id: u64,
+}
+
 #[derive(Clone, Copy, Serialize, Deserialize)]
 enum SnapshotItemKind {
     File,
@@ -77,29 +150,310 @@ pub(crate) fn persist_quick_help_dismissed(value: bool) {
     let _ = std::fs::write(path, if value { "1" } else { "0" });
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct WindowPrefs {
+    pub(crate) width: Option<f32>,
+    pub(crate) fullheight: bool,
+    pub(crate) fullscreen: bool,
+}
+
+pub(crate) fn load_window_prefs() -> WindowPrefs {
+    let Ok(content) = std::fs::read_to_string(window_config_path()) else {
+        return WindowPrefs::default();
+    };
+
+    let mut prefs = WindowPrefs::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "width" => {
+                prefs.width = value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|width| width.is_finite() && *width > 0.0)
+            }
+            "fullheight" => prefs.fullheight = value == "1",
+            "fullscreen" => prefs.fullscreen = value == "1",
+            _ => {}
+        }
+    }
+    prefs
+}
+
+pub(crate) fn persist_window_prefs(prefs: WindowPrefs) {
+    let path = window_config_path();
+    if let Some(parent) = path.parent() {
+        if std::fs::create_dir_all(parent).is_err() {
+            return;
+        }
+    }
+
+    let mut content = String::new();
+    if let Some(width) = prefs.width {
+        content.push_str(&format!("width={}\n", width.round()));
+    }
+    content.push_str(&format!(
+        "fullheight={}\nfullscreen={}\n",
+        u8::from(prefs.fullheight),
+        u8::from(prefs.fullscreen)
+    ));
+    let _ = std::fs::write(path, content);
+}
+
+const SEARCH_HISTORY_LIMIT: usize = 50;
+
+/// Most recent first.
+pub(crate) fn load_search_history() -> VecDeque<String> {
+    let Ok(content) = std::fs::read_to_string(search_history_path()) else {
+        return VecDeque::new();
+    };
+
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .take(SEARCH_HISTORY_LIMIT)
+        .map(str::to_string)
+        .collect()
+}
+
+pub(crate) fn persist_search_history(history: &VecDeque<String>) {
+    let path = search_history_path();
+    if let Some(parent) = path.parent() {
+        if std::fs::create_dir_all(parent).is_err() {
+            return;
+        }
+    }
+
+    let mut content = String::new();
+    for entry in history {
+        content.push_str(entry);
+        content.push('\n');
+    }
+    let _ = std::fs::write(path, content);
+}
+
+/// The search that was in the box when the app last ran, or an empty string.
+pub(crate) fn load_last_query() -> String {
+    let Ok(content) = std::fs::read_to_string(last_query_path()) else {
+        return String::new();
+    };
+
+    let query = content.trim();
+    if query.starts_with('/') {
+        return String::new();
+    }
+    query.to_string()
+}
+
+/// Saves `query` for the next start; an empty query removes the file.
+pub(crate) fn persist_last_query(query: &str) {
+    let path = last_query_path();
+    if query.is_empty() {
+        let _ = std::fs::remove_file(path);
+        return;
+    }
+    if let Some(parent) = path.parent() {
+        if std::fs::create_dir_all(parent).is_err() {
+            return;
+        }
+    }
+
+    let _ = std::fs::write(path, query);
+}
+
+/// Pinned full paths, in the order they were pinned.
+pub(crate) fn load_pinned_paths() -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(pinned_paths_path()) else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub(crate) fn persist_pinned_paths(pins: &[String]) {
+    let path = pinned_paths_path();
+    if let Some(parent) = path.parent() {
+        if std::fs::create_dir_all(parent).is_err() {
+            return;
+        }
+    }
+
+    let mut content = String::new();
+    for pin in pins {
+        content.push_str(pin);
+        content.push('\n');
+    }
+    let _ = std::fs::write(path, content);
+}
+
+/// Pins `path`, or unpins it if it is already pinned. Paths compare without
+/// regard to case, as on NTFS. Returns whether `path` is now pinned.
+pub(crate) fn toggle_pinned_path(pins: &mut Vec<String>, path: &str) -> bool {
+    let before = pins.len();
+    pins.retain(|pin| !pin.eq_ignore_ascii_case(path));
+    if pins.len() != before {
+        return false;
+    }
+    pins.push(path.to_string());
+    true
+}
+
+/// Moves `query` to the front of `history`. Commands and blank queries are
+/// not searches and are skipped. Returns whether `history` changed.
+pub(crate) fn push_search_history(history: &mut VecDeque<String>, query: &str) -> bool {
+    let query = query.trim();
+    if query.is_empty() || query.starts_with('/') || query.contains('\n') {
+        return false;
+    }
+    if history.front().is_some_and(|latest| latest == query) {
+        return false;
+    }
+
+    history.retain(|entry| entry != query);
+    history.push_front(query.to_string());
+    history.truncate(SEARCH_HISTORY_LIMIT);
+    true
+}
+
+/// Serializes a snapshot as checksummed, zstd-compressed bincode.
+pub(crate) fn encode_snapshot<T: Serialize>(snapshot: &T) -> Option<Vec<u8>> {
+    let mut encoder = zstd::Encoder::new(SNAPSHOT_MAGIC.to_vec(), SNAPSHOT_ZSTD_LEVEL).ok()?;
+    bincode::serialize_into(&mut encoder, snapshot).ok()?;
+    let mut bytes = encoder.finish().ok()?;
+    let checksum = crc32fast::hash(&bytes[SNAPSHOT_MAGIC.len()..]);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    Some(bytes)
+}
+
+/// Reads a snapshot written by [`encode_snapshot`], or an older unchecked one.
+/// Returns `None` when the checksum does not match.
+pub(crate) fn decode_snapshot<T: DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+    if let Some(framed) = bytes.strip_prefix(&SNAPSHOT_MAGIC) {
+        let (payload, checksum) = framed.split_at_checked(framed.len().checked_sub(4)?)?;
+        if crc32fast::hash(payload).to_le_bytes() != checksum {
+            debug_log("discarding snapshot with a bad checksum");
+            return None;
+        }
+        bincode::deserialize_from(zstd::Decoder::new(payload).ok()?).ok()
+    } else if bytes.starts_with(&ZSTD_MAGIC) {
+        bincode::deserialize_from(zstd::Decoder::new(bytes).ok()?).ok()
+    } else {
+        bincode::deserialize(bytes).ok()
+    }
+}
+
 pub(crate) fn load_scope_snapshot(scope: &SearchScope) -> Option<Vec<SearchItem>> {
-    if let Ok(file) = std::fs::File::open(scope_snapshot_path(scope)) {
-        if let Ok(snapshot) = bincode::deserialize_from::<_, ScopeIndexSnapshot>(file) {
-            if snapshot.version == 1 && snapshot.scope == scope.label() {
-                return Some(
-                    snapshot
+    let bytes = std::fs::read(scope_snapshot_path(scope)).ok()?;
+    decode_scope_snapshot(&bytes, scope)
+}
+
+fn decode_scope_snapshot(bytes: &[u8], scope: &SearchScope) -> Option<Vec<SearchItem>> {
+    match decode_snapshot::<u32>(bytes)? {
+        2 => {
+            let snapshot = decode_snapshot::<ScopeIndexSnapshot>(bytes)?;
+            if snapshot.scope != scope.label() {
+                return None;
+            }
+
+            let mut folders = PathTable::default();
+            Some(
+                snapshot
+                    .items
+                    .into_iter()
+                    .map(|item| {
+                        folders.item(
+                            &item.path,
+                            item.modified_unix_secs,
+                            item.size,
+                            search_item_kind(item.kind),
+                        )
+                    })
+                    .collect(),
+            )
+        }
+        3..=5 => {
+            let snapshot = decode_snapshot::<ScopeIndexSnapshotV3>(bytes)?;
+            scope_items(
+                ScopeIndexSnapshotV7 {
+                    version: snapshot.version,
+                    scope: snapshot.scope,
+                    folders: snapshot.folders,
+                    items: snapshot
                         .items
                         .into_iter()
-                        .map(|item| SearchItem {
-                            path: item.path.into_boxed_str(),
+                        .map(|item| SnapshotItemV7 {
+                            folder: item.folder,
+                            name: item.name,
                             modified_unix_secs: item.modified_unix_secs,
-                            kind: match item.kind {
-                                SnapshotItemKind::File => SearchItemKind::File,
-                                SnapshotItemKind::Folder => SearchItemKind::Folder,
-                            },
+                            size: item.size,
+                            kind: item.kind,
+                            attrs: 0,
+                            file_id: 0,
                         })
                         .collect(),
-                );
-            }
+                },
+                scope,
+            )
+        }
+        6 => {
+            let snapshot = decode_snapshot::<ScopeIndexSnapshotV6>(bytes)?;
+            scope_items(
+                ScopeIndexSnapshotV7 {
+                    version: snapshot.version,
+                    scope: snapshot.scope,
+                    folders: snapshot.folders,
+                    items: snapshot
+                        .items
+                        .into_iter()
+                        .map(|item| SnapshotItemV7 {
+                            folder: item.folder,
+                            name: item.name,
+                            modified_unix_secs: item.modified_unix_secs,
+                            size: item.size,
+                            kind: item.kind,
+                            attrs: item.attrs,
+                            file_id: 0,
+                        })
+                        .collect(),
+                },
+                scope,
+            )
         }
+        SCOPE_SNAPSHOT_VERSION => scope_items(decode_snapshot(bytes)?, scope),
+        _ => None,
+    }
+}
+
+fn scope_items(snapshot: ScopeIndexSnapshotV7, scope: &SearchScope) -> Option<Vec<SearchItem>> {
+    if snapshot.scope != scope.label() {
+        return None;
     }
 
-    None
+    let folders: Vec<Arc<str>> = snapshot.folders.into_iter().map(Arc::from).collect();
+    snapshot
+        .items
+        .into_iter()
+        .map(|item| {
+            Some(SearchItem {
+                folder: folders.get(item.folder as usize)?.clone(),
+                name: item.name.into_boxed_str(),
+                modified_unix_secs: item.modified_unix_secs,
+                size: item.size,
+                kind: search_item_kind(item.kind),
+                attrs: item.attrs,
+                file_id: item.file_id,
+            })
+        })
+        .collect()
 }
 
 pub(crate) fn persist_scope_snapshot_async(scope: SearchScope, items: Vec<SearchItem>) {
@@ -111,33 +465,121 @@ pub(crate) fn persist_scope_snapshot_async(scope: SearchScope, items: Vec<Search
             }
         }
 
-        let snapshot = ScopeIndexSnapshot {
-            version: 1,
-            scope: scope.label(),
-            items: items
-                .into_iter()
-                .map(|item| SnapshotItem {
-                    path: item.path.into_string(),
-                    modified_unix_secs: item.modified_unix_secs,
-                    kind: match item.kind {
-                        SearchItemKind::File => SnapshotItemKind::File,
-                        SearchItemKind::Folder => SnapshotItemKind::Folder,
-                    },
-                })
-                .collect(),
-        };
-
-        let Ok(file) = std::fs::File::create(path) else {
+        let Some(bytes) = encode_snapshot(&scope_snapshot(&scope, &items)) else {
             return;
         };
-        let _ = bincode::serialize_into(file, &snapshot);
+        if let Err(err) = write_atomically(&path, &bytes) {
+            debug_log(&format!(
+                "snapshot write failed {}: {}",
+                path.display(),
+                err
+            ));
+        }
     });
 }
 
+/// Writes to a sibling `.tmp` file and renames it over `path`, so an
+/// interrupted write never replaces a good file with a partial one.
+pub(crate) fn write_atomically(path: &std::path::Path, bytes: &[u8]) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, bytes)?;
+    std::fs::rename(&tmp_path, path)
+}
+
+fn scope_snapshot(scope: &SearchScope, items: &[SearchItem]) -> ScopeIndexSnapshotV7 {
+    let mut folders: Vec<String> = Vec::new();
+    let mut folder_ids: HashMap<&str, u32> = HashMap::new();
+    let mut snapshot_items = Vec::with_capacity(items.len());
+    for item in items {
+        let folder = *folder_ids.entry(item.folder.as_ref()).or_insert_with(|| {
+            folders.push(item.folder.to_string());
+            (folders.len() - 1) as u32
+        });
+        snapshot_items.push(SnapshotItemV7 {
+            folder,
+            name: item.name.to_string(),
+            modified_unix_secs: item.modified_unix_secs,
+            size: item.size,
+            kind: match item.kind {
+                SearchItemKind::File => SnapshotItemKind::File,
+                SearchItemKind::Folder => SnapshotItemKind::Folder,
+            },
+            attrs: item.attrs,
+            file_id: item.file_id,
+        });
+    }
+
+    ScopeIndexSnapshotV7 {
+        version: SCOPE_SNAPSHOT_VERSION,
+        scope: scope.label(),
+        folders,
+        items: snapshot_items,
+    }
+}
+
+fn search_item_kind(kind: SnapshotItemKind) -> SearchItemKind {
+    match kind {
+        SnapshotItemKind::File => SearchItemKind::File,
+        SnapshotItemKind::Folder => SearchItemKind::Folder,
+    }
+}
+
+fn default_snapshot_size() -> u64 {
+    UNKNOWN_SIZE
+}
+
 fn default_snapshot_kind() -> SnapshotItemKind {
     SnapshotItemKind::File
 }
 
+/// Deletes the index snapshots and USN checkpoints, leaving settings and other
+/// preferences alone. Returns how many files were removed.
+pub(crate) fn clear_all_caches() -> std::io::Result<usize> {
+    clear_caches_in(&cache_dir())
+}
+
+fn clear_caches_in(dir: &std::path::Path) -> std::io::Result<usize> {
+    let mut removed = 0;
+    match std::fs::read_dir(dir.join("snapshots")) {
+        Ok(entries) => {
+            for entry in entries {
+                let path = entry?.path();
+                let is_cache_file = path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("bin") || ext == "tmp");
+                if is_cache_file && path.is_file() {
+                    std::fs::remove_file(&path)?;
+                    removed += 1;
+                }
+            }
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+
+    match std::fs::remove_file(dir.join(USN_CHECKPOINTS_FILE)) {
+        Ok(()) => removed += 1,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+    Ok(removed)
+}
+
+const USN_CHECKPOINTS_FILE: &str = "usn_checkpoints.txt";
+
+pub(crate) fn cache_dir() -> std::path::PathBuf {
+    let base = env::var("LOCALAPPDATA").unwrap_or_else(|_| ".".to_string());
+    std::path::PathBuf::from(base).join("WizMini")
+}
+
+pub(crate) fn snapshots_dir() -> std::path::PathBuf {
+    cache_dir().join("snapshots")
+}
+
+pub(crate) fn usn_checkpoint_path() -> std::path::PathBuf {
+    cache_dir().join(USN_CHECKPOINTS_FILE)
+}
+
 fn scope_config_path() -> std::path::PathBuf {
     let base = env::var("LOCALAPPDATA").unwrap_or_else(|_| ".".to_string());
     std::path::PathBuf::from(base)
@@ -152,10 +594,197 @@ fn quick_help_config_path() -> std::path::PathBuf {
         .join("quick-help-dismissed.txt")
 }
 
-fn scope_snapshot_path(scope: &SearchScope) -> std::path::PathBuf {
+fn window_config_path() -> std::path::PathBuf {
     let base = env::var("LOCALAPPDATA").unwrap_or_else(|_| ".".to_string());
     std::path::PathBuf::from(base)
         .join("WizMini")
-        .join("snapshots")
-        .join(format!("scope-{}.bin", scope.label()))
+        .join("window.txt")
+}
+
+fn pinned_paths_path() -> std::path::PathBuf {
+    cache_dir().join("pins.txt")
+}
+
+fn search_history_path() -> std::path::PathBuf {
+    cache_dir().join("history.txt")
+}
+
+fn last_query_path() -> std::path::PathBuf {
+    cache_dir().join("last-query.txt")
+}
+
+fn scope_snapshot_path(scope: &SearchScope) -> std::path::PathBuf {
+    snapshots_dir().join(format!("scope-{}.bin", scope.label()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn synthetic_items() -> Vec<SearchItem> {
+        (0..2_000)
+            .map(|i| {
+                SearchItem::new(
+                    &format!("C:\\projects\\app-{}\\src\\module_{}.rs", i % 20, i),
+                    1_700_000_000 + i,
+                    4096,
+                    SearchItemKind::File,
+                )
+                .with_attrs(if i % 7 == 0 { 0x2 } else { 0 })
+                .with_file_id(i as u64)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compressed_snapshot_round_trips_and_is_smaller() {
+        let scope = SearchScope::Drive('D');
+        let items = synthetic_items();
+        let snapshot = scope_snapshot(&scope, &items);
+        let raw = bincode::serialize(&snapshot).unwrap();
+        let compressed = encode_snapshot(&snapshot).unwrap();
+        assert!(compressed.starts_with(&SNAPSHOT_MAGIC));
+        assert!(
+            compressed.len() * 3 < raw.len(),
+            "{} vs {}",
+            compressed.len(),
+            raw.len()
+        );
+
+        let loaded = decode_scope_snapshot(&compressed, &scope).unwrap();
+        assert_eq!(loaded.len(), items.len());
+        for (loaded, item) in loaded.iter().zip(&items) {
+            assert_eq!(loaded.full_path(), item.full_path());
+            assert_eq!(loaded.modified_unix_secs, item.modified_unix_secs);
+            assert_eq!(loaded.size, item.size);
+            assert_eq!(loaded.attrs, item.attrs);
+            assert_eq!(loaded.file_id, item.file_id);
+        }
+        assert!(decode_scope_snapshot(&compressed, &SearchScope::Drive('E')).is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_or_corrupt_snapshots() {
+        let scope = SearchScope::EntireCurrentDrive;
+        let bytes = encode_snapshot(&scope_snapshot(&scope, &synthetic_items())).unwrap();
+        assert!(decode_scope_snapshot(&bytes, &scope).is_some());
+
+        for len in [0, 3, 8, bytes.len() / 2, bytes.len() - 1] {
+            assert!(
+                decode_scope_snapshot(&bytes[..len], &scope).is_none(),
+                "{}",
+                len
+            );
+        }
+
+        let mut corrupt = bytes.clone();
+        corrupt[bytes.len() / 2] ^= 0xff;
+        assert!(decode_scope_snapshot(&corrupt, &scope).is_none());
+    }
+
+    #[test]
+    fn pinning_twice_unpins() {
+        let mut pins = vec![r"C:\notes\todo.txt".to_string()];
+        assert!(toggle_pinned_path(&mut pins, r"D:\work\plan.md"));
+        assert_eq!(pins.len(), 2);
+        assert!(!toggle_pinned_path(&mut pins, r"c:\NOTES\todo.txt"));
+        assert_eq!(pins, [r"D:\work\plan.md"]);
+    }
+
+    #[test]
+    fn search_history_keeps_distinct_recent_queries() {
+        let mut history = VecDeque::new();
+        assert!(push_search_history(&mut history, " invoice "));
+        assert!(!push_search_history(&mut history, "invoice"));
+        assert!(!push_search_history(&mut history, "/reindex"));
+        assert!(!push_search_history(&mut history, "   "));
+        assert!(push_search_history(&mut history, "notes"));
+        assert!(push_search_history(&mut history, "invoice"));
+        assert_eq!(history, ["invoice", "notes"]);
+
+        for index in 0..SEARCH_HISTORY_LIMIT {
+            push_search_history(&mut history, &format!("query {}", index));
+        }
+        assert_eq!(history.len(), SEARCH_HISTORY_LIMIT);
+        assert_eq!(history.front().map(String::as_str), Some("query 49"));
+    }
+
+    #[test]
+    fn clears_only_cache_files() {
+        let dir = env::temp_dir().join(format!("rustsearch-cache-{}", std::process::id()));
+        assert_eq!(clear_caches_in(&dir).unwrap(), 0);
+
+        let snapshots = dir.join("snapshots");
+        std::fs::create_dir_all(&snapshots).unwrap();
+        for name in ["C.bin", "scope-all-local-drives.bin", "scope-d_.tmp"] {
+            std::fs::write(snapshots.join(name), b"x").unwrap();
+        }
+        std::fs::write(dir.join(USN_CHECKPOINTS_FILE), b"x").unwrap();
+        std::fs::write(dir.join("settings.toml"), b"x").unwrap();
+
+        assert_eq!(clear_caches_in(&dir).unwrap(), 4);
+        assert!(dir.join("settings.toml").exists());
+        assert_eq!(std::fs::read_dir(&snapshots).unwrap().count(), 0);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn reads_uncompressed_snapshots() {
+        let scope = SearchScope::AllLocalDrives;
+        let items = synthetic_items();
+        let raw = bincode::serialize(&scope_snapshot(&scope, &items)).unwrap();
+        assert_eq!(
+            decode_scope_snapshot(&raw, &scope).unwrap().len(),
+            items.len()
+        );
+
+        let v5 = ScopeIndexSnapshotV3 {
+            version: 5,
+            scope: scope.label(),
+            folders: vec!["C:\\docs\\".to_string()],
+            items: vec![SnapshotItemV3 {
+                folder: 0,
+                name: "b.txt".to_string(),
+                modified_unix_secs: 7,
+                size: 1,
+                kind: SnapshotItemKind::File,
+            }],
+        };
+        let raw = bincode::serialize(&v5).unwrap();
+        let loaded = decode_scope_snapshot(&raw, &scope).unwrap();
+        assert_eq!(loaded[0].full_path(), "C:\\docs\\b.txt");
+        assert_eq!(loaded[0].attrs, 0);
+
+        let v6 = ScopeIndexSnapshotV6 {
+            version: 6,
+            scope: scope.label(),
+            folders: vec!["C:\\docs\\".to_string()],
+            items: vec![SnapshotItemV6 {
+                folder: 0,
+                name: "c.txt".to_string(),
+                modified_unix_secs: 7,
+                size: 1,
+                kind: SnapshotItemKind::File,
+                attrs: 0x20,
+            }],
+        };
+        let raw = bincode::serialize(&v6).unwrap();
+        let loaded = decode_scope_snapshot(&raw, &scope).unwrap();
+        assert_eq!(loaded[0].attrs, 0x20);
+        assert_eq!(loaded[0].file_id, 0);
+
+        let legacy = ScopeIndexSnapshot {
+            version: 2,
+            scope: scope.label(),
+            items: vec![SnapshotItem {
+                path: "C:\\a.txt".to_string(),
+                modified_unix_secs: 7,
+                size: 1,
+                kind: SnapshotItemKind::File,
+            }],
+        };
+        let raw = bincode::serialize(&legacy).unwrap();
+        let loaded = decode_scope_snapshot(&raw, &scope).unwrap();
+        assert_eq!(loaded[0].full_path(), "C:\\a.txt");
+    }
 }
@@ -0,0 +1,295 @@
+use std::collections::BTreeMap;
+
+use eframe::egui;
+use ratatui::style::Color;
+
+use crate::feed::ChangeKind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Rgb(u8, u8, u8);
+
+/// File name colors keyed by lowercase extension without the dot.
+pub(crate) type ColorMap = BTreeMap<String, [u8; 3]>;
+
+impl From<Rgb> for egui::Color32 {
+    fn from(Rgb(r, g, b): Rgb) -> Self {
+        egui::Color32::from_rgb(r, g, b)
+    }
+}
+
+impl From<Rgb> for Color {
+    fn from(Rgb(r, g, b): Rgb) -> Self {
+        Color::Rgb(r, g, b)
+    }
+}
+
+/// Colors shared by the GPU and soft renderers.
+#[derive(Debug)]
+pub(crate) struct Palette {
+    pub(crate) background: Rgb,
+    pub(crate) panel: Rgb,
+    pub(crate) track: Rgb,
+    pub(crate) popup: Rgb,
+    pub(crate) border: Rgb,
+    pub(crate) popup_border: Rgb,
+    pub(crate) text: Rgb,
+    pub(crate) label: Rgb,
+    pub(crate) status: Rgb,
+    pub(crate) dim: Rgb,
+    pub(crate) accent: Rgb,
+    pub(crate) info: Rgb,
+    pub(crate) danger: Rgb,
+    pub(crate) selection: Rgb,
+    pub(crate) indexing: Rgb,
+    pub(crate) searching: Rgb,
+    pub(crate) ok: Rgb,
+    pub(crate) warn: Rgb,
+    pub(crate) neutral: Rgb,
+    pub(crate) file_rs: Rgb,
+    pub(crate) file_ts: Rgb,
+    pub(crate) file_js: Rgb,
+    pub(crate) file_json: Rgb,
+    pub(crate) file_md: Rgb,
+    pub(crate) file_other: Rgb,
+}
+
+impl Palette {
+    pub(crate) fn change_color(&self, kind: ChangeKind) -> Rgb {
+        match kind {
+            ChangeKind::Created => self.ok,
+            ChangeKind::Modified => self.warn,
+            ChangeKind::Renamed => self.info,
+            ChangeKind::Deleted => self.danger,
+        }
+    }
+
+    /// Colors from `overrides` win over the theme's own in every theme.
+    pub(crate) fn file_color(&self, name: &str, overrides: &ColorMap) -> Rgb {
+        let lower = name.to_ascii_lowercase();
+        if let Some(&[r, g, b]) = lower
+            .rsplit_once('.')
+            .and_then(|(_, ext)| overrides.get(ext))
+        {
+            Rgb(r, g, b)
+        } else if lower.ends_with(".rs") {
+            self.file_rs
+        } else if lower.ends_with(".ts") || lower.ends_with(".tsx") {
+            self.file_ts
+        } else if lower.ends_with(".js") || lower.ends_with(".jsx") {
+            self.file_js
+        } else if lower.ends_with(".json") {
+            self.file_json
+        } else if lower.ends_with(".md") {
+            self.file_md
+        } else {
+            self.file_other
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Theme {
+    Default,
+    SolarizedDark,
+    Gruvbox,
+    HighContrast,
+}
+
+impl Theme {
+    pub(crate) const ALL: [Theme; 4] = [
+        Theme::Default,
+        Theme::SolarizedDark,
+        Theme::Gruvbox,
+        Theme::HighContrast,
+    ];
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::SolarizedDark => "solarized-dark",
+            Self::Gruvbox => "gruvbox",
+            Self::HighContrast => "high-contrast",
+        }
+    }
+
+    /// Accepts `solarized-dark`, `solarized_dark`, and `SolarizedDark` alike.
+    pub(crate) fn from_name(name: &str) -> Option<Theme> {
+        let key: String = name
+            .chars()
+            .filter(|ch| ch.is_ascii_alphanumeric())
+            .map(|ch| ch.to_ascii_lowercase())
+            .collect();
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.name().replace('-', "") == key)
+    }
+
+    pub(crate) fn next(self) -> Theme {
+        let index = Self::ALL
+            .iter()
+            .position(|theme| *theme == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub(crate) fn palette(self) -> &'static Palette {
+        match self {
+            Self::Default => &DEFAULT,
+            Self::SolarizedDark => &SOLARIZED_DARK,
+            Self::Gruvbox => &GRUVBOX,
+            Self::HighContrast => &HIGH_CONTRAST,
+        }
+    }
+}
+
+const DEFAULT: Palette = Palette {
+    background: Rgb(10, 14, 20),
+    panel: Rgb(15, 20, 28),
+    track: Rgb(24, 30, 40),
+    popup: Rgb(20, 26, 36),
+    border: Rgb(62, 72, 86),
+    popup_border: Rgb(78, 92, 112),
+    text: Rgb(236, 239, 244),
+    label: Rgb(155, 168, 185),
+    status: Rgb(160, 168, 178),
+    dim: Rgb(145, 150, 160),
+    accent: Rgb(255, 213, 128),
+    info: Rgb(130, 210, 255),
+    danger: Rgb(230, 80, 80),
+    selection: Rgb(58, 84, 122),
+    indexing: Rgb(178, 126, 28),
+    searching: Rgb(56, 122, 168),
+    ok: Rgb(117, 227, 140),
+    warn: Rgb(255, 184, 76),
+    neutral: Rgb(184, 184, 184),
+    file_rs: Rgb(255, 153, 85),
+    file_ts: Rgb(99, 179, 237),
+    file_js: Rgb(246, 224, 94),
+    file_json: Rgb(104, 211, 145),
+    file_md: Rgb(180, 178, 255),
+    file_other: Rgb(220, 220, 220),
+};
+
+const SOLARIZED_DARK: Palette = Palette {
+    background: Rgb(0, 43, 54),
+    panel: Rgb(7, 54, 66),
+    track: Rgb(0, 43, 54),
+    popup: Rgb(7, 54, 66),
+    border: Rgb(88, 110, 117),
+    popup_border: Rgb(101, 123, 131),
+    text: Rgb(238, 232, 213),
+    label: Rgb(131, 148, 150),
+    status: Rgb(147, 161, 161),
+    dim: Rgb(101, 123, 131),
+    accent: Rgb(181, 137, 0),
+    info: Rgb(38, 139, 210),
+    danger: Rgb(220, 50, 47),
+    selection: Rgb(7, 74, 92),
+    indexing: Rgb(203, 75, 22),
+    searching: Rgb(38, 139, 210),
+    ok: Rgb(133, 153, 0),
+    warn: Rgb(181, 137, 0),
+    neutral: Rgb(131, 148, 150),
+    file_rs: Rgb(203, 75, 22),
+    file_ts: Rgb(38, 139, 210),
+    file_js: Rgb(181, 137, 0),
+    file_json: Rgb(42, 161, 152),
+    file_md: Rgb(108, 113, 196),
+    file_other: Rgb(147, 161, 161),
+};
+
+const GRUVBOX: Palette = Palette {
+    background: Rgb(29, 32, 33),
+    panel: Rgb(40, 40, 40),
+    track: Rgb(60, 56, 54),
+    popup: Rgb(50, 48, 47),
+    border: Rgb(80, 73, 69),
+    popup_border: Rgb(102, 92, 84),
+    text: Rgb(235, 219, 178),
+    label: Rgb(168, 153, 132),
+    status: Rgb(189, 174, 147),
+    dim: Rgb(146, 131, 116),
+    accent: Rgb(250, 189, 47),
+    info: Rgb(131, 165, 152),
+    danger: Rgb(251, 73, 52),
+    selection: Rgb(80, 73, 69),
+    indexing: Rgb(215, 153, 33),
+    searching: Rgb(69, 133, 136),
+    ok: Rgb(184, 187, 38),
+    warn: Rgb(254, 128, 25),
+    neutral: Rgb(146, 131, 116),
+    file_rs: Rgb(254, 128, 25),
+    file_ts: Rgb(131, 165, 152),
+    file_js: Rgb(250, 189, 47),
+    file_json: Rgb(142, 192, 124),
+    file_md: Rgb(211, 134, 155),
+    file_other: Rgb(235, 219, 178),
+};
+
+const HIGH_CONTRAST: Palette = Palette {
+    background: Rgb(0, 0, 0),
+    panel: Rgb(0, 0, 0),
+    track: Rgb(40, 40, 40),
+    popup: Rgb(0, 0, 0),
+    border: Rgb(255, 255, 255),
+    popup_border: Rgb(255, 255, 255),
+    text: Rgb(255, 255, 255),
+    label: Rgb(255, 255, 255),
+    status: Rgb(255, 255, 255),
+    dim: Rgb(200, 200, 200),
+    accent: Rgb(255, 255, 0),
+    info: Rgb(0, 255, 255),
+    danger: Rgb(255, 64, 64),
+    selection: Rgb(0, 60, 200),
+    indexing: Rgb(255, 170, 0),
+    searching: Rgb(0, 140, 255),
+    ok: Rgb(0, 255, 0),
+    warn: Rgb(255, 170, 0),
+    neutral: Rgb(220, 220, 220),
+    file_rs: Rgb(255, 140, 0),
+    file_ts: Rgb(0, 200, 255),
+    file_js: Rgb(255, 255, 0),
+    file_json: Rgb(0, 255, 128),
+    file_md: Rgb(255, 128, 255),
+    file_other: Rgb(255, 255, 255),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_names_loosely_and_cycles() {
+        assert_eq!(Theme::from_name("Gruvbox"), Some(Theme::Gruvbox));
+        assert_eq!(
+            Theme::from_name("solarized_dark"),
+            Some(Theme::SolarizedDark)
+        );
+        assert_eq!(Theme::from_name("HighContrast"), Some(Theme::HighContrast));
+        assert_eq!(Theme::from_name("neon"), None);
+        assert_eq!(Theme::HighContrast.next(), Theme::Default);
+        for theme in Theme::ALL {
+            assert_eq!(Theme::from_name(theme.name()), Some(theme));
+        }
+    }
+
+    #[test]
+    fn configured_file_colors_override_the_theme() {
+        let overrides = ColorMap::from([
+            ("log".to_string(), [255, 140, 0]),
+            ("rs".to_string(), [1, 2, 3]),
+        ]);
+        let palette = Theme::Default.palette();
+        assert_eq!(
+            palette.file_color("Build.LOG", &overrides),
+            Rgb(255, 140, 0)
+        );
+        assert_eq!(palette.file_color("main.rs", &overrides), Rgb(1, 2, 3));
+        assert_eq!(palette.file_color("app.ts", &overrides), palette.file_ts);
+        assert_eq!(palette.file_color("log", &overrides), palette.file_other);
+        assert_eq!(
+            palette.file_color("main.rs", &ColorMap::new()),
+            palette.file_rs
+        );
+    }
+}
@@ -1,19 +1,22 @@
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Wrap};
+use ratatui::widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap};
 
-use crate::app_state::AppState;
-use crate::commands::{command_menu_items, format_latest_window};
-use crate::search::{file_name_from_path, file_type_color, truncate_middle};
-use crate::{
-    backend_status_color, format_bytes, state_status_color, SearchItemKind, FILE_PATH_MAX_CHARS,
+use crate::app_state::{AppState, ROW_ACTIONS};
+use crate::feed::format_feed_time;
+use crate::search::{highlight_terms, truncate_middle};
+use crate::settings::RowDensity;
+use crate::view_model::{
+    footer_fields, result_row, scroll_top_for, search_busy_label, status_line, FOOTER_KEYS,
 };
+use crate::{backend_status_color, state_status_color};
 
-pub(crate) fn draw(frame: &mut ratatui::Frame<'_>, app: &AppState) {
+pub(crate) fn draw(frame: &mut ratatui::Frame<'_>, app: &AppState, results_top: &mut usize) {
+    let palette = app.palette();
     let area = frame.area();
     frame.render_widget(
-        Block::default().style(Style::default().bg(Color::Rgb(10, 14, 20))),
+        Block::default().style(Style::default().bg(Color::from(palette.background))),
         area,
     );
 
@@ -38,13 +41,22 @@ pub(crate) fn draw(frame: &mut ratatui::Frame<'_>, app: &AppState) {
     if sections[1].height > 0 {
         draw_progress(frame, sections[1], app);
     }
-    draw_results(frame, sections[2], app);
+    draw_results(frame, sections[2], app, results_top);
     draw_status(frame, sections[3], app);
     draw_footer(frame, sections[4], app);
 
     if let Some(area) = commands_popup_area(sections[2], app) {
         draw_commands(frame, area, app);
     }
+    if let Some(area) = action_palette_area(sections[2], app) {
+        draw_action_palette(frame, area, app);
+    }
+
+    let hotkey_line = format!(
+        "Hotkey: {} toggles panel | Enter opens | Alt+Enter reveals | Ctrl+Enter opens folder",
+        app.settings.hotkey
+    );
+    let quick_hotkey_line = format!("Press {} to show or hide RustSearch", app.settings.hotkey);
 
     if app.show_quick_help_overlay {
         draw_overlay(
@@ -52,11 +64,12 @@ pub(crate) fn draw(frame: &mut ratatui::Frame<'_>, app: &AppState) {
             area,
             vec![
                 "Quick Start",
-                "Press ` to show or hide RustSearch",
+                &quick_hotkey_line,
                 "Type to search, Enter to open, Alt+Enter to reveal",
                 "Use / for commands: /all /entire /reindex /track /exit",
             ],
-            Color::Rgb(130, 210, 255),
+            Color::from(palette.info),
+            Color::from(palette.popup),
         );
     }
 
@@ -69,12 +82,13 @@ pub(crate) fn draw(frame: &mut ratatui::Frame<'_>, app: &AppState) {
                 "made by IvRogoz - 2026",
                 "Rendering: egui native GPU UI (fallback: /soft)",
                 "Indexing: NTFS/USN live when elevated, DIRWALK fallback otherwise",
-                "Hotkey: ` toggles panel | Enter opens | Alt+Enter reveals",
+                &hotkey_line,
                 "Commands: /all /entire /reindex /up /track /latest /fullscreen /fullheight",
                 "",
                 "Press any key to close",
             ],
-            Color::Rgb(130, 210, 255),
+            Color::from(palette.info),
+            Color::from(palette.popup),
         );
     }
 
@@ -93,26 +107,28 @@ pub(crate) fn draw(frame: &mut ratatui::Frame<'_>, app: &AppState) {
                 "Using DIRWALK fallback (SLOWER)",
                 "Type /up and press Enter to relaunch elevated",
             ],
-            Color::Rgb(230, 80, 80),
+            Color::from(palette.danger),
+            Color::from(palette.popup),
         );
     }
 }
 
 fn draw_prompt(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
-    let title = if app.indexing_in_progress {
-        "Indexing"
-    } else {
-        "Search"
+    let palette = app.palette();
+    let (title, text) = match &app.renaming {
+        Some((_, name)) => ("Rename", name.as_str()),
+        None if app.indexing_in_progress => ("Indexing", app.raw_query.as_str()),
+        None => ("Search", app.raw_query.as_str()),
     };
     let line = Line::from(vec![
         Span::styled(
             "> ",
             Style::default()
-                .fg(Color::Rgb(255, 213, 128))
+                .fg(Color::from(palette.accent))
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::raw(app.raw_query.as_str()),
-        Span::styled("█", Style::default().fg(Color::Rgb(130, 210, 255))),
+        Span::raw(text),
+        Span::styled("█", Style::default().fg(Color::from(palette.info))),
     ]);
     let paragraph = Paragraph::new(line)
         .block(Block::default().borders(Borders::ALL).title(title))
@@ -121,7 +137,8 @@ fn draw_prompt(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
 }
 
 fn draw_commands(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
-    let suggestions = command_menu_items(&app.raw_query, app.tracking_enabled);
+    let palette = app.palette();
+    let suggestions = app.command_suggestions();
     if suggestions.is_empty() {
         return;
     }
@@ -136,9 +153,9 @@ fn draw_commands(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
                 " "
             };
             let cmd_color = if item.command.eq_ignore_ascii_case("/exit") {
-                Color::Rgb(235, 72, 72)
+                Color::from(palette.danger)
             } else {
-                Color::Rgb(130, 210, 255)
+                Color::from(palette.info)
             };
             ListItem::new(Line::from(vec![
                 Span::raw(format!("{} ", marker)),
@@ -157,40 +174,99 @@ fn draw_commands(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
         Block::default()
             .borders(Borders::ALL)
             .title("Commands")
-            .style(Style::default().bg(Color::Rgb(20, 26, 36))),
+            .style(Style::default().bg(Color::from(palette.popup))),
     );
-    frame.render_widget(list, area);
+    let mut state = ListState::default();
+    state.select(Some(app.command_selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_action_palette(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
+    let palette = app.palette();
+    let Some(item) = app.items.get(app.selected) else {
+        return;
+    };
+
+    let items: Vec<ListItem<'_>> = ROW_ACTIONS
+        .iter()
+        .enumerate()
+        .map(|(index, (label, _))| {
+            let marker = if index == app.action_selected {
+                ">"
+            } else {
+                " "
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{} ", marker)),
+                Span::styled(*label, Style::default().fg(Color::from(palette.info))),
+            ]))
+        })
+        .collect();
+
+    frame.render_widget(Clear, area);
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Actions: {}", item.name))
+            .style(Style::default().bg(Color::from(palette.popup))),
+    );
+    let mut state = ListState::default();
+    state.select(Some(app.action_selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn action_palette_area(results_area: Rect, app: &AppState) -> Option<Rect> {
+    if !app.action_palette_open {
+        return None;
+    }
+
+    let width = results_area.width.saturating_sub(4).min(40);
+    let height = (ROW_ACTIONS.len() as u16 + 2).min(results_area.height.saturating_sub(1));
+    if width < 20 || height < 3 {
+        return None;
+    }
+
+    Some(Rect {
+        x: results_area.x + 2,
+        y: results_area.y,
+        width,
+        height,
+    })
 }
 
 fn draw_progress(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
+    let palette = app.palette();
     let (label, value, color) = if app.indexing_in_progress {
         (
             format!(
-                "{} {}",
+                "{} {} · {}",
                 index_phase_label(app.indexing_phase),
-                app.scope.label()
+                app.scope.label(),
+                app.indexing_detail()
             ),
             app.indexing_progress,
-            Color::Rgb(178, 126, 28),
+            Color::from(palette.indexing),
         )
     } else if app.active_search_query.is_some() {
         let total = app.all_items.len().max(1);
+        let ratio = (app.active_search_cursor as f32 / total as f32).clamp(0.0, 1.0);
         (
-            "search".to_string(),
-            (app.active_search_cursor as f32 / total as f32).clamp(0.0, 1.0),
-            Color::Rgb(56, 122, 168),
+            format!("search {:.0}%", ratio * 100.0),
+            ratio,
+            Color::from(palette.searching),
         )
     } else {
-        ("idle".to_string(), 1.0, Color::Rgb(117, 227, 140))
+        ("idle 100%".to_string(), 1.0, Color::from(palette.ok))
     };
 
     let gauge = Gauge::default()
         .block(Block::default().borders(Borders::ALL).title("Progress"))
         .gauge_style(Style::default().fg(color))
         .label(Span::styled(
-            format!(" {} {:.0}% ", label, value * 100.0),
+            format!(" {} ", label),
             Style::default()
-                .fg(Color::Rgb(245, 245, 245))
+                .fg(Color::from(palette.text))
                 .add_modifier(Modifier::BOLD),
         ))
         .ratio(value as f64);
@@ -200,7 +276,7 @@ fn draw_progress(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
 fn index_phase_label(phase: &str) -> &'static str {
     match phase {
         "snapshot" => "reading snapshot",
-        "index" => "reading index",
+        "index" => "building index",
         "write" => "finalizing index",
         "live" => "live updates",
         "done" => "ready",
@@ -213,7 +289,7 @@ fn commands_popup_area(results_area: Rect, app: &AppState) -> Option<Rect> {
         return None;
     }
 
-    let count = command_menu_items(&app.raw_query, app.tracking_enabled).len() as u16;
+    let count = app.command_suggestions().len() as u16;
     let width = results_area.width.saturating_sub(4).min(74);
     let height = (count + 2).min(results_area.height.saturating_sub(1));
     if width < 20 || height < 3 {
@@ -228,23 +304,36 @@ fn commands_popup_area(results_area: Rect, app: &AppState) -> Option<Rect> {
     })
 }
 
-fn draw_results(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
+fn draw_results(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState, top: &mut usize) {
+    if app.feed_mode {
+        draw_feed(frame, area, app);
+        return;
+    }
+    let palette = app.palette();
     let viewport_rows = area.height.saturating_sub(2) as usize;
     let total = app.items.len();
 
-    let start = if viewport_rows == 0 || total <= viewport_rows {
-        0
-    } else {
-        let max_start = total - viewport_rows;
-        let preferred = app.selected.saturating_sub(viewport_rows / 2);
-        preferred.min(max_start)
-    };
+    let start = scroll_top_for(
+        app.selected,
+        *top,
+        viewport_rows,
+        total,
+        app.settings.scroll_margin_rows(),
+    );
+    *top = start;
     let end = if viewport_rows == 0 {
         total
     } else {
         (start + viewport_rows).min(total)
     };
 
+    let terms = highlight_terms(&app.query);
+    // Compact rows drop the double gaps between columns and pad names less.
+    let (gap, name_width) = if app.settings.density == RowDensity::Compact {
+        (" ", 32usize)
+    } else {
+        ("  ", 42usize)
+    };
     let items: Vec<ListItem<'_>> = app
         .items
         .iter()
@@ -252,97 +341,155 @@ fn draw_results(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
         .skip(start)
         .take(end.saturating_sub(start))
         .map(|(index, item)| {
-            let selected = index == app.selected;
-            let marker = if selected { ">" } else { " " };
-            let name = file_name_from_path(item.path.as_ref());
-            let path = truncate_middle(item.path.as_ref(), FILE_PATH_MAX_CHARS);
-            let kind = if item.kind == SearchItemKind::Folder {
-                "[D]"
-            } else {
-                "   "
-            };
+            let view = result_row(app, index, item, &terms);
+            let selected = view.selected;
             let style = if selected {
                 Style::default()
-                    .bg(Color::Rgb(58, 84, 122))
-                    .fg(Color::Rgb(255, 213, 128))
+                    .bg(Color::from(palette.selection))
+                    .fg(Color::from(palette.accent))
             } else {
                 Style::default()
             };
-            ListItem::new(Line::from(vec![
-                Span::styled(format!("{} ", marker), style),
-                Span::styled(format!("{} ", kind), style.fg(Color::Rgb(130, 210, 255))),
-                Span::styled(format!("{:<42}", name), style.fg(file_type_color(name))),
-                Span::styled(path, style.fg(Color::Rgb(145, 150, 160))),
-            ]))
+            let name_style = style.fg(Color::from(view.color));
+            let highlight_style = if selected {
+                style.fg(Color::from(palette.text))
+            } else {
+                style.fg(Color::from(palette.accent))
+            }
+            .add_modifier(Modifier::BOLD);
+
+            let mut spans = vec![
+                Span::styled(format!("{} ", view.marker), style),
+                Span::styled(
+                    format!("{} ", view.kind),
+                    style.fg(Color::from(palette.info)),
+                ),
+                Span::styled(
+                    format!("{:>10}{}", view.size, gap),
+                    style.fg(Color::from(palette.dim)),
+                ),
+                Span::styled(
+                    format!("{:>16}{}", view.modified, gap),
+                    style.fg(Color::from(palette.dim)),
+                ),
+            ];
+            if let Some(kind) = view.change {
+                spans.push(Span::styled(format!("{:<9}", kind.label()), name_style));
+            }
+            for &(segment, matched) in &view.name_segments {
+                let segment_style = if matched { highlight_style } else { name_style };
+                spans.push(Span::styled(segment.to_string(), segment_style));
+            }
+            if view.is_folder {
+                spans.push(Span::styled("\\", name_style));
+            }
+            let padding = name_width.saturating_sub(view.name_len());
+            spans.push(Span::styled(" ".repeat(padding), name_style));
+            spans.push(Span::styled(view.path, style.fg(Color::from(palette.dim))));
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
-    let list = List::new(items).block(Block::default().borders(Borders::ALL).title("Results"));
+    let block = Block::default().borders(Borders::ALL).title("Results");
+    if let Some(message) = app.empty_state_message() {
+        let empty = Paragraph::new(message)
+            .style(Style::default().fg(Color::from(palette.dim)))
+            .alignment(Alignment::Center)
+            .block(block);
+        frame.render_widget(empty, area);
+        return;
+    }
+    let list = List::new(items).block(block);
     frame.render_widget(list, area);
 }
 
+/// `/feed`: live changes newest first, colored by what happened to the path.
+fn draw_feed(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
+    let palette = app.palette();
+    let feed = &app.change_feed;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Live feed  {}", feed.counts_label()));
+    if feed.is_empty() {
+        let waiting = Paragraph::new("Waiting for changes...")
+            .style(Style::default().fg(Color::from(palette.dim)))
+            .alignment(Alignment::Center)
+            .block(block);
+        frame.render_widget(waiting, area);
+        return;
+    }
+
+    let path_chars = app.path_max_chars();
+    let rows: Vec<ListItem<'_>> = feed
+        .newest()
+        .take(area.height.saturating_sub(2) as usize)
+        .map(|entry| {
+            let style = Style::default().fg(Color::from(palette.change_color(entry.kind)));
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{}  ", format_feed_time(entry.at_unix_secs)),
+                    Style::default().fg(Color::from(palette.dim)),
+                ),
+                Span::styled(format!("{:<8}  ", entry.kind.label()), style),
+                Span::styled(truncate_middle(&entry.path, path_chars), style),
+            ]))
+        })
+        .collect();
+    frame.render_widget(List::new(rows).block(block), area);
+}
+
 fn draw_status(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
-    let status = format!(
-        "{}SCOPE: {}{} | MEM: {} | CHG: +{} ~{} -{} | RESULTS: {} | LAST: {}",
-        if app.is_elevated {
-            ""
-        } else {
-            "[NOT ELEVATED] "
-        },
-        app.scope.label(),
-        if app.latest_only_mode {
-            format!(
-                " | FILTER: latest-{}",
-                format_latest_window(app.latest_window_secs)
-            )
-        } else {
-            String::new()
-        },
-        format_bytes(app.index_memory_bytes),
-        app.changes_added_since_index,
-        app.changes_updated_since_index,
-        app.changes_deleted_since_index,
-        app.items.len(),
-        app.last_action
-    );
-    let p = Paragraph::new(status).style(Style::default().fg(Color::Rgb(160, 168, 178)));
+    let palette = app.palette();
+    let mut spans = Vec::new();
+    if let Some(busy) = search_busy_label(app) {
+        spans.push(Span::styled(
+            format!("{} | ", busy),
+            Style::default().fg(Color::from(palette.searching)),
+        ));
+    }
+    spans.push(Span::styled(
+        status_line(app),
+        Style::default().fg(Color::from(palette.status)),
+    ));
+    let p = Paragraph::new(Line::from(spans));
     frame.render_widget(p, area);
 }
 
 fn draw_footer(frame: &mut ratatui::Frame<'_>, area: Rect, app: &AppState) {
+    let palette = app.palette();
+    let footer = footer_fields(app);
     let line = Line::from(vec![
-        Span::raw("Enter open | Alt+Enter reveal | Esc hide | IDX: "),
+        Span::raw(format!("{} | IDX: ", FOOTER_KEYS)),
         Span::styled(
-            app.index_backend.label(),
-            Style::default().fg(backend_status_color(app.index_backend)),
+            footer.backend,
+            Style::default().fg(backend_status_color(palette, app.index_backend)),
         ),
         Span::raw(" | LIVE: "),
         Span::styled(
-            if app.index_backend.live_updates() {
-                "on"
+            if footer.live { "on" } else { "off" },
+            Style::default().fg(if footer.live {
+                Color::from(palette.ok)
             } else {
-                "off"
-            },
-            Style::default().fg(if app.index_backend.live_updates() {
-                Color::Rgb(117, 227, 140)
-            } else {
-                Color::Rgb(184, 184, 184)
+                Color::from(palette.neutral)
             }),
         ),
         Span::raw(" | STATE: "),
         Span::styled(
-            if app.indexing_in_progress {
-                "indexing"
-            } else {
-                "idle"
-            },
-            Style::default().fg(state_status_color(app.indexing_in_progress)),
+            footer.state,
+            Style::default().fg(state_status_color(palette, app.indexing_in_progress)),
         ),
+        Span::raw(format!(" | JOB: {} | RENDER: soft", footer.job)),
     ]);
     frame.render_widget(Paragraph::new(line), area);
 }
 
-fn draw_overlay(frame: &mut ratatui::Frame<'_>, area: Rect, lines: Vec<&str>, color: Color) {
+fn draw_overlay(
+    frame: &mut ratatui::Frame<'_>,
+    area: Rect,
+    lines: Vec<&str>,
+    color: Color,
+    background: Color,
+) {
     let max_line = lines.iter().map(|line| line.len()).max().unwrap_or(10) as u16;
     let desired_width = max_line.saturating_add(6);
     let width = desired_width.min(area.width.saturating_sub(2)).max(24);
@@ -387,7 +534,7 @@ fn draw_overlay(frame: &mut ratatui::Frame<'_>, area: Rect, lines: Vec<&str>, co
 
     let p = Paragraph::new(rendered_lines)
         .block(Block::default().borders(Borders::ALL).title("Notice"))
-        .style(Style::default().bg(Color::Rgb(18, 22, 30)))
+        .style(Style::default().bg(background))
         .wrap(Wrap { trim: true });
     frame.render_widget(p, box_area);
 }
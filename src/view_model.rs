@@ -0,0 +1,212 @@
+use crate::app_state::AppState;
+use crate::commands::format_latest_window;
+use crate::feed::ChangeKind;
+use crate::search::{format_modified, highlight_segments, truncate_middle};
+use crate::theme::Rgb;
+use crate::{format_bytes, format_count, format_item_size, SearchItem, SearchItemKind};
+
+/// What both renderers show for one result, worked out once so they only
+/// differ in layout and styling.
+pub(crate) struct ResultRow<'a> {
+    pub(crate) selected: bool,
+    pub(crate) marked: bool,
+    /// Selection arrow followed by the pin or mark character.
+    pub(crate) marker: String,
+    pub(crate) kind: &'static str,
+    pub(crate) size: String,
+    pub(crate) modified: String,
+    /// Set in the `/latest` view.
+    pub(crate) change: Option<ChangeKind>,
+    /// The name split into matched and unmatched runs.
+    pub(crate) name_segments: Vec<(&'a str, bool)>,
+    pub(crate) is_folder: bool,
+    pub(crate) path: String,
+    /// Name color before selection is applied.
+    pub(crate) color: Rgb,
+}
+
+impl ResultRow<'_> {
+    /// Name length in characters, counting a folder's trailing `\`.
+    pub(crate) fn name_len(&self) -> usize {
+        let chars: usize = self
+            .name_segments
+            .iter()
+            .map(|(segment, _)| segment.chars().count())
+            .sum();
+        chars + usize::from(self.is_folder)
+    }
+}
+
+pub(crate) fn result_row<'a>(
+    app: &AppState,
+    row: usize,
+    item: &'a SearchItem,
+    terms: &[String],
+) -> ResultRow<'a> {
+    let palette = app.palette();
+    let selected = row == app.selected;
+    let is_folder = item.kind == SearchItemKind::Folder;
+    let name = item.name.as_ref();
+    let change = app.latest_change(item);
+    let color = if let Some(kind) = change {
+        palette.change_color(kind)
+    } else if app.is_missing_pin(item) {
+        palette.dim
+    } else if is_folder {
+        palette.info
+    } else {
+        palette.file_color(name, &app.settings.file_colors)
+    };
+    let ranges = app.name_highlights(name, terms);
+
+    ResultRow {
+        selected,
+        marked: app.is_marked(item),
+        marker: format!("{}{}", if selected { ">" } else { " " }, app.row_mark(item)),
+        kind: app.settings.density.kind_marker(is_folder),
+        size: format_item_size(item.size),
+        modified: format_modified(item.modified_unix_secs),
+        change,
+        name_segments: highlight_segments(name, &ranges),
+        is_folder,
+        path: truncate_middle(&item.full_path(), app.path_max_chars()),
+        color,
+    }
+}
+
+/// The line above the footer: scope, filters, sort, memory, changes, results
+/// and the last action.
+pub(crate) fn status_line(app: &AppState) -> String {
+    format!(
+        "{}SCOPE: {}{}{}{}{} | SORT: {} | MEM: {}{} | CHG: +{} ~{} -{} | RESULTS: {} | LAST: {}",
+        if app.is_elevated {
+            ""
+        } else {
+            "[NOT ELEVATED] "
+        },
+        app.scope.label(),
+        if app.latest_only_mode {
+            format!(
+                " | FILTER: latest-{}",
+                format_latest_window(app.latest_window_secs)
+            )
+        } else {
+            String::new()
+        },
+        if app.settings.search_full_paths {
+            " | MATCH: full path"
+        } else {
+            ""
+        },
+        if app.settings.index_hidden {
+            " | HIDDEN: on"
+        } else {
+            ""
+        },
+        app.date_filter
+            .map(|filter| format!(" | MODIFIED: {}", filter.label().to_lowercase()))
+            .unwrap_or_default(),
+        app.sort_label(),
+        format_bytes(app.index_memory_bytes),
+        match app.settings.memory_limit_bytes() {
+            Some(limit) if app.index_over_memory_limit() => {
+                format!(" (over {} limit)", format_bytes(limit))
+            }
+            _ => String::new(),
+        },
+        app.changes_added_since_index,
+        app.changes_updated_since_index,
+        app.changes_deleted_since_index,
+        app.results_label(),
+        app.last_action
+    )
+}
+
+/// First row to show so the selection keeps `margin` rows of context above
+/// and below it, moving from `top` only as far as that needs.
+pub(crate) fn scroll_top_for(
+    selected: usize,
+    top: usize,
+    viewport_rows: usize,
+    total: usize,
+    margin: usize,
+) -> usize {
+    if viewport_rows == 0 || total <= viewport_rows {
+        return 0;
+    }
+    let margin = margin.min((viewport_rows - 1) / 2);
+    let lowest = (selected + margin + 1).saturating_sub(viewport_rows);
+    let highest = selected.saturating_sub(margin).max(lowest);
+    top.clamp(lowest, highest).min(total - viewport_rows)
+}
+
+/// Spinner and scan count while a worker search runs, `None` once it is done.
+pub(crate) fn search_busy_label(app: &AppState) -> Option<String> {
+    app.active_search_job?;
+    Some(busy_label(
+        app.active_search_started.elapsed().as_millis(),
+        app.active_search_cursor,
+        app.all_items.len(),
+    ))
+}
+
+fn busy_label(elapsed_ms: u128, scanned: usize, total: usize) -> String {
+    const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+    format!(
+        "{} searching... {} / {} scanned",
+        SPINNER[(elapsed_ms / 120 % 4) as usize],
+        format_count(scanned.min(total)),
+        format_count(total)
+    )
+}
+
+pub(crate) const FOOTER_KEYS: &str = "Enter open | Alt+Enter reveal | Ctrl+Enter folder | Esc hide";
+
+/// Index fields of the footer.
+pub(crate) struct FooterFields {
+    pub(crate) backend: &'static str,
+    pub(crate) live: bool,
+    pub(crate) state: &'static str,
+    /// The index job results come from, `-` when none is running.
+    pub(crate) job: String,
+}
+
+pub(crate) fn footer_fields(app: &AppState) -> FooterFields {
+    FooterFields {
+        backend: app.index_backend.label(),
+        live: app.index_backend.live_updates(),
+        state: if app.indexing_in_progress {
+            "indexing"
+        } else {
+            "idle"
+        },
+        job: app
+            .active_index_job
+            .map_or_else(|| "-".to_string(), |job| format!("#{}", job)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busy_label_spins_and_counts_what_was_scanned() {
+        assert_eq!(
+            busy_label(0, 12_000, 2_500_000),
+            "| searching... 12,000 / 2,500,000 scanned"
+        );
+        assert_eq!(busy_label(130, 0, 10), "/ searching... 0 / 10 scanned");
+        assert_eq!(busy_label(480, 50, 10), "| searching... 10 / 10 scanned");
+    }
+
+    #[test]
+    fn scroll_top_keeps_margin_rows_around_selection() {
+        assert_eq!(scroll_top_for(5, 0, 10, 100, 3), 0);
+        assert_eq!(scroll_top_for(7, 0, 10, 100, 3), 1);
+        assert_eq!(scroll_top_for(20, 25, 10, 100, 3), 17);
+        assert_eq!(scroll_top_for(99, 0, 10, 100, 3), 90);
+        assert_eq!(scroll_top_for(4, 2, 5, 100, 9), 2);
+        assert_eq!(scroll_top_for(3, 7, 10, 8, 3), 0);
+    }
+}